@@ -0,0 +1,26 @@
+//! Times parsing a 256-cpu `/proc/cpuinfo`. Run with `cargo bench`.
+
+use std::{hint::black_box, time::Instant};
+
+use cpuinfo::{cpuinfo, Generator, Profile};
+
+const ITERATIONS: u32 = 200;
+
+fn main() {
+    let input = Generator::new(Profile::Zen4)
+        .sockets(2)
+        .cores_per_socket(64)
+        .threads_per_core(2)
+        .to_proc_string();
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        black_box(cpuinfo(black_box(&input)).unwrap());
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!(
+        "parse 256 cpus ({} KiB): {:?} per iteration",
+        input.len() / 1024,
+        elapsed
+    );
+}
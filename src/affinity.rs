@@ -0,0 +1,123 @@
+use std::{io, mem};
+
+use crate::{CpuInfo, Result};
+
+/// The logical cpus the calling thread may run on, in ascending order.
+pub fn allowed_cpus() -> Result<Vec<u32>> {
+    // SAFETY: cpu_set_t is a plain bitmask, valid when zeroed.
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    // SAFETY: the size passed matches the set being written to.
+    if unsafe { libc::sched_getaffinity(0, mem::size_of_val(&set), &mut set) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+
+    Ok((0..libc::CPU_SETSIZE as usize)
+        // SAFETY: the index is below CPU_SETSIZE.
+        .filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) })
+        .map(|cpu| cpu as u32)
+        .collect())
+}
+
+/// Restricts the calling thread, and threads it spawns afterwards, to
+/// `cpus`.
+pub fn set_affinity(cpus: &[u32]) -> Result<()> {
+    // SAFETY: see `allowed_cpus`.
+    let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+    for &cpu in cpus {
+        if cpu as usize >= libc::CPU_SETSIZE as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("cpu {} is beyond the largest supported cpu set", cpu),
+            )
+            .into());
+        }
+        // SAFETY: the index was checked above.
+        unsafe { libc::CPU_SET(cpu as usize, &mut set) };
+    }
+
+    // SAFETY: the size passed matches the set being read from.
+    if unsafe { libc::sched_setaffinity(0, mem::size_of_val(&set), &set) } != 0 {
+        return Err(io::Error::last_os_error().into());
+    }
+    Ok(())
+}
+
+impl CpuInfo<'_> {
+    /// Pins the calling thread to the allowed cpus of `socket`, returning
+    /// them.
+    pub fn pin_to_socket(&self, socket: u32) -> Result<Vec<u32>> {
+        let allowed = allowed_cpus()?;
+        let cpus = self
+            .topology()
+            .cpus
+            .iter()
+            .filter(|placement| placement.socket == socket)
+            .map(|placement| placement.processor)
+            .filter(|cpu| allowed.contains(cpu))
+            .collect();
+        pin(cpus, || format!("no allowed cpus on socket {}", socket))
+    }
+
+    /// Pins the calling thread to one allowed logical cpu per physical
+    /// core, leaving SMT siblings out, and returns them.
+    pub fn pin_to_physical_cores(&self) -> Result<Vec<u32>> {
+        let allowed = allowed_cpus()?;
+        let mut cores = Vec::new();
+        let mut cpus = Vec::new();
+        for placement in &self.topology().cpus {
+            let core = (placement.socket, placement.core);
+            if allowed.contains(&placement.processor) && !cores.contains(&core) {
+                cores.push(core);
+                cpus.push(placement.processor);
+            }
+        }
+        pin(cpus, || "no allowed cpus".to_string())
+    }
+}
+
+fn pin<F: FnOnce() -> String>(mut cpus: Vec<u32>, none: F) -> Result<Vec<u32>> {
+    if cpus.is_empty() {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, none()).into());
+    }
+    cpus.sort_unstable();
+    set_affinity(&cpus)?;
+    Ok(cpus)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_affinity() {
+        let allowed = allowed_cpus().unwrap();
+        assert!(!allowed.is_empty());
+
+        set_affinity(&allowed[..1]).unwrap();
+        assert_eq!(allowed_cpus().unwrap(), allowed[..1]);
+        set_affinity(&allowed).unwrap();
+        assert_eq!(allowed_cpus().unwrap(), allowed);
+
+        assert!(set_affinity(&[]).is_err());
+        assert!(set_affinity(&[u32::MAX]).is_err());
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    #[test]
+    fn pins_using_the_topology() {
+        let cpuinfo = CpuInfo::current().unwrap();
+        let cpuinfo = cpuinfo.as_cpuinfo();
+        let allowed = allowed_cpus().unwrap();
+
+        let socket = cpuinfo.topology().cpus[0].socket;
+        let pinned = cpuinfo.pin_to_socket(socket).unwrap();
+        assert!(pinned.iter().all(|cpu| allowed.contains(cpu)));
+        assert_eq!(allowed_cpus().unwrap(), pinned);
+
+        set_affinity(&allowed).unwrap();
+        let pinned = cpuinfo.pin_to_physical_cores().unwrap();
+        assert!(pinned.len() <= cpuinfo.topology().sockets * cpuinfo.topology().cores_per_socket);
+        assert!(cpuinfo.pin_to_socket(u32::MAX).is_err());
+        set_affinity(&allowed).unwrap();
+    }
+}
@@ -0,0 +1,315 @@
+use alloc::vec::Vec;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alphanumeric1, line_ending, not_line_ending, space0},
+    combinator::{map_res, opt},
+    number::complete::double,
+    sequence::{pair, preceded, terminated},
+    IResult,
+};
+
+use crate::{
+    blocks, field_value, finish, hexadecimal, list, model_name, processor, FieldSpec, Result,
+    Schema,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuInfo<'a> {
+    pub cpus: Vec<Cpu<'a>>,
+    pub machine: Machine<'a>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cpu<'a> {
+    pub processor: u32,
+    pub model_name: &'a str,
+    pub bogomips: f64,
+    pub features: Vec<&'a str>,
+    pub cpu_implementer: u32,
+    pub cpu_architecture: &'a str,
+    pub cpu_variant: u32,
+    pub cpu_part: u32,
+    pub cpu_revision: u32,
+}
+
+impl_eq_hash!(Cpu<'_> {
+    floats: [bogomips],
+    fields: [
+        processor, model_name, features, cpu_implementer, cpu_architecture, cpu_variant, cpu_part,
+        cpu_revision,
+    ],
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Machine<'a> {
+    pub hardware: &'a str,
+    pub revision: &'a str,
+    pub serial: &'a str,
+    pub model: Option<&'a str>,
+}
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("processor"),
+        FieldSpec::required("model name"),
+        FieldSpec::required("BogoMIPS"),
+        FieldSpec::required("Features"),
+        FieldSpec::required("CPU implementer"),
+        FieldSpec::required("CPU architecture"),
+        FieldSpec::required("CPU variant"),
+        FieldSpec::required("CPU part"),
+        FieldSpec::required("CPU revision"),
+        FieldSpec::required("Hardware"),
+        FieldSpec::required("Revision"),
+        FieldSpec::required("Serial"),
+        FieldSpec::optional("Model"),
+    ],
+    restart: 0,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    let (cpus, machine) = finish(
+        input,
+        pair(cpus, preceded(line_ending, machine)),
+        &SCHEMA,
+        false,
+    )?;
+    Ok(CpuInfo { cpus, machine })
+}
+
+fn bogomips(input: &str) -> IResult<&str, f64> {
+    field_value(tag("BogoMIPS"), double)(input)
+}
+
+fn features(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("Features"), terminated(list, space0))(input)
+}
+
+fn cpu_implementer(input: &str) -> IResult<&str, u32> {
+    field_value(tag("CPU implementer"), map_res(hexadecimal, u32::try_from))(input)
+}
+
+fn cpu_architecture(input: &str) -> IResult<&str, &str> {
+    field_value(tag("CPU architecture"), alphanumeric1)(input)
+}
+
+fn cpu_variant(input: &str) -> IResult<&str, u32> {
+    field_value(tag("CPU variant"), map_res(hexadecimal, u32::try_from))(input)
+}
+
+fn cpu_part(input: &str) -> IResult<&str, u32> {
+    field_value(tag("CPU part"), map_res(hexadecimal, u32::try_from))(input)
+}
+
+fn cpu_revision(input: &str) -> IResult<&str, u32> {
+    field_value(tag("CPU revision"), complete::u32)(input)
+}
+
+fn hardware(input: &str) -> IResult<&str, &str> {
+    field_value(tag("Hardware"), not_line_ending)(input)
+}
+
+fn revision(input: &str) -> IResult<&str, &str> {
+    field_value(tag("Revision"), alphanumeric1)(input)
+}
+
+fn serial(input: &str) -> IResult<&str, &str> {
+    field_value(tag("Serial"), alphanumeric1)(input)
+}
+
+fn model(input: &str) -> IResult<&str, &str> {
+    field_value(tag("Model"), not_line_ending)(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    let (input, processor) = processor(input)?;
+    let (input, model_name) = model_name(input)?;
+    let (input, bogomips) = bogomips(input)?;
+    let (input, features) = features(input)?;
+    let (input, cpu_implementer) = cpu_implementer(input)?;
+    let (input, cpu_architecture) = cpu_architecture(input)?;
+    let (input, cpu_variant) = cpu_variant(input)?;
+    let (input, cpu_part) = cpu_part(input)?;
+    let (input, cpu_revision) = cpu_revision(input)?;
+
+    let cpu = Cpu {
+        processor,
+        model_name,
+        bogomips,
+        features,
+        cpu_implementer,
+        cpu_architecture,
+        cpu_variant,
+        cpu_part,
+        cpu_revision,
+    };
+
+    Ok((input, cpu))
+}
+
+fn cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(cpu)(input)
+}
+
+fn machine(input: &str) -> IResult<&str, Machine<'_>> {
+    let (input, hardware) = hardware(input)?;
+    let (input, revision) = revision(input)?;
+    let (input, serial) = serial(input)?;
+    let (input, model) = opt(model)(input)?;
+
+    let machine = Machine {
+        hardware,
+        revision,
+        serial,
+        model,
+    };
+
+    Ok((input, machine))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn parses_bogomips() {
+        let result = bogomips(
+            "BogoMIPS	: 38.40
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 38.4);
+    }
+
+    #[test]
+    fn parses_features() {
+        let result = features(
+            "Features	: half thumb fastmult vfp edsp neon vfpv3 tls vfpv4 idiva idivt vfpd32 lpae evtstrm
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec![
+                "half", "thumb", "fastmult", "vfp", "edsp", "neon", "vfpv3", "tls", "vfpv4",
+                "idiva", "idivt", "vfpd32", "lpae", "evtstrm",
+            ]
+        )
+    }
+
+    #[test]
+    fn parses_features_with_trailing_space() {
+        let result = features("Features\t: half thumb fastmult \n");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, vec!["half", "thumb", "fastmult"])
+    }
+
+    #[test]
+    fn parses_cpu_implementer() {
+        let result = cpu_implementer(
+            "CPU implementer	: 0x41
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0x41);
+    }
+
+    #[test]
+    fn parses_cpu_architecture() {
+        let result = cpu_architecture(
+            "CPU architecture: 7
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "7");
+    }
+
+    #[test]
+    fn parses_cpu_variant() {
+        let result = cpu_variant(
+            "CPU variant	: 0x0
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0);
+    }
+
+    #[test]
+    fn parses_cpu_part() {
+        let result = cpu_part(
+            "CPU part	: 0xc07
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0xc07);
+    }
+
+    #[test]
+    fn parses_cpu_revision() {
+        let result = cpu_revision(
+            "CPU revision	: 3
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 3);
+    }
+
+    #[test]
+    fn parses_machine() {
+        let result = machine(
+            "Hardware	: BCM2835
+Revision	: a01041
+Serial		: 00000000b2c3d4e5
+Model		: Raspberry Pi 2 Model B Rev 1.1
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            Machine {
+                hardware: "BCM2835",
+                revision: "a01041",
+                serial: "00000000b2c3d4e5",
+                model: Some("Raspberry Pi 2 Model B Rev 1.1"),
+            }
+        )
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "processor	: 0
+model name	: ARMv7 Processor rev 3 (v7l)
+BogoMIPS	: 108.00
+Features	: half thumb fastmult vfp edsp neon vfpv3 tls vfpv4 idiva idivt vfpd32 lpae evtstrm crc32
+CPU implementer	: 0x41
+CPU architecture: 7
+CPU variant	: 0x0
+CPU part	: 0xd08
+CPU revision	: 3
+
+processor	: 1
+model name	: ARMv7 Processor rev 3 (v7l)
+BogoMIPS	: 108.00
+Features	: half thumb fastmult vfp edsp neon vfpv3 tls vfpv4 idiva idivt vfpd32 lpae evtstrm crc32
+CPU implementer	: 0x41
+CPU architecture: 7
+CPU variant	: 0x0
+CPU part	: 0xd08
+CPU revision	: 3
+
+Hardware	: BCM2711
+Revision	: c03111
+Serial		: 10000000a1b2c3d4
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.cpus[1].model_name, "ARMv7 Processor rev 3 (v7l)");
+        assert_eq!(cpuinfo.machine.hardware, "BCM2711");
+        assert!(cpuinfo.machine.model.is_none());
+    }
+}
@@ -0,0 +1,79 @@
+use crate::{CpuInfoError, CpuInfoOwned, Result};
+
+const MAGIC: &[u8; 4] = b"CPUI";
+/// Bumped whenever the encoding of [`CpuInfoOwned`] changes, since postcard
+/// output carries no field names to stay compatible with.
+const VERSION: u8 = 4;
+
+impl CpuInfoOwned {
+    /// Encodes the snapshot with postcard, behind a magic number and a
+    /// format version. This is typically under two thirds of the text size.
+    pub fn to_binary(&self) -> Result<Vec<u8>> {
+        let mut header = MAGIC.to_vec();
+        header.push(VERSION);
+        Ok(postcard::to_extend(self, header)?)
+    }
+
+    /// Reads a snapshot written by `to_binary`.
+    pub fn from_binary(input: &[u8]) -> Result<Self> {
+        let (&found, payload) = input
+            .strip_prefix(MAGIC)
+            .and_then(<[u8]>::split_first)
+            .ok_or(CpuInfoError::NotASnapshot)?;
+        if found != VERSION {
+            return Err(CpuInfoError::UnsupportedVersion {
+                found,
+                expected: VERSION,
+            });
+        }
+
+        let mut snapshot: Self = postcard::from_bytes(payload)?;
+        snapshot.intern();
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        Frequency,
+    };
+
+    #[test]
+    fn round_trips_through_binary() {
+        let input = format!("{}\n{}", SKYLAKE, EPYC);
+        let cpuinfo = cpuinfo(&input).unwrap();
+        let binary = cpuinfo.to_owned().to_binary().unwrap();
+        assert!(binary.starts_with(b"CPUI\x04"));
+        assert!(binary.len() < input.len() * 2 / 3);
+
+        let snapshot = CpuInfoOwned::from_binary(&binary).unwrap();
+        assert!(cpuinfo.diff(&snapshot.as_cpuinfo()).is_empty());
+        assert_eq!(snapshot.cpus[1].cpu_mhz, Frequency::from_mhz(2445.406));
+        assert_eq!(snapshot.to_binary().unwrap(), binary);
+    }
+
+    #[test]
+    fn rejects_foreign_input() {
+        let binary = cpuinfo(SKYLAKE).unwrap().to_owned().to_binary().unwrap();
+
+        assert!(matches!(
+            CpuInfoOwned::from_binary(SKYLAKE.as_bytes()),
+            Err(CpuInfoError::NotASnapshot)
+        ));
+        assert!(matches!(
+            CpuInfoOwned::from_binary(b"CPUI\x05"),
+            Err(CpuInfoError::UnsupportedVersion {
+                found: 5,
+                expected: 4
+            })
+        ));
+        assert!(matches!(
+            CpuInfoOwned::from_binary(&binary[..binary.len() - 4]),
+            Err(CpuInfoError::Postcard(_))
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{convert::Infallible, fmt, str::FromStr};
+
+use crate::Cpu;
+
+macro_rules! cpu_bugs {
+    ($($variant:ident => $name:literal [$($cve:literal),*],)*) => {
+        /// An entry from the `bugs` line, as named by the kernel.
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        #[cfg_attr(
+            feature = "serde",
+            derive(serde::Serialize, serde::Deserialize),
+            serde(into = "String", from = "String")
+        )]
+        pub enum CpuBug {
+            $($variant,)*
+            Unknown(String),
+        }
+
+        impl CpuBug {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $name,)*
+                    Self::Unknown(name) => name,
+                }
+            }
+
+            /// CVE identifiers assigned to the issue. Errata that never got
+            /// one, and unknown bugs, report none.
+            pub fn cves(&self) -> &'static [&'static str] {
+                match self {
+                    $(Self::$variant => &[$($cve),*],)*
+                    Self::Unknown(_) => &[],
+                }
+            }
+        }
+
+        impl FromStr for CpuBug {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(match s {
+                    $($name => Self::$variant,)*
+                    _ => Self::Unknown(s.to_string()),
+                })
+            }
+        }
+    };
+}
+
+cpu_bugs! {
+    F00f => "f00f" [],
+    Fdiv => "fdiv" [],
+    Coma => "coma" [],
+    TlbMmatch => "tlb_mmatch" [],
+    ApicC1e => "apic_c1e" [],
+    Bug11ap => "11ap" [],
+    FxsaveLeak => "fxsave_leak" [],
+    ClflushMonitor => "clflush_monitor" [],
+    SysretSsAttrs => "sysret_ss_attrs" [],
+    NullSeg => "null_seg" [],
+    SwapgsFence => "swapgs_fence" [],
+    Monitor => "monitor" [],
+    AmdE400 => "amd_e400" [],
+    Meltdown => "cpu_meltdown" ["CVE-2017-5754"],
+    SpectreV1 => "spectre_v1" ["CVE-2017-5753"],
+    SpectreV2 => "spectre_v2" ["CVE-2017-5715"],
+    SpectreV2User => "spectre_v2_user" ["CVE-2017-5715"],
+    SpecStoreBypass => "spec_store_bypass" ["CVE-2018-3639"],
+    L1tf => "l1tf" ["CVE-2018-3615", "CVE-2018-3620", "CVE-2018-3646"],
+    Mds => "mds" ["CVE-2018-12126", "CVE-2018-12127", "CVE-2018-12130", "CVE-2019-11091"],
+    MsbdsOnly => "msbds_only" ["CVE-2018-12126"],
+    Swapgs => "swapgs" ["CVE-2019-1125"],
+    Taa => "taa" ["CVE-2019-11135"],
+    ItlbMultihit => "itlb_multihit" ["CVE-2018-12207"],
+    Srbds => "srbds" ["CVE-2020-0543"],
+    MmioStaleData => "mmio_stale_data" ["CVE-2022-21123", "CVE-2022-21125", "CVE-2022-21166"],
+    MmioUnknown => "mmio_unknown" [],
+    Retbleed => "retbleed" ["CVE-2022-29900", "CVE-2022-29901"],
+    EibrsPbrsb => "eibrs_pbrsb" ["CVE-2022-26373"],
+    SmtRsb => "smt_rsb" ["CVE-2022-27672"],
+    Gds => "gds" ["CVE-2022-40982"],
+    TdxPwMce => "tdx_pw_mce" [],
+    Srso => "srso" ["CVE-2023-20569"],
+    Div0 => "div0" ["CVE-2023-20588"],
+    Rfds => "rfds" ["CVE-2023-28746"],
+    Bhi => "bhi" ["CVE-2022-0001", "CVE-2022-0002"],
+    IbpbNoRet => "ibpb_no_ret" ["CVE-2022-23824"],
+    Its => "its" ["CVE-2024-28956"],
+    ItsNativeOnly => "its_native_only" ["CVE-2024-28956"],
+    Tsa => "tsa" ["CVE-2024-36350", "CVE-2024-36357"],
+    OldMicrocode => "old_microcode" [],
+    Vmscape => "vmscape" ["CVE-2025-40300"],
+}
+
+impl From<&str> for CpuBug {
+    fn from(s: &str) -> Self {
+        let Ok(bug) = s.parse();
+        bug
+    }
+}
+
+impl From<String> for CpuBug {
+    fn from(s: String) -> Self {
+        CpuBug::from(s.as_str())
+    }
+}
+
+impl From<CpuBug> for String {
+    fn from(bug: CpuBug) -> Self {
+        match bug {
+            CpuBug::Unknown(name) => name,
+            bug => bug.as_str().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for CpuBug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Cpu<'_> {
+    /// The `bugs` line as [`CpuBug`]s, in the order the kernel printed them.
+    pub fn typed_bugs(&self) -> Vec<CpuBug> {
+        self.bugs.iter().map(|&bug| CpuBug::from(bug)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, fixtures::EPYC};
+    use alloc::vec;
+
+    #[test]
+    fn maps_bugs_to_cves() {
+        assert_eq!(CpuBug::from("cpu_meltdown"), CpuBug::Meltdown);
+        assert_eq!(CpuBug::Meltdown.cves(), ["CVE-2017-5754"]);
+        assert_eq!(CpuBug::Retbleed.cves().len(), 2);
+        assert!(CpuBug::NullSeg.cves().is_empty());
+    }
+
+    #[test]
+    fn keeps_unknown_bugs() {
+        let bug = CpuBug::from("new_bug");
+        assert_eq!(bug, CpuBug::Unknown("new_bug".to_string()));
+        assert!(bug.cves().is_empty());
+        assert_eq!(bug.to_string(), "new_bug");
+    }
+
+    #[test]
+    fn types_bugs_of_a_real_cpu() {
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        assert_eq!(
+            cpuinfo.cpus[0].typed_bugs(),
+            vec![
+                CpuBug::SysretSsAttrs,
+                CpuBug::NullSeg,
+                CpuBug::SpectreV1,
+                CpuBug::SpectreV2,
+                CpuBug::SpecStoreBypass,
+                CpuBug::Srso,
+            ]
+        );
+    }
+}
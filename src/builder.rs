@@ -0,0 +1,176 @@
+use crate::{AddressSizes, CacheSize, Cpu, CpuInfo, Frequency, SizeUnit, TlbSize};
+use alloc::vec::Vec;
+
+macro_rules! setters {
+    ($($field:ident: $type:ty),* $(,)?) => {
+        $(
+            pub fn $field(mut self, $field: $type) -> Self {
+                self.cpu.$field = $field;
+                self
+            }
+        )*
+    };
+}
+
+/// Builds a [`Cpu`], starting from a single-core cpu with no microcode,
+/// cache size or physical id, as a guest would report it.
+#[derive(Debug, Clone)]
+pub struct CpuBuilder<'a> {
+    cpu: Cpu<'a>,
+}
+
+impl Default for CpuBuilder<'_> {
+    fn default() -> Self {
+        Self {
+            cpu: Cpu {
+                vendor_id: "GenuineIntel",
+                stepping: Some(0),
+                siblings: 1,
+                cpu_cores: 1,
+                fpu: true,
+                fpu_exception: true,
+                wp: true,
+                clflush_size: 64,
+                cache_alignment: 64,
+                address_sizes: AddressSizes {
+                    physical_size: 39,
+                    virtual_size: 48,
+                },
+                ..Default::default()
+            },
+        }
+    }
+}
+
+impl<'a> CpuBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    setters! {
+        processor: u32,
+        vendor_id: &'a str,
+        cpu_family: u32,
+        model: u32,
+        model_name: &'a str,
+        stepping: Option<u32>,
+        microcode: Option<u64>,
+        cpu_mhz: Frequency,
+        cache_size: Option<CacheSize>,
+        cache_size_unit: SizeUnit,
+        physical_id: Option<u32>,
+        siblings: u32,
+        core_id: u32,
+        cpu_cores: u32,
+        apicid: u32,
+        initial_apicid: u32,
+        fpu: bool,
+        fpu_exception: bool,
+        cpuid_level: u32,
+        wp: bool,
+        flags: Vec<&'a str>,
+        vmx_flags: Vec<&'a str>,
+        svm_flags: Vec<&'a str>,
+        bugs: Vec<&'a str>,
+        bogomips: f64,
+        tlb_size: Option<TlbSize>,
+        clflush_size: u32,
+        cache_alignment: u32,
+        address_sizes: AddressSizes,
+        power_management: Vec<&'a str>,
+    }
+
+    pub fn flag(mut self, flag: &'a str) -> Self {
+        self.cpu.flags.push(flag);
+        self
+    }
+
+    pub fn build(self) -> Cpu<'a> {
+        self.cpu
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CpuInfoBuilder<'a> {
+    cpus: Vec<Cpu<'a>>,
+}
+
+impl<'a> CpuInfoBuilder<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cpu(mut self, cpu: Cpu<'a>) -> Self {
+        self.cpus.push(cpu);
+        self
+    }
+
+    /// Appends `count` copies of `template`, numbering their processor and
+    /// APIC ids after the cpus already added.
+    pub fn cpus(mut self, count: usize, template: &CpuBuilder<'a>) -> Self {
+        for _ in 0..count {
+            let id = self.cpus.len() as u32;
+            let cpu = template
+                .clone()
+                .processor(id)
+                .apicid(id)
+                .initial_apicid(id)
+                .build();
+            self.cpus.push(cpu);
+        }
+        self
+    }
+
+    pub fn build(self) -> CpuInfo<'a> {
+        CpuInfo { cpus: self.cpus }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn builds_cpu_with_defaults() {
+        let cpu = CpuBuilder::new()
+            .model_name("Test CPU")
+            .cpu_mhz(Frequency::from_mhz(3000.0))
+            .flag("fpu")
+            .flag("sse2")
+            .build();
+
+        assert_eq!(cpu.vendor_id, "GenuineIntel");
+        assert_eq!(cpu.model_name, "Test CPU");
+        assert_eq!(cpu.flags, vec!["fpu", "sse2"]);
+        assert_eq!(cpu.clflush_size, 64);
+        assert!(cpu.fpu);
+    }
+
+    #[test]
+    fn builds_cpuinfo() {
+        let template = CpuBuilder::new().vendor_id("AuthenticAMD").cpu_cores(4);
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpus(4, &template)
+            .cpu(CpuBuilder::new().processor(4).build())
+            .build();
+
+        assert_eq!(cpuinfo.cpus.len(), 5);
+        assert_eq!(cpuinfo.cpus[3].processor, 3);
+        assert_eq!(cpuinfo.cpus[3].apicid, 3);
+        assert_eq!(cpuinfo.cpus[3].vendor_id, "AuthenticAMD");
+        assert_eq!(cpuinfo.cpus[4].vendor_id, "GenuineIntel");
+    }
+
+    #[test]
+    fn builds_parseable_cpuinfo() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpus(2, &CpuBuilder::new().model_name("Test CPU"))
+            .build();
+        let text = cpuinfo.to_proc_string();
+
+        let parsed = crate::cpuinfo_guest(&text).unwrap();
+        assert_eq!(parsed.cpus.len(), 2);
+        assert_eq!(parsed.cpus[1].model_name, "Test CPU");
+    }
+}
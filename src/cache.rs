@@ -0,0 +1,202 @@
+use std::{io, path::Path};
+
+use nom::combinator::all_consuming;
+
+use crate::{
+    sysfs::{cpu_list, Sysfs},
+    tlb_page_size, CacheSize, Cpu, CpuInfo, Result,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CacheType {
+    Data,
+    Instruction,
+    Unified,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Cache {
+    pub level: u32,
+    pub cache_type: CacheType,
+    pub size: CacheSize,
+    /// Logical cpus sharing this cache, including the owner.
+    pub shared_cpus: Vec<u32>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheHierarchy {
+    pub caches: Vec<Cache>,
+}
+
+impl CacheHierarchy {
+    pub fn get(&self, level: u32, cache_type: CacheType) -> Option<&Cache> {
+        self.caches
+            .iter()
+            .find(|cache| cache.level == level && cache.cache_type == cache_type)
+    }
+
+    pub fn l1d(&self) -> Option<&Cache> {
+        self.get(1, CacheType::Data)
+    }
+
+    pub fn l1i(&self) -> Option<&Cache> {
+        self.get(1, CacheType::Instruction)
+    }
+
+    pub fn l2(&self) -> Option<&Cache> {
+        self.get(2, CacheType::Unified)
+    }
+
+    pub fn l3(&self) -> Option<&Cache> {
+        self.get(3, CacheType::Unified)
+    }
+}
+
+fn invalid(path: &Path, value: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid value `{}` in {}", value, path.display()),
+    )
+}
+
+fn read_cache(sysfs: &Sysfs, index: &Path) -> io::Result<Cache> {
+    let read = |attribute: &str| {
+        let path = index.join(attribute);
+        sysfs.read(&path).map(|value| (path, value))
+    };
+
+    let (path, level) = read("level")?;
+    let level = level.parse().map_err(|_| invalid(&path, &level))?;
+
+    let (path, cache_type) = read("type")?;
+    let cache_type = match cache_type.as_str() {
+        "Data" => CacheType::Data,
+        "Instruction" => CacheType::Instruction,
+        "Unified" => CacheType::Unified,
+        _ => return Err(invalid(&path, &cache_type)),
+    };
+
+    // Sizes are printed like TLB page sizes, e.g. `32K`.
+    let (path, size) = read("size")?;
+    let (_, size) = all_consuming(tlb_page_size)(&size).map_err(|_| invalid(&path, &size))?;
+
+    let (path, shared) = read("shared_cpu_list")?;
+    let shared_cpus = cpu_list(&shared).ok_or_else(|| invalid(&path, &shared))?;
+
+    Ok(Cache {
+        level,
+        cache_type,
+        size: CacheSize::from_bytes(size),
+        shared_cpus,
+    })
+}
+
+impl Cpu<'_> {
+    /// Reads the caches of this cpu from `cpuN/cache/index*`.
+    pub fn cache_hierarchy(&self, sysfs: &Sysfs) -> Result<CacheHierarchy> {
+        let mut caches = Vec::new();
+        for index in 0.. {
+            let path = Sysfs::cpu_path(self.processor, &format!("cache/index{}", index));
+            if !sysfs.root().join(&path).exists() {
+                break;
+            }
+            caches.push(read_cache(sysfs, &path)?);
+        }
+        Ok(CacheHierarchy { caches })
+    }
+}
+
+impl CpuInfo<'_> {
+    /// The cache hierarchy of every cpu, in the same order as `cpus`.
+    pub fn cache_hierarchies(&self, sysfs: &Sysfs) -> Result<Vec<CacheHierarchy>> {
+        self.cpus
+            .iter()
+            .map(|cpu| cpu.cache_hierarchy(sysfs))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::FakeSysfs, CpuBuilder, CpuInfoError};
+
+    fn index(cpu: u32, index: u32, attribute: &str) -> String {
+        format!(
+            "devices/system/cpu/cpu{}/cache/index{}/{}",
+            cpu, index, attribute
+        )
+    }
+
+    fn skylake(name: &str) -> FakeSysfs {
+        let caches = [
+            ("1", "Data", "32K", "0,4"),
+            ("1", "Instruction", "32K", "0,4"),
+            ("2", "Unified", "256K", "0,4"),
+            ("3", "Unified", "8192K", "0-7"),
+        ];
+        let files: Vec<(String, &str)> = caches
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (level, cache_type, size, shared))| {
+                let i = i as u32;
+                [
+                    (index(0, i, "level"), *level),
+                    (index(0, i, "type"), *cache_type),
+                    (index(0, i, "size"), *size),
+                    (index(0, i, "shared_cpu_list"), *shared),
+                ]
+            })
+            .collect();
+        let files: Vec<(&str, &str)> = files.iter().map(|(p, v)| (p.as_str(), *v)).collect();
+        FakeSysfs::new(name, &files)
+    }
+
+    #[test]
+    fn reads_cache_hierarchy() {
+        let fake = skylake("cache");
+        let cpu = CpuBuilder::new().processor(0).build();
+        let caches = cpu.cache_hierarchy(&Sysfs::new(&fake.root)).unwrap();
+
+        assert_eq!(caches.caches.len(), 4);
+        assert_eq!(
+            caches.l1d().map(|cache| cache.size),
+            Some(CacheSize::from_kib(32))
+        );
+        assert_eq!(caches.l1i().map(|cache| cache.level), Some(1));
+        assert_eq!(
+            caches.l2().map(|cache| cache.size),
+            Some(CacheSize::from_kib(256))
+        );
+        assert_eq!(
+            caches.l3(),
+            Some(&Cache {
+                level: 3,
+                cache_type: CacheType::Unified,
+                size: CacheSize::from_mib(8),
+                shared_cpus: (0..8).collect(),
+            })
+        );
+
+        let cpu = CpuBuilder::new().processor(1).build();
+        assert!(cpu
+            .cache_hierarchy(&Sysfs::new(&fake.root))
+            .unwrap()
+            .caches
+            .is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_attributes() {
+        let fake = skylake("cache-invalid");
+        std::fs::write(fake.root.join(index(0, 2, "type")), "Victim\n").unwrap();
+        let cpu = CpuBuilder::new().processor(0).build();
+        assert!(matches!(
+            cpu.cache_hierarchy(&Sysfs::new(&fake.root)),
+            Err(CpuInfoError::Io(e)) if e.kind() == io::ErrorKind::InvalidData
+        ));
+    }
+}
@@ -0,0 +1,242 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+use crate::{Cpu, CpuFlag};
+
+/// What a flag is about, for presenting long flag lists in groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum FlagCategory {
+    Simd,
+    Cryptography,
+    Virtualization,
+    Security,
+    PowerManagement,
+    /// Everything else, from basic x86 features to kernel bookkeeping.
+    Other,
+}
+
+const FLAGS: &[(FlagCategory, &[&str])] = &[
+    (
+        FlagCategory::Simd,
+        &[
+            "mmx",
+            "mmxext",
+            "3dnow",
+            "3dnowext",
+            "3dnowprefetch",
+            "sse",
+            "sse2",
+            "pni",
+            "ssse3",
+            "sse4_1",
+            "sse4_2",
+            "sse4a",
+            "misalignsse",
+            "avx",
+            "avx2",
+            "f16c",
+            "fma",
+            "fma4",
+            "xop",
+            "gfni",
+            "vaes",
+            "vpclmulqdq",
+            "avx_vnni",
+            "amx_bf16",
+            "amx_tile",
+            "amx_int8",
+        ],
+    ),
+    (
+        FlagCategory::Cryptography,
+        &[
+            "aes",
+            "pclmulqdq",
+            "sha_ni",
+            "rdrand",
+            "rdseed",
+            "padlock",
+            "sm3",
+            "sm4",
+        ],
+    ),
+    (
+        FlagCategory::Virtualization,
+        &[
+            "vmx",
+            "svm",
+            "hypervisor",
+            "ept",
+            "ept_ad",
+            "vpid",
+            "npt",
+            "flexpriority",
+            "tpr_shadow",
+            "vnmi",
+            "nrip_save",
+            "lbrv",
+            "tsc_scale",
+            "vmcb_clean",
+            "flushbyasid",
+            "decodeassists",
+            "pausefilter",
+            "pfthreshold",
+            "avic",
+            "v_vmsave_vmload",
+            "vgif",
+            "x2avic",
+            "vmmcall",
+            "unrestricted_guest",
+        ],
+    ),
+    (
+        FlagCategory::Security,
+        &[
+            "nx",
+            "smep",
+            "smap",
+            "umip",
+            "pku",
+            "ospke",
+            "md_clear",
+            "flush_l1d",
+            "ibrs",
+            "ibpb",
+            "stibp",
+            "ssbd",
+            "spec_ctrl",
+            "intel_stibp",
+            "arch_capabilities",
+            "ibt",
+            "shstk",
+            "user_shstk",
+            "sme",
+            "sev",
+            "sev_es",
+            "sev_snp",
+            "virt_ssbd",
+            "amd_ssbd",
+            "amd_ibrs",
+            "amd_ibpb",
+            "amd_stibp",
+            "ibrs_enhanced",
+            "sgx",
+            "tme",
+        ],
+    ),
+    (
+        FlagCategory::PowerManagement,
+        &[
+            "acpi",
+            "est",
+            "tm",
+            "tm2",
+            "eist",
+            "ida",
+            "arat",
+            "pln",
+            "pts",
+            "aperfmperf",
+            "hwp",
+            "hwp_notify",
+            "hwp_act_window",
+            "hwp_epp",
+            "hwp_pkg_req",
+            "cpb",
+            "hw_pstate",
+            "cppc",
+            "rapl",
+            "epb",
+            "monitor",
+            "mwaitx",
+        ],
+    ),
+];
+
+impl FlagCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Simd => "SIMD",
+            Self::Cryptography => "Cryptography",
+            Self::Virtualization => "Virtualization",
+            Self::Security => "Security",
+            Self::PowerManagement => "Power management",
+            Self::Other => "Other",
+        }
+    }
+
+    /// The category of `flag`, as the kernel names it.
+    pub fn of(flag: &str) -> Self {
+        if flag.starts_with("avx512") {
+            return Self::Simd;
+        }
+        FLAGS
+            .iter()
+            .find(|(_, flags)| flags.contains(&flag))
+            .map_or(Self::Other, |&(category, _)| category)
+    }
+}
+
+impl fmt::Display for FlagCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl CpuFlag {
+    pub fn category(&self) -> FlagCategory {
+        FlagCategory::of(self.as_str())
+    }
+}
+
+impl<'a> Cpu<'a> {
+    /// The `flags` line split by category, each in the order the kernel
+    /// printed them. Categories without flags are left out.
+    pub fn flags_by_category(&self) -> BTreeMap<FlagCategory, Vec<&'a str>> {
+        let mut groups: BTreeMap<FlagCategory, Vec<&'a str>> = BTreeMap::new();
+        for &flag in &self.flags {
+            groups.entry(FlagCategory::of(flag)).or_default().push(flag);
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, fixtures::EPYC};
+    use alloc::string::ToString;
+
+    #[test]
+    fn groups_flags_by_category() {
+        assert_eq!(FlagCategory::of("avx512_vnni"), FlagCategory::Simd);
+        assert_eq!(
+            CpuFlag::from("sha_ni").category(),
+            FlagCategory::Cryptography
+        );
+        assert_eq!(FlagCategory::of("constant_tsc"), FlagCategory::Other);
+
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let groups = cpuinfo[0].flags_by_category();
+        assert_eq!(
+            groups.keys().copied().collect::<Vec<_>>(),
+            [
+                FlagCategory::Simd,
+                FlagCategory::Cryptography,
+                FlagCategory::Virtualization,
+                FlagCategory::Security,
+                FlagCategory::PowerManagement,
+                FlagCategory::Other,
+            ]
+        );
+        assert_eq!(
+            groups[&FlagCategory::Cryptography],
+            ["pclmulqdq", "aes", "rdrand", "rdseed", "sha_ni"]
+        );
+        assert_eq!(groups.values().map(Vec::len).sum::<usize>(), 101);
+        assert_eq!(
+            FlagCategory::PowerManagement.to_string(),
+            "Power management"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::{
+    hash::{Hash, Hasher},
+    ops::Index,
+    slice,
+};
+
+use crate::{Cpu, CpuInfo};
+
+/// FNV-1a, to bucket cpus by their `Hash` without std's `HashSet`.
+struct Fnv(u64);
+
+impl Hasher for Fnv {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = (self.0 ^ u64::from(byte)).wrapping_mul(0x100_0000_01b3);
+        }
+    }
+}
+
+fn fingerprint(cpu: &Cpu) -> u64 {
+    let mut hasher = Fnv(0xcbf2_9ce4_8422_2325);
+    cpu.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl<'a> CpuInfo<'a> {
+    pub fn iter(&self) -> slice::Iter<'_, Cpu<'a>> {
+        self.cpus.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cpus.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cpus.is_empty()
+    }
+
+    /// Looks a cpu up by its `processor` number rather than its position,
+    /// which differs when cpus are offline.
+    pub fn get_by_processor(&self, processor: u32) -> Option<&Cpu<'a>> {
+        self.cpus.iter().find(|cpu| cpu.processor == processor)
+    }
+
+    /// Orders cpus by `processor` number. The sort is stable, so cpus
+    /// sharing a number keep their relative order.
+    pub fn sort_by_processor(&mut self) {
+        self.cpus.sort_by_key(|cpu| cpu.processor);
+    }
+
+    /// Orders cpus by socket, then core, so the threads of a core end up
+    /// next to each other. The sort is stable: threads of one core keep
+    /// their relative order, so calling [`sort_by_processor`] first lists
+    /// them by number. Cpus without a `physical id` come first.
+    ///
+    /// [`sort_by_processor`]: CpuInfo::sort_by_processor
+    pub fn sort_by_core(&mut self) {
+        self.cpus.sort_by_key(|cpu| (cpu.physical_id, cpu.core_id));
+    }
+
+    /// Drops cpus identical to an earlier one, e.g. a block a collector
+    /// wrote twice. Only exact copies go: two blocks with the same
+    /// `processor` but different clocks are both kept. The first copy stays
+    /// where it was and the order of the rest is unchanged. Cpus are only
+    /// compared in full when their hashes collide, so this stays cheap for
+    /// snapshots of thousands of cpus.
+    pub fn dedup_identical(&mut self) {
+        let mut seen: BTreeMap<u64, Vec<usize>> = BTreeMap::new();
+        let mut kept: Vec<Cpu<'a>> = Vec::with_capacity(self.cpus.len());
+        for cpu in self.cpus.drain(..) {
+            let same_hash = seen.entry(fingerprint(&cpu)).or_default();
+            if !same_hash.iter().any(|&index| kept[index] == cpu) {
+                same_hash.push(kept.len());
+                kept.push(cpu);
+            }
+        }
+        self.cpus = kept;
+    }
+}
+
+impl<'a> Index<usize> for CpuInfo<'a> {
+    type Output = Cpu<'a>;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.cpus[index]
+    }
+}
+
+impl<'a, 'b> IntoIterator for &'b CpuInfo<'a> {
+    type Item = &'b Cpu<'a>;
+    type IntoIter = slice::Iter<'b, Cpu<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cpus.iter()
+    }
+}
+
+impl<'a> IntoIterator for CpuInfo<'a> {
+    type Item = Cpu<'a>;
+    type IntoIter = alloc::vec::IntoIter<Cpu<'a>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cpus.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CpuBuilder, CpuInfoBuilder, Frequency};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn behaves_like_a_collection() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(CpuBuilder::new().processor(0).build())
+            .cpu(CpuBuilder::new().processor(2).build())
+            .build();
+
+        assert_eq!(cpuinfo.len(), 2);
+        assert!(!cpuinfo.is_empty());
+        assert_eq!(cpuinfo[1].processor, 2);
+        assert_eq!(
+            cpuinfo.get_by_processor(2).map(|cpu| cpu.processor),
+            Some(2)
+        );
+        assert!(cpuinfo.get_by_processor(1).is_none());
+
+        let processors: Vec<u32> = (&cpuinfo).into_iter().map(|cpu| cpu.processor).collect();
+        assert_eq!(processors, [0, 2]);
+        assert_eq!(cpuinfo.iter().count(), 2);
+        assert_eq!(cpuinfo.into_iter().last().map(|cpu| cpu.processor), Some(2));
+
+        assert!(CpuInfoBuilder::new().build().is_empty());
+    }
+
+    #[test]
+    fn sorts_stably() {
+        let cpu = |processor, physical_id, core_id| {
+            CpuBuilder::new()
+                .processor(processor)
+                .physical_id(Some(physical_id))
+                .core_id(core_id)
+                .build()
+        };
+        let mut cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu(5, 1, 0))
+            .cpu(cpu(2, 0, 1))
+            .cpu(cpu(4, 1, 0))
+            .cpu(cpu(0, 0, 0))
+            .cpu(cpu(3, 0, 1))
+            .cpu(cpu(1, 0, 0))
+            .build();
+        let processors = |cpuinfo: &crate::CpuInfo| -> Vec<u32> {
+            cpuinfo.iter().map(|cpu| cpu.processor).collect()
+        };
+
+        cpuinfo.sort_by_core();
+        assert_eq!(processors(&cpuinfo), [0, 1, 2, 3, 5, 4]);
+        cpuinfo.sort_by_processor();
+        assert_eq!(processors(&cpuinfo), [0, 1, 2, 3, 4, 5]);
+        cpuinfo.sort_by_core();
+        assert_eq!(processors(&cpuinfo), [0, 1, 2, 3, 4, 5]);
+
+        // Threads of core 0 listed second to first stay that way.
+        cpuinfo.cpus.swap(0, 1);
+        cpuinfo.sort_by_core();
+        assert_eq!(processors(&cpuinfo), [1, 0, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn drops_identical_blocks() {
+        let cpu = |processor, mhz| {
+            CpuBuilder::new()
+                .processor(processor)
+                .cpu_mhz(Frequency::from_mhz(mhz))
+                .build()
+        };
+        let mut cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu(1, 800.0))
+            .cpu(cpu(0, 800.0))
+            .cpu(cpu(1, 800.0))
+            .cpu(cpu(0, 3400.0))
+            .cpu(cpu(1, 800.0))
+            .build();
+
+        cpuinfo.dedup_identical();
+        let cpus: Vec<(u32, f64)> = cpuinfo
+            .iter()
+            .map(|cpu| (cpu.processor, cpu.cpu_mhz.as_mhz()))
+            .collect();
+        assert_eq!(cpus, [(1, 800.0), (0, 800.0), (0, 3400.0)]);
+    }
+}
@@ -0,0 +1,145 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{CpuInfo, PsAbiLevel};
+
+/// What a binary needs from the cpus it runs on.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FeatureSet {
+    flags: Vec<String>,
+    level: Option<PsAbiLevel>,
+}
+
+impl FeatureSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Requires `flag`, as the kernel names it, e.g. `avx512f`.
+    pub fn flag(mut self, flag: &str) -> Self {
+        self.flags.push(flag.to_string());
+        self
+    }
+
+    pub fn flags<'a, I: IntoIterator<Item = &'a str>>(self, flags: I) -> Self {
+        flags.into_iter().fold(self, Self::flag)
+    }
+
+    /// Requires at least `level`.
+    pub fn level(mut self, level: PsAbiLevel) -> Self {
+        self.level = Some(level);
+        self
+    }
+}
+
+/// The outcome of [`CpuInfo::supports`]. Its `Display` explains what is
+/// missing, for installers to print before refusing to continue.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Compatibility {
+    /// Required flags some cpu lacks, in the order they were required.
+    pub missing_flags: Vec<String>,
+    /// The required psABI level and the one found, when the latter is
+    /// lower or the cpus are not 64-bit.
+    pub missing_level: Option<(PsAbiLevel, Option<PsAbiLevel>)>,
+}
+
+impl Compatibility {
+    pub fn is_supported(&self) -> bool {
+        self.missing_flags.is_empty() && self.missing_level.is_none()
+    }
+}
+
+impl fmt::Display for Compatibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_supported() {
+            return f.write_str("all required cpu features are present");
+        }
+        if let Some((required, found)) = self.missing_level {
+            write!(f, "requires {}, ", required)?;
+            match found {
+                Some(found) => write!(f, "found {}", found)?,
+                None => f.write_str("found a 32-bit cpu")?,
+            }
+            if !self.missing_flags.is_empty() {
+                f.write_str("; ")?;
+            }
+        }
+        if !self.missing_flags.is_empty() {
+            write!(f, "missing {}", self.missing_flags.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Checks `requirements` against every cpu. Without cpus every required
+    /// flag and level is missing, though an empty `FeatureSet` is still
+    /// supported.
+    pub fn supports(&self, requirements: &FeatureSet) -> Compatibility {
+        let found = self.psabi_level();
+        Compatibility {
+            missing_flags: requirements
+                .flags
+                .iter()
+                .filter(|flag| !self.all_have_flag(flag))
+                .cloned()
+                .collect(),
+            missing_level: requirements
+                .level
+                .filter(|&level| found < Some(level))
+                .map(|level| (level, found)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        CpuInfoBuilder,
+    };
+
+    #[test]
+    fn checks_requirements() {
+        let epyc = cpuinfo(EPYC).unwrap();
+        let avx2 = FeatureSet::new()
+            .flags(["avx2", "fma"])
+            .level(PsAbiLevel::V3);
+        assert!(epyc.supports(&avx2).is_supported());
+        assert!(epyc.supports(&FeatureSet::new()).is_supported());
+
+        let avx512 = FeatureSet::new()
+            .flags(["avx2", "avx512f", "avx512bw"])
+            .level(PsAbiLevel::V4);
+        let compatibility = epyc.supports(&avx512);
+        assert!(!compatibility.is_supported());
+        assert_eq!(compatibility.missing_flags, ["avx512f", "avx512bw"]);
+        assert_eq!(
+            compatibility.to_string(),
+            "requires x86-64-v4, found x86-64-v3; missing avx512f, avx512bw"
+        );
+
+        let skylake = cpuinfo(SKYLAKE).unwrap();
+        assert_eq!(
+            skylake
+                .supports(&FeatureSet::new().level(PsAbiLevel::V1))
+                .to_string(),
+            "requires x86-64, found a 32-bit cpu"
+        );
+    }
+
+    #[test]
+    fn checks_empty_cpuinfo() {
+        let empty = CpuInfoBuilder::new().build();
+        assert!(empty.supports(&FeatureSet::new()).is_supported());
+
+        let compatibility = empty.supports(&FeatureSet::new().flag("sse2").level(PsAbiLevel::V1));
+        assert_eq!(compatibility.missing_flags, ["sse2"]);
+        assert_eq!(compatibility.missing_level, Some((PsAbiLevel::V1, None)));
+    }
+}
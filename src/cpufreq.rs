@@ -0,0 +1,257 @@
+use std::path::{Path, PathBuf};
+
+use crate::{sysfs::cpu_list, Cpu, CpuInfo, Frequency, Result, Sysfs};
+
+/// cpufreq state of one cpu. Each attribute is optional since drivers
+/// expose different subsets.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuFreq {
+    pub current: Option<Frequency>,
+    pub min: Option<Frequency>,
+    pub max: Option<Frequency>,
+    pub base: Option<Frequency>,
+    /// The hardware limit from `cpuinfo_max_freq`, i.e. the highest turbo
+    /// frequency, which drivers lower when turbo is turned off.
+    pub turbo: Option<Frequency>,
+    pub driver: Option<String>,
+    pub governor: Option<String>,
+    pub available_governors: Vec<String>,
+}
+
+/// A cpufreq policy: cpus whose frequency is scaled together, with the
+/// governor doing the scaling.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpufreqPolicy {
+    /// `N` of `cpufreq/policyN`.
+    pub id: u32,
+    /// `related_cpus`, online or not.
+    pub cpus: Vec<u32>,
+    pub driver: Option<String>,
+    pub governor: Option<String>,
+    pub available_governors: Vec<String>,
+}
+
+fn governors(sysfs: &Sysfs, path: PathBuf) -> Vec<String> {
+    sysfs
+        .read(path)
+        .map(|list| list.split_whitespace().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Reads `devices/system/cpu/cpufreq/policy*`, in policy order. Empty when
+/// there is no cpufreq driver.
+pub fn cpufreq_policies(sysfs: &Sysfs) -> Result<Vec<CpufreqPolicy>> {
+    let dir = Path::new("devices/system/cpu/cpufreq");
+    if !sysfs.root().join(dir).is_dir() {
+        return Ok(Vec::new());
+    }
+    sysfs
+        .numbered(dir, "policy")?
+        .into_iter()
+        .map(|id| {
+            let policy = dir.join(format!("policy{}", id));
+            Ok(CpufreqPolicy {
+                id,
+                cpus: sysfs
+                    .read(policy.join("related_cpus"))
+                    .ok()
+                    .and_then(|list| cpu_list(&list))
+                    .unwrap_or_default(),
+                driver: sysfs.read(policy.join("scaling_driver")).ok(),
+                governor: sysfs.read(policy.join("scaling_governor")).ok(),
+                available_governors: governors(sysfs, policy.join("scaling_available_governors")),
+            })
+        })
+        .collect()
+}
+
+impl CpuFreq {
+    /// Whether the cpu currently runs above its base clock, which is only
+    /// known when the driver reports one.
+    pub fn is_boosting(&self) -> bool {
+        matches!((self.current, self.base), (Some(current), Some(base)) if current > base)
+    }
+}
+
+/// Whether turbo, or boost on AMD, is enabled, from `intel_pstate/no_turbo`
+/// or else the cpufreq `boost` switch. `None` when neither driver offers a
+/// switch, as in most virtual machines.
+pub fn turbo_enabled(sysfs: &Sysfs) -> Option<bool> {
+    if let Some(no_turbo) = sysfs.parse::<u8, _>("devices/system/cpu/intel_pstate/no_turbo") {
+        return Some(no_turbo == 0);
+    }
+    sysfs
+        .parse::<u8, _>("devices/system/cpu/cpufreq/boost")
+        .map(|boost| boost != 0)
+}
+
+impl Cpu<'_> {
+    /// Reads `cpuN/cpufreq`, or returns `None` when the cpu has no cpufreq
+    /// policy, as is common in virtual machines.
+    pub fn cpufreq(&self, sysfs: &Sysfs) -> Option<CpuFreq> {
+        let path =
+            |attribute: &str| Sysfs::cpu_path(self.processor, &format!("cpufreq/{}", attribute));
+        if !sysfs.root().join(path("")).is_dir() {
+            return None;
+        }
+        let khz = |attribute: &str| sysfs.parse(path(attribute)).map(Frequency::from_khz);
+
+        Some(CpuFreq {
+            current: khz("scaling_cur_freq"),
+            min: khz("scaling_min_freq"),
+            max: khz("scaling_max_freq"),
+            base: khz("base_frequency"),
+            turbo: khz("cpuinfo_max_freq"),
+            driver: sysfs.read(path("scaling_driver")).ok(),
+            governor: sysfs.read(path("scaling_governor")).ok(),
+            available_governors: governors(sysfs, path("scaling_available_governors")),
+        })
+    }
+}
+
+impl CpuInfo<'_> {
+    /// cpufreq state of every cpu, in the same order as `cpus`.
+    pub fn cpufreq(&self, sysfs: &Sysfs) -> Vec<Option<CpuFreq>> {
+        self.cpus.iter().map(|cpu| cpu.cpufreq(sysfs)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::FakeSysfs, CpuBuilder, CpuInfoBuilder};
+
+    #[test]
+    fn reads_cpufreq() {
+        let fake = FakeSysfs::new(
+            "cpufreq",
+            &[
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_cur_freq",
+                    "3999876\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_min_freq",
+                    "800000\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_max_freq",
+                    "4200000\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/base_frequency",
+                    "4000000\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq",
+                    "4500000\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_driver",
+                    "intel_pstate\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_governor",
+                    "powersave\n",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_available_governors",
+                    "performance powersave\n",
+                ),
+                (
+                    "devices/system/cpu/cpu1/cpufreq/scaling_cur_freq",
+                    "800000\n",
+                ),
+            ],
+        );
+        let cpuinfo = CpuInfoBuilder::new().cpus(3, &CpuBuilder::new()).build();
+
+        let cpufreq = cpuinfo.cpufreq(&Sysfs::new(&fake.root));
+        assert_eq!(
+            cpufreq[0],
+            Some(CpuFreq {
+                current: Some(Frequency::from_khz(3_999_876)),
+                min: Some(Frequency::from_khz(800_000)),
+                max: Some(Frequency::from_khz(4_200_000)),
+                base: Some(Frequency::from_khz(4_000_000)),
+                turbo: Some(Frequency::from_khz(4_500_000)),
+                driver: Some("intel_pstate".to_string()),
+                governor: Some("powersave".to_string()),
+                available_governors: vec!["performance".to_string(), "powersave".to_string()],
+            })
+        );
+        assert!(!cpufreq[0].as_ref().unwrap().is_boosting());
+        assert_eq!(
+            cpufreq[1],
+            Some(CpuFreq {
+                current: Some(Frequency::from_khz(800_000)),
+                ..Default::default()
+            })
+        );
+        assert_eq!(cpufreq[2], None);
+    }
+
+    #[test]
+    fn reads_turbo_switches() {
+        let intel = FakeSysfs::new(
+            "turbo-intel",
+            &[
+                ("devices/system/cpu/intel_pstate/no_turbo", "1\n"),
+                ("devices/system/cpu/cpufreq/boost", "1\n"),
+            ],
+        );
+        assert_eq!(turbo_enabled(&Sysfs::new(&intel.root)), Some(false));
+
+        let amd = FakeSysfs::new("turbo-amd", &[("devices/system/cpu/cpufreq/boost", "1\n")]);
+        assert_eq!(turbo_enabled(&Sysfs::new(&amd.root)), Some(true));
+
+        let guest = FakeSysfs::new("turbo-guest", &[]);
+        assert_eq!(turbo_enabled(&Sysfs::new(&guest.root)), None);
+
+        let boosting = CpuFreq {
+            current: Some(Frequency::from_mhz(4400.0)),
+            base: Some(Frequency::from_mhz(4000.0)),
+            ..Default::default()
+        };
+        assert!(boosting.is_boosting());
+    }
+
+    #[test]
+    fn groups_cpus_by_policy() {
+        let fake = FakeSysfs::new(
+            "cpufreq-policies",
+            &[
+                ("devices/system/cpu/cpufreq/policy0/related_cpus", "0-1\n"),
+                (
+                    "devices/system/cpu/cpufreq/policy0/scaling_governor",
+                    "performance\n",
+                ),
+                ("devices/system/cpu/cpufreq/policy2/related_cpus", "2,3\n"),
+                (
+                    "devices/system/cpu/cpufreq/policy2/scaling_governor",
+                    "schedutil\n",
+                ),
+                (
+                    "devices/system/cpu/cpufreq/policy2/scaling_available_governors",
+                    "performance schedutil\n",
+                ),
+            ],
+        );
+        let policies = cpufreq_policies(&Sysfs::new(&fake.root)).unwrap();
+        assert_eq!(policies.len(), 2);
+        assert_eq!(policies[0].cpus, [0, 1]);
+        assert_eq!(policies[0].governor.as_deref(), Some("performance"));
+        assert_eq!(policies[1].id, 2);
+        assert_eq!(
+            policies[1].available_governors,
+            ["performance", "schedutil"]
+        );
+
+        let guest = FakeSysfs::new("cpufreq-no-policies", &[]);
+        assert!(cpufreq_policies(&Sysfs::new(&guest.root))
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -0,0 +1,424 @@
+#[cfg(target_arch = "x86")]
+use std::arch::x86::{__cpuid_count, CpuidResult};
+#[cfg(target_arch = "x86_64")]
+use std::arch::x86_64::{__cpuid_count, CpuidResult};
+use std::sync::Arc;
+
+use crate::{AddressSizes, CacheSize, CpuOwned, Frequency, SizeUnit};
+
+#[derive(Clone, Copy)]
+enum Register {
+    Leaf1Ecx,
+    Leaf1Edx,
+    Leaf7Ebx,
+    Leaf7Ecx,
+    Leaf7Edx,
+    Leaf7Sub1Eax,
+    LeafDSub1Eax,
+    ExtEcx,
+    ExtEdx,
+}
+
+use Register::*;
+
+/// CPUID feature bits, named as the kernel prints them. Bits the kernel
+/// hides from `flags` are left out.
+const FLAGS: &[(Register, u32, &str)] = &[
+    (Leaf1Edx, 0, "fpu"),
+    (Leaf1Edx, 1, "vme"),
+    (Leaf1Edx, 2, "de"),
+    (Leaf1Edx, 3, "pse"),
+    (Leaf1Edx, 4, "tsc"),
+    (Leaf1Edx, 5, "msr"),
+    (Leaf1Edx, 6, "pae"),
+    (Leaf1Edx, 7, "mce"),
+    (Leaf1Edx, 8, "cx8"),
+    (Leaf1Edx, 9, "apic"),
+    (Leaf1Edx, 11, "sep"),
+    (Leaf1Edx, 12, "mtrr"),
+    (Leaf1Edx, 13, "pge"),
+    (Leaf1Edx, 14, "mca"),
+    (Leaf1Edx, 15, "cmov"),
+    (Leaf1Edx, 16, "pat"),
+    (Leaf1Edx, 17, "pse36"),
+    (Leaf1Edx, 18, "pn"),
+    (Leaf1Edx, 19, "clflush"),
+    (Leaf1Edx, 21, "dts"),
+    (Leaf1Edx, 22, "acpi"),
+    (Leaf1Edx, 23, "mmx"),
+    (Leaf1Edx, 24, "fxsr"),
+    (Leaf1Edx, 25, "sse"),
+    (Leaf1Edx, 26, "sse2"),
+    (Leaf1Edx, 27, "ss"),
+    (Leaf1Edx, 28, "ht"),
+    (Leaf1Edx, 29, "tm"),
+    (Leaf1Edx, 30, "ia64"),
+    (Leaf1Edx, 31, "pbe"),
+    (ExtEdx, 11, "syscall"),
+    (ExtEdx, 19, "mp"),
+    (ExtEdx, 20, "nx"),
+    (ExtEdx, 22, "mmxext"),
+    (ExtEdx, 25, "fxsr_opt"),
+    (ExtEdx, 26, "pdpe1gb"),
+    (ExtEdx, 27, "rdtscp"),
+    (ExtEdx, 29, "lm"),
+    (ExtEdx, 30, "3dnowext"),
+    (ExtEdx, 31, "3dnow"),
+    (Leaf1Ecx, 0, "pni"),
+    (Leaf1Ecx, 1, "pclmulqdq"),
+    (Leaf1Ecx, 2, "dtes64"),
+    (Leaf1Ecx, 3, "monitor"),
+    (Leaf1Ecx, 4, "ds_cpl"),
+    (Leaf1Ecx, 5, "vmx"),
+    (Leaf1Ecx, 6, "smx"),
+    (Leaf1Ecx, 7, "est"),
+    (Leaf1Ecx, 8, "tm2"),
+    (Leaf1Ecx, 9, "ssse3"),
+    (Leaf1Ecx, 10, "cid"),
+    (Leaf1Ecx, 11, "sdbg"),
+    (Leaf1Ecx, 12, "fma"),
+    (Leaf1Ecx, 13, "cx16"),
+    (Leaf1Ecx, 14, "xtpr"),
+    (Leaf1Ecx, 15, "pdcm"),
+    (Leaf1Ecx, 17, "pcid"),
+    (Leaf1Ecx, 18, "dca"),
+    (Leaf1Ecx, 19, "sse4_1"),
+    (Leaf1Ecx, 20, "sse4_2"),
+    (Leaf1Ecx, 21, "x2apic"),
+    (Leaf1Ecx, 22, "movbe"),
+    (Leaf1Ecx, 23, "popcnt"),
+    (Leaf1Ecx, 24, "tsc_deadline_timer"),
+    (Leaf1Ecx, 25, "aes"),
+    (Leaf1Ecx, 26, "xsave"),
+    (Leaf1Ecx, 28, "avx"),
+    (Leaf1Ecx, 29, "f16c"),
+    (Leaf1Ecx, 30, "rdrand"),
+    (Leaf1Ecx, 31, "hypervisor"),
+    (ExtEcx, 0, "lahf_lm"),
+    (ExtEcx, 1, "cmp_legacy"),
+    (ExtEcx, 2, "svm"),
+    (ExtEcx, 3, "extapic"),
+    (ExtEcx, 4, "cr8_legacy"),
+    (ExtEcx, 5, "abm"),
+    (ExtEcx, 6, "sse4a"),
+    (ExtEcx, 7, "misalignsse"),
+    (ExtEcx, 8, "3dnowprefetch"),
+    (ExtEcx, 9, "osvw"),
+    (ExtEcx, 10, "ibs"),
+    (ExtEcx, 11, "xop"),
+    (ExtEcx, 12, "skinit"),
+    (ExtEcx, 13, "wdt"),
+    (ExtEcx, 15, "lwp"),
+    (ExtEcx, 16, "fma4"),
+    (ExtEcx, 17, "tce"),
+    (ExtEcx, 19, "nodeid_msr"),
+    (ExtEcx, 21, "tbm"),
+    (ExtEcx, 22, "topoext"),
+    (ExtEcx, 23, "perfctr_core"),
+    (ExtEcx, 24, "perfctr_nb"),
+    (ExtEcx, 26, "bpext"),
+    (ExtEcx, 27, "ptsc"),
+    (ExtEcx, 28, "perfctr_llc"),
+    (ExtEcx, 29, "mwaitx"),
+    (Leaf7Ebx, 0, "fsgsbase"),
+    (Leaf7Ebx, 1, "tsc_adjust"),
+    (Leaf7Ebx, 2, "sgx"),
+    (Leaf7Ebx, 3, "bmi1"),
+    (Leaf7Ebx, 4, "hle"),
+    (Leaf7Ebx, 5, "avx2"),
+    (Leaf7Ebx, 7, "smep"),
+    (Leaf7Ebx, 8, "bmi2"),
+    (Leaf7Ebx, 9, "erms"),
+    (Leaf7Ebx, 10, "invpcid"),
+    (Leaf7Ebx, 11, "rtm"),
+    (Leaf7Ebx, 12, "cqm"),
+    (Leaf7Ebx, 14, "mpx"),
+    (Leaf7Ebx, 15, "rdt_a"),
+    (Leaf7Ebx, 16, "avx512f"),
+    (Leaf7Ebx, 17, "avx512dq"),
+    (Leaf7Ebx, 18, "rdseed"),
+    (Leaf7Ebx, 19, "adx"),
+    (Leaf7Ebx, 20, "smap"),
+    (Leaf7Ebx, 21, "avx512ifma"),
+    (Leaf7Ebx, 23, "clflushopt"),
+    (Leaf7Ebx, 24, "clwb"),
+    (Leaf7Ebx, 25, "intel_pt"),
+    (Leaf7Ebx, 26, "avx512pf"),
+    (Leaf7Ebx, 27, "avx512er"),
+    (Leaf7Ebx, 28, "avx512cd"),
+    (Leaf7Ebx, 29, "sha_ni"),
+    (Leaf7Ebx, 30, "avx512bw"),
+    (Leaf7Ebx, 31, "avx512vl"),
+    (LeafDSub1Eax, 0, "xsaveopt"),
+    (LeafDSub1Eax, 1, "xsavec"),
+    (LeafDSub1Eax, 2, "xgetbv1"),
+    (LeafDSub1Eax, 3, "xsaves"),
+    (Leaf7Sub1Eax, 4, "avx_vnni"),
+    (Leaf7Sub1Eax, 5, "avx512_bf16"),
+    (Leaf7Ecx, 1, "avx512vbmi"),
+    (Leaf7Ecx, 2, "umip"),
+    (Leaf7Ecx, 3, "pku"),
+    (Leaf7Ecx, 4, "ospke"),
+    (Leaf7Ecx, 5, "waitpkg"),
+    (Leaf7Ecx, 6, "avx512_vbmi2"),
+    (Leaf7Ecx, 8, "gfni"),
+    (Leaf7Ecx, 9, "vaes"),
+    (Leaf7Ecx, 10, "vpclmulqdq"),
+    (Leaf7Ecx, 11, "avx512_vnni"),
+    (Leaf7Ecx, 12, "avx512_bitalg"),
+    (Leaf7Ecx, 13, "tme"),
+    (Leaf7Ecx, 14, "avx512_vpopcntdq"),
+    (Leaf7Ecx, 16, "la57"),
+    (Leaf7Ecx, 22, "rdpid"),
+    (Leaf7Ecx, 24, "bus_lock_detect"),
+    (Leaf7Ecx, 25, "cldemote"),
+    (Leaf7Ecx, 27, "movdiri"),
+    (Leaf7Ecx, 28, "movdir64b"),
+    (Leaf7Ecx, 29, "enqcmd"),
+    (Leaf7Ecx, 30, "sgx_lc"),
+    (Leaf7Edx, 2, "avx512_4vnniw"),
+    (Leaf7Edx, 3, "avx512_4fmaps"),
+    (Leaf7Edx, 4, "fsrm"),
+    (Leaf7Edx, 8, "avx512_vp2intersect"),
+    (Leaf7Edx, 10, "md_clear"),
+    (Leaf7Edx, 14, "serialize"),
+    (Leaf7Edx, 16, "tsxldtrk"),
+    (Leaf7Edx, 18, "pconfig"),
+    (Leaf7Edx, 19, "arch_lbr"),
+    (Leaf7Edx, 20, "ibt"),
+    (Leaf7Edx, 22, "amx_bf16"),
+    (Leaf7Edx, 23, "avx512_fp16"),
+    (Leaf7Edx, 24, "amx_tile"),
+    (Leaf7Edx, 25, "amx_int8"),
+    (Leaf7Edx, 28, "flush_l1d"),
+    (Leaf7Edx, 29, "arch_capabilities"),
+];
+
+#[allow(unused_unsafe)]
+fn cpuid(leaf: u32, subleaf: u32) -> CpuidResult {
+    // SAFETY: every x86 cpu able to run Rust's std supports CPUID, and
+    // leaves above the reported maximum return zeroes or a copy of the
+    // highest leaf, never fault.
+    unsafe { __cpuid_count(leaf, subleaf) }
+}
+
+fn bit(register: u32, bit: u32) -> u32 {
+    (register >> bit) & 1
+}
+
+/// Leaf `leaf`, or zeroes when the cpu does not support it.
+fn leaf(max: u32, leaf: u32, subleaf: u32) -> CpuidResult {
+    if leaf <= max {
+        cpuid(leaf, subleaf)
+    } else {
+        CpuidResult {
+            eax: 0,
+            ebx: 0,
+            ecx: 0,
+            edx: 0,
+        }
+    }
+}
+
+fn bytes(registers: &[u32]) -> String {
+    let bytes: Vec<u8> = registers.iter().flat_map(|r| r.to_le_bytes()).collect();
+    String::from_utf8_lossy(&bytes)
+        .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+        .to_string()
+}
+
+/// Size of the highest level cache described by leaf 4.
+fn intel_cache_size(max: u32) -> Option<CacheSize> {
+    (0..)
+        .map(|subleaf| leaf(max, 4, subleaf))
+        .take_while(|r| r.eax & 0x1f != 0)
+        .max_by_key(|r| (r.eax >> 5) & 0x7)
+        .map(|r| {
+            let ways = u64::from((r.ebx >> 22) + 1);
+            let partitions = u64::from(((r.ebx >> 12) & 0x3ff) + 1);
+            let line = u64::from((r.ebx & 0xfff) + 1);
+            let sets = u64::from(r.ecx) + 1;
+            CacheSize::from_bytes(ways * partitions * line * sets)
+        })
+}
+
+/// Threads per core and logical cpus per package from the extended
+/// topology leaf, 0x1F or the older 0xB, whose last level spans the package.
+fn intel_topology(max: u32) -> Option<(u32, u32)> {
+    let topology = [0x1f, 0xb]
+        .into_iter()
+        .find(|&topology| leaf(max, topology, 0).ebx & 0xffff != 0)?;
+    let levels: Vec<CpuidResult> = (0..)
+        .map(|subleaf| leaf(max, topology, subleaf))
+        .take_while(|r| (r.ecx >> 8) & 0xff != 0)
+        .collect();
+    let threads = levels
+        .iter()
+        .find(|r| (r.ecx >> 8) & 0xff == 1)
+        .map_or(1, |r| r.ebx & 0xffff);
+    let logical = levels.last()?.ebx & 0xffff;
+    Some((threads.max(1), logical))
+}
+
+/// Threads per core from leaf 0x8000_001E, present with `topoext`, and
+/// logical cpus per package from leaf 0x8000_0008.
+fn amd_topology(ext: impl Fn(u32) -> CpuidResult) -> (u32, u32) {
+    let threads = match bit(ext(0x8000_0001).ecx, 22) {
+        1 => ((ext(0x8000_001e).ebx >> 8) & 0xff) + 1,
+        _ => 1,
+    };
+    (threads, (ext(0x8000_0008).ecx & 0xff) + 1)
+}
+
+impl CpuOwned {
+    /// Describes the cpu the calling thread runs on by executing CPUID,
+    /// without needing procfs.
+    ///
+    /// Only what CPUID reports is filled in: `cpu_mhz`, `bogomips` and
+    /// `microcode` are zero or `None`, `bugs` is empty, and `flags` lacks
+    /// the ones the kernel synthesizes, such as `constant_tsc`. `siblings`
+    /// and `cpu_cores` describe the package as CPUID enumerates it, so they
+    /// count offline cpus the kernel leaves out.
+    pub fn from_cpuid() -> Self {
+        let basic = cpuid(0, 0);
+        let max = basic.eax;
+        let max_ext = cpuid(0x8000_0000, 0).eax;
+        let ext = |l: u32| leaf(max_ext, l, 0);
+
+        let vendor_id = bytes(&[basic.ebx, basic.edx, basic.ecx]);
+        let signature = leaf(max, 1, 0);
+        let ext_signature = ext(0x8000_0001);
+        let leaf7 = leaf(max, 7, 0);
+        let leaf7_1 = if leaf7.eax >= 1 {
+            leaf(max, 7, 1).eax
+        } else {
+            0
+        };
+
+        let registers = |register: Register| match register {
+            Leaf1Ecx => signature.ecx,
+            Leaf1Edx => signature.edx,
+            Leaf7Ebx => leaf7.ebx,
+            Leaf7Ecx => leaf7.ecx,
+            Leaf7Edx => leaf7.edx,
+            Leaf7Sub1Eax => leaf7_1,
+            LeafDSub1Eax => leaf(max, 0xd, 1).eax,
+            ExtEcx => ext_signature.ecx,
+            ExtEdx => ext_signature.edx,
+        };
+        let flags = FLAGS
+            .iter()
+            .filter(|(register, n, _)| bit(registers(*register), *n) == 1)
+            .map(|(_, _, name)| Arc::from(*name))
+            .collect();
+
+        let base_family = (signature.eax >> 8) & 0xf;
+        let base_model = (signature.eax >> 4) & 0xf;
+        let cpu_family = match base_family {
+            0xf => base_family + ((signature.eax >> 20) & 0xff),
+            _ => base_family,
+        };
+        let model = match base_family {
+            6 | 0xf => base_model + (((signature.eax >> 16) & 0xf) << 4),
+            _ => base_model,
+        };
+
+        let model_name = bytes(
+            &(0x8000_0002..=0x8000_0004)
+                .map(ext)
+                .flat_map(|r| [r.eax, r.ebx, r.ecx, r.edx])
+                .collect::<Vec<_>>(),
+        );
+
+        let is_amd = vendor_id == "AuthenticAMD" || vendor_id == "HygonGenuine";
+        let (cache_size, (threads, siblings)) = if is_amd {
+            let l2 = ext(0x8000_0006).ecx >> 16;
+            (
+                (l2 != 0).then(|| CacheSize::from_kib(l2.into())),
+                amd_topology(ext),
+            )
+        } else {
+            // Without a topology leaf, leaf 1 only bounds the logical cpus
+            // per package; assume they are all cores.
+            let fallback = match bit(signature.edx, 28) {
+                1 => (signature.ebx >> 16) & 0xff,
+                _ => 1,
+            };
+            (
+                intel_cache_size(max),
+                intel_topology(max).unwrap_or((1, fallback)),
+            )
+        };
+        let cpu_cores = (siblings / threads).max(1);
+
+        let clflush_size = ((signature.ebx >> 8) & 0xff) * 8;
+        let apicid = signature.ebx >> 24;
+        let address = ext(0x8000_0008).eax;
+
+        Self {
+            processor: 0,
+            vendor_id,
+            cpu_family,
+            model,
+            model_name: Arc::from(model_name),
+            stepping: Some(signature.eax & 0xf),
+            microcode: None,
+            cpu_mhz: Frequency::default(),
+            cache_size,
+            cache_size_unit: SizeUnit::Kilobytes,
+            physical_id: None,
+            siblings,
+            core_id: 0,
+            cpu_cores,
+            apicid,
+            initial_apicid: apicid,
+            fpu: bit(signature.edx, 0) == 1,
+            fpu_exception: bit(signature.edx, 0) == 1,
+            cpuid_level: max,
+            wp: true,
+            flags,
+            vmx_flags: Arc::from([]),
+            svm_flags: Arc::from([]),
+            bugs: Arc::from([]),
+            bogomips: 0.0,
+            tlb_size: None,
+            clflush_size,
+            cache_alignment: clflush_size,
+            address_sizes: AddressSizes {
+                physical_size: address & 0xff,
+                virtual_size: (address >> 8) & 0xff,
+            },
+            power_management: Arc::from([]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_cpuid() {
+        let cpu = CpuOwned::from_cpuid();
+        assert!(!cpu.vendor_id.is_empty());
+        assert!(cpu.cpu_family > 0);
+        assert!(cpu.flags.iter().any(|flag| &**flag == "fpu"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn matches_proc_cpuinfo() {
+        let cpu = CpuOwned::from_cpuid();
+        let current = crate::CpuInfo::current().unwrap();
+        let parsed = &current.cpus[0];
+
+        assert_eq!(cpu.vendor_id, parsed.vendor_id);
+        assert_eq!(cpu.cpu_family, parsed.cpu_family);
+        assert_eq!(cpu.model, parsed.model);
+        assert_eq!(cpu.stepping, parsed.stepping);
+        assert_eq!(cpu.model_name, parsed.model_name);
+        assert_eq!(cpu.cpuid_level, parsed.cpuid_level);
+        assert_eq!(cpu.siblings, parsed.siblings);
+        assert_eq!(cpu.cpu_cores, parsed.cpu_cores);
+    }
+}
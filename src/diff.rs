@@ -0,0 +1,216 @@
+use crate::{Cpu, CpuInfo, Frequency};
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldChange {
+    Microcode {
+        old: Option<u64>,
+        new: Option<u64>,
+    },
+    Mhz {
+        old: Frequency,
+        new: Frequency,
+    },
+    FlagsGained(Vec<String>),
+    FlagsLost(Vec<String>),
+    BugsGained(Vec<String>),
+    BugsLost(Vec<String>),
+    /// Any other field, with values as printed by `Debug`.
+    Other {
+        field: &'static str,
+        old: String,
+        new: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuDiff {
+    pub processor: u32,
+    pub changes: Vec<FieldChange>,
+}
+
+/// Differences between two snapshots, with cpus matched by `processor`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuInfoDiff {
+    pub added: Vec<u32>,
+    pub removed: Vec<u32>,
+    pub changed: Vec<CpuDiff>,
+}
+
+impl CpuInfoDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+fn missing(from: &[&str], to: &[&str]) -> Vec<String> {
+    from.iter()
+        .filter(|item| !to.contains(item))
+        .map(|item| item.to_string())
+        .collect()
+}
+
+macro_rules! other_fields {
+    ($old:ident, $new:ident, $changes:ident, $($field:ident: $name:literal),* $(,)?) => {
+        $(
+            if $old.$field != $new.$field {
+                $changes.push(FieldChange::Other {
+                    field: $name,
+                    old: format!("{:?}", $old.$field),
+                    new: format!("{:?}", $new.$field),
+                });
+            }
+        )*
+    };
+}
+
+impl Cpu<'_> {
+    pub fn diff(&self, other: &Cpu) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+
+        if self.microcode != other.microcode {
+            changes.push(FieldChange::Microcode {
+                old: self.microcode,
+                new: other.microcode,
+            });
+        }
+        if self.cpu_mhz != other.cpu_mhz {
+            changes.push(FieldChange::Mhz {
+                old: self.cpu_mhz,
+                new: other.cpu_mhz,
+            });
+        }
+
+        let gained = missing(&other.flags, &self.flags);
+        if !gained.is_empty() {
+            changes.push(FieldChange::FlagsGained(gained));
+        }
+        let lost = missing(&self.flags, &other.flags);
+        if !lost.is_empty() {
+            changes.push(FieldChange::FlagsLost(lost));
+        }
+        let gained = missing(&other.bugs, &self.bugs);
+        if !gained.is_empty() {
+            changes.push(FieldChange::BugsGained(gained));
+        }
+        let lost = missing(&self.bugs, &other.bugs);
+        if !lost.is_empty() {
+            changes.push(FieldChange::BugsLost(lost));
+        }
+
+        other_fields!(self, other, changes,
+            vendor_id: "vendor_id",
+            cpu_family: "cpu family",
+            model: "model",
+            model_name: "model name",
+            stepping: "stepping",
+            cache_size: "cache size",
+            physical_id: "physical id",
+            siblings: "siblings",
+            core_id: "core id",
+            cpu_cores: "cpu cores",
+            apicid: "apicid",
+            initial_apicid: "initial apicid",
+            cpuid_level: "cpuid level",
+            address_sizes: "address sizes",
+        );
+
+        changes
+    }
+}
+
+impl CpuInfo<'_> {
+    pub fn diff(&self, other: &CpuInfo) -> CpuInfoDiff {
+        let find = |cpus: &[Cpu], processor| cpus.iter().any(|cpu| cpu.processor == processor);
+
+        CpuInfoDiff {
+            added: other
+                .cpus
+                .iter()
+                .map(|cpu| cpu.processor)
+                .filter(|&processor| !find(&self.cpus, processor))
+                .collect(),
+            removed: self
+                .cpus
+                .iter()
+                .map(|cpu| cpu.processor)
+                .filter(|&processor| !find(&other.cpus, processor))
+                .collect(),
+            changed: self
+                .cpus
+                .iter()
+                .filter_map(|old| {
+                    let new = other
+                        .cpus
+                        .iter()
+                        .find(|cpu| cpu.processor == old.processor)?;
+                    let changes = old.diff(new);
+                    (!changes.is_empty()).then_some(CpuDiff {
+                        processor: old.processor,
+                        changes,
+                    })
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, fixtures::SKYLAKE};
+    use alloc::vec;
+
+    #[test]
+    fn reports_no_changes() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        assert!(cpuinfo.diff(&cpuinfo.clone()).is_empty());
+    }
+
+    #[test]
+    fn reports_field_changes() {
+        let old = cpuinfo(SKYLAKE).unwrap();
+        let input = SKYLAKE
+            .replace("0xf0", "0xf4")
+            .replace("4000.000", "800.000")
+            .replace("fpu vme", "fpu md_clear")
+            .replace("cpu_meltdown", "");
+        let new = cpuinfo(&input).unwrap();
+
+        let diff = old.diff(&new);
+        assert!(diff.added.is_empty() && diff.removed.is_empty());
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(
+            diff.changed[0].changes,
+            [
+                FieldChange::Microcode {
+                    old: Some(0xf0),
+                    new: Some(0xf4),
+                },
+                FieldChange::Mhz {
+                    old: Frequency::from_mhz(4000.0),
+                    new: Frequency::from_mhz(800.0),
+                },
+                FieldChange::FlagsGained(vec!["md_clear".to_string()]),
+                FieldChange::FlagsLost(vec!["vme".to_string()]),
+                FieldChange::BugsLost(vec!["cpu_meltdown".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_hotplug() {
+        let one = cpuinfo(SKYLAKE).unwrap();
+        let second = SKYLAKE.replace("processor	: 0", "processor	: 1");
+        let input = format!("{}\n{}", SKYLAKE, second);
+        let two = cpuinfo(&input).unwrap();
+
+        assert_eq!(one.diff(&two).added, [1]);
+        assert_eq!(two.diff(&one).removed, [1]);
+        assert!(one.diff(&two).changed.is_empty());
+    }
+}
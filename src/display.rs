@@ -0,0 +1,229 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{Cpu, CpuInfo};
+
+const WIDTH: usize = 16;
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const RESET: &str = "\x1b[0m";
+
+fn row(f: &mut fmt::Formatter<'_>, label: &str, value: impl fmt::Display) -> fmt::Result {
+    writeln!(f, "{:<WIDTH$}{}", format!("{}:", label), value)
+}
+
+fn paint(text: String, color: &str, enabled: bool) -> String {
+    if enabled && !text.is_empty() {
+        format!("{}{}{}", color, text, RESET)
+    } else {
+        text
+    }
+}
+
+fn optional<T: fmt::Display>(value: Option<T>) -> String {
+    value.map_or_else(|| "unknown".to_string(), |v| v.to_string())
+}
+
+fn write_cpu(f: &mut fmt::Formatter<'_>, cpu: &Cpu, color: bool) -> fmt::Result {
+    row(f, "Processor", cpu.processor)?;
+    row(f, "Vendor", cpu.vendor_id)?;
+    row(f, "Model name", cpu.model_name)?;
+    row(
+        f,
+        "Family/model",
+        format!(
+            "{}/{}, stepping {}",
+            cpu.cpu_family,
+            cpu.model,
+            optional(cpu.stepping)
+        ),
+    )?;
+    row(
+        f,
+        "Microcode",
+        optional(cpu.microcode.map(|m| format!("{:#x}", m))),
+    )?;
+    row(f, "Frequency", format!("{:.3} MHz", cpu.cpu_mhz.as_mhz()))?;
+    row(f, "Cache size", optional(cpu.cache_size))?;
+    row(
+        f,
+        "Topology",
+        format!(
+            "package {}, core {}, {} cores, {} siblings",
+            optional(cpu.physical_id),
+            cpu.core_id,
+            cpu.cpu_cores,
+            cpu.siblings
+        ),
+    )?;
+    row(f, "APIC id", cpu.apicid)?;
+    row(
+        f,
+        "Address sizes",
+        format!(
+            "{} bits physical, {} bits virtual",
+            cpu.address_sizes.physical_size, cpu.address_sizes.virtual_size
+        ),
+    )?;
+    row(f, "BogoMIPS", format!("{:.2}", cpu.bogomips))?;
+    row(f, "Flags", paint(cpu.flags.join(" "), GREEN, color))?;
+    row(f, "Bugs", paint(cpu.bugs.join(" "), RED, color))
+}
+
+fn write_cpuinfo(f: &mut fmt::Formatter<'_>, cpuinfo: &CpuInfo, color: bool) -> fmt::Result {
+    for (index, cpu) in cpuinfo.cpus.iter().enumerate() {
+        if index > 0 {
+            writeln!(f)?;
+        }
+        write_cpu(f, cpu, color)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for Cpu<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_cpu(f, self, false)
+    }
+}
+
+impl fmt::Display for CpuInfo<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_cpuinfo(f, self, false)
+    }
+}
+
+/// Displays the wrapped value with ANSI colors, flags in green and bugs in
+/// red. Deciding whether the output goes to a terminal is up to the caller.
+pub struct Colored<'a, T>(&'a T);
+
+impl fmt::Display for Colored<'_, Cpu<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_cpu(f, self.0, true)
+    }
+}
+
+impl fmt::Display for Colored<'_, CpuInfo<'_>> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_cpuinfo(f, self.0, true)
+    }
+}
+
+impl Cpu<'_> {
+    /// Displays like `to_string()`, with ANSI colors.
+    pub fn colored(&self) -> Colored<'_, Self> {
+        Colored(self)
+    }
+
+    /// Describes the processor on a single line, e.g.
+    /// `cpu0: Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz (GenuineIntel 6/94/3) @ 4000 MHz`.
+    pub fn summary(&self) -> String {
+        format!(
+            "cpu{}: {} ({} {}/{}/{}) @ {:.0} MHz",
+            self.processor,
+            self.model_name,
+            self.vendor_id,
+            self.cpu_family,
+            self.model,
+            optional(self.stepping),
+            self.cpu_mhz.as_mhz()
+        )
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Displays like `to_string()`, with ANSI colors.
+    pub fn colored(&self) -> Colored<'_, Self> {
+        Colored(self)
+    }
+
+    /// Describes the whole system on a single line, e.g.
+    /// `8 x Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz`.
+    pub fn summary(&self) -> String {
+        let mut models: Vec<(&str, usize)> = Vec::new();
+        for cpu in &self.cpus {
+            match models.iter_mut().find(|(name, _)| *name == cpu.model_name) {
+                Some((_, count)) => *count += 1,
+                None => models.push((cpu.model_name, 1)),
+            }
+        }
+
+        if models.is_empty() {
+            return "no cpus".to_string();
+        }
+
+        models
+            .iter()
+            .map(|(name, count)| format!("{} x {}", count, name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cpuinfo, fixtures::SKYLAKE};
+    use alloc::{format, string::ToString};
+
+    #[test]
+    fn displays_cpu() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        assert_eq!(
+            cpuinfo.cpus[0].to_string(),
+            "Processor:      0
+Vendor:         GenuineIntel
+Model name:     Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz
+Family/model:   6/94, stepping 3
+Microcode:      0xf0
+Frequency:      4000.000 MHz
+Cache size:     8 MiB
+Topology:       package 0, core 0, 4 cores, 8 siblings
+APIC id:        0
+Address sizes:  39 bits physical, 48 bits virtual
+BogoMIPS:       8003.30
+Flags:          fpu vme de pse
+Bugs:           cpu_meltdown
+"
+        );
+    }
+
+    #[test]
+    fn summarizes() {
+        let input = format!(
+            "{}\n{}",
+            SKYLAKE,
+            SKYLAKE.replace("processor	: 0", "processor	: 1")
+        );
+        let cpuinfo = cpuinfo(&input).unwrap();
+
+        assert_eq!(
+            cpuinfo.cpus[1].summary(),
+            "cpu1: Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz (GenuineIntel 6/94/3) @ 4000 MHz"
+        );
+        assert_eq!(
+            cpuinfo.summary(),
+            "2 x Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz"
+        );
+        assert_eq!(cpuinfo.to_string().matches("Processor:").count(), 2);
+    }
+
+    #[test]
+    fn displays_colors() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        let colored = cpuinfo.colored().to_string();
+        assert!(colored.contains("Flags:          \x1b[32mfpu vme de pse\x1b[0m\n"));
+        assert!(colored.contains("Bugs:           \x1b[31mcpu_meltdown\x1b[0m\n"));
+        assert_eq!(
+            colored
+                .replace("\x1b[32m", "")
+                .replace("\x1b[31m", "")
+                .replace("\x1b[0m", ""),
+            cpuinfo.to_string()
+        );
+        assert_eq!(cpuinfo.cpus[0].colored().to_string(), colored);
+    }
+}
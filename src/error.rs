@@ -0,0 +1,436 @@
+use alloc::string::{String, ToString};
+use core::fmt;
+#[cfg(feature = "std")]
+use std::io;
+
+use thiserror::Error;
+
+use crate::Architecture;
+
+#[derive(Debug, Error)]
+pub enum CpuInfoError {
+    #[error("unexpected field `{found}`, expected `{expected}` at {location}")]
+    UnexpectedField {
+        expected: &'static str,
+        found: String,
+        location: Location,
+    },
+    #[error("missing field `{field}` at {location}")]
+    MissingField {
+        field: &'static str,
+        location: Location,
+    },
+    #[error("invalid value `{value}` for field `{field}` at {location}")]
+    InvalidValue {
+        field: String,
+        value: String,
+        location: Location,
+    },
+    #[error("input is {len} bytes, limit is {limit}")]
+    InputTooLarge { len: usize, limit: usize },
+    #[error("found {count} cpus, limit is {limit}")]
+    TooManyCpus { count: usize, limit: usize },
+    #[error("expected {expected:?} cpuinfo, found {found:?}")]
+    ArchitectureMismatch {
+        expected: Architecture,
+        found: Option<Architecture>,
+    },
+    #[error("{0:?} cpuinfo is not supported, only x86")]
+    UnsupportedArchitecture(Architecture),
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[cfg(feature = "json")]
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[cfg(feature = "postcard")]
+    #[error(transparent)]
+    Postcard(#[from] postcard::Error),
+    #[cfg(feature = "postcard")]
+    #[error("not a binary cpuinfo snapshot")]
+    NotASnapshot,
+    #[cfg(feature = "postcard")]
+    #[error("binary snapshot version {found}, expected {expected}")]
+    UnsupportedVersion { found: u8, expected: u8 },
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlSerialize(#[from] toml::ser::Error),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlDeserialize(#[from] toml::de::Error),
+    #[cfg(feature = "yaml")]
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
+}
+
+/// Where in the input a parse error was detected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// 1-based line number within the input.
+    pub line: usize,
+    /// The processor block the line belongs to, when there is one.
+    pub processor: Option<u32>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}", self.line)?;
+        if let Some(processor) = self.processor {
+            write!(f, " (processor {})", processor)?;
+        }
+        Ok(())
+    }
+}
+
+pub type Result<T> = core::result::Result<T, CpuInfoError>;
+
+#[derive(Clone, Copy, PartialEq)]
+pub(crate) enum Presence {
+    Required,
+    Optional,
+    /// Required, except in guest mode.
+    Host,
+}
+
+/// One entry in the order fields are printed in. Repeated fields are keyed
+/// by a prefix followed by an index, e.g. `cache0`, `processor 1`.
+pub(crate) struct FieldSpec {
+    pub name: &'static str,
+    pub presence: Presence,
+    pub repeated: bool,
+}
+
+impl FieldSpec {
+    pub const fn required(name: &'static str) -> Self {
+        Self {
+            name,
+            presence: Presence::Required,
+            repeated: false,
+        }
+    }
+
+    pub const fn optional(name: &'static str) -> Self {
+        Self {
+            name,
+            presence: Presence::Optional,
+            repeated: false,
+        }
+    }
+
+    pub const fn host(name: &'static str) -> Self {
+        Self {
+            name,
+            presence: Presence::Host,
+            repeated: false,
+        }
+    }
+
+    pub const fn repeated(name: &'static str, presence: Presence) -> Self {
+        Self {
+            name,
+            presence,
+            repeated: true,
+        }
+    }
+
+    fn matches(&self, key: &str) -> bool {
+        if self.repeated {
+            key.strip_prefix(self.name)
+                .and_then(|rest| rest.chars().next())
+                .is_some_and(|c| c.is_ascii_digit() || c == ' ')
+        } else {
+            key.eq_ignore_ascii_case(self.name)
+        }
+    }
+
+    fn is_required(&self, guest: bool) -> bool {
+        match self.presence {
+            Presence::Required => true,
+            Presence::Optional => false,
+            Presence::Host => !guest,
+        }
+    }
+}
+
+/// Describes the fields of a cpuinfo flavour, with `restart` pointing at the
+/// field that opens a new block after a blank line.
+pub(crate) struct Schema {
+    pub fields: &'static [FieldSpec],
+    pub restart: usize,
+}
+
+impl Schema {
+    fn position(&self, key: &str) -> Option<usize> {
+        self.fields.iter().position(|field| field.matches(key))
+    }
+
+    fn next_required(&self, from: usize, guest: bool) -> usize {
+        (from..self.fields.len())
+            .chain(self.restart..self.fields.len())
+            .find(|&i| self.fields[i].is_required(guest))
+            .unwrap_or(self.restart)
+    }
+}
+
+fn key_value(line: &str) -> (&str, &str) {
+    match line.split_once(':') {
+        Some((key, value)) => (key.trim(), value.trim()),
+        None => (line.trim(), ""),
+    }
+}
+
+/// Finds the processor number of the block containing `line`, looking at
+/// the line itself first and then upwards until the start of the block.
+fn processor<'a>(line: &'a str, before: impl Iterator<Item = &'a str>) -> Option<u32> {
+    core::iter::once(line)
+        .chain(before)
+        .take_while(|line| !line.trim().is_empty())
+        .find_map(|line| match key_value(line) {
+            ("processor", value) => Some(value.parse().ok()),
+            (key, _) => key
+                .strip_prefix("processor ")
+                .map(|number| number.trim().parse().ok()),
+        })
+        .flatten()
+}
+
+/// Turns a nom failure into an error describing the offending field, based
+/// on the line the parser stopped at and the field printed before it.
+pub(crate) fn diagnose(
+    input: &str,
+    error: nom::Err<nom::error::Error<&str>>,
+    schema: &Schema,
+    guest: bool,
+) -> CpuInfoError {
+    let offset = match error {
+        nom::Err::Error(e) | nom::Err::Failure(e) => input.len() - e.input.len(),
+        nom::Err::Incomplete(_) => input.len(),
+    };
+
+    let start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+    let line = input[start..].lines().next().unwrap_or_default();
+
+    let mut restarted = start == 0;
+    let mut previous = None;
+    for before in input[..start].lines().rev() {
+        if before.trim().is_empty() {
+            restarted = true;
+        } else {
+            previous = Some(key_value(before).0);
+            break;
+        }
+    }
+
+    // Fields that may legitimately appear at this point: everything up to
+    // and including the next required one.
+    let (from, next) = match previous.and_then(|key| schema.position(key)) {
+        Some(i) if schema.fields[i].repeated => (i, i + 1),
+        Some(i) => (i + 1, i + 1),
+        None => (0, 0),
+    };
+    let expected = schema.next_required(next, guest);
+    let allowed = |i: usize| {
+        (from <= i && i <= expected)
+            || (expected < from && (i >= from || (schema.restart <= i && i <= expected)))
+            || (restarted && i == schema.restart)
+    };
+
+    let (key, value) = key_value(line);
+    let expected = schema.fields[expected].name;
+    let location = Location {
+        line: input[..start].matches('\n').count() + 1,
+        processor: processor(line, input[..start].lines().rev()),
+    };
+
+    if line.trim().is_empty() {
+        return CpuInfoError::MissingField {
+            field: expected,
+            location,
+        };
+    }
+
+    match schema.position(key) {
+        Some(i) if allowed(i) => CpuInfoError::InvalidValue {
+            field: key.to_string(),
+            value: value.to_string(),
+            location,
+        },
+        Some(_) => CpuInfoError::MissingField {
+            field: expected,
+            location,
+        },
+        None => CpuInfoError::UnexpectedField {
+            expected,
+            found: key.to_string(),
+            location,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::SKYLAKE;
+    use crate::{arm, cpuinfo, s390};
+    use alloc::format;
+
+    fn two_cpus(second: &str) -> String {
+        format!("{}\n{}", SKYLAKE, second)
+    }
+
+    #[test]
+    fn reports_invalid_value() {
+        let input = SKYLAKE.replace("4000.000", "fast");
+        match cpuinfo(&input) {
+            Err(CpuInfoError::InvalidValue {
+                field,
+                value,
+                location,
+            }) => {
+                assert_eq!(field, "cpu MHz");
+                assert_eq!(value, "fast");
+                assert_eq!(
+                    location,
+                    Location {
+                        line: 8,
+                        processor: Some(0),
+                    }
+                );
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_missing_field() {
+        let input = SKYLAKE.replace("stepping	: 3\n", "");
+        assert!(matches!(
+            cpuinfo(&input),
+            Err(CpuInfoError::MissingField {
+                field: "stepping",
+                location: Location { line: 6, .. },
+            })
+        ));
+    }
+
+    #[test]
+    fn reports_missing_field_at_end_of_input() {
+        let input = SKYLAKE.replace("power management:\n", "");
+        assert!(matches!(
+            cpuinfo(&input),
+            Err(CpuInfoError::MissingField {
+                field: "power management",
+                location: Location { line: 26, .. },
+            })
+        ));
+    }
+
+    #[test]
+    fn reports_unexpected_field() {
+        let input = SKYLAKE.replace("wp		: yes\n", "wp		: yes\nfrobnication	: on\n");
+        match cpuinfo(&input) {
+            Err(CpuInfoError::UnexpectedField {
+                expected, found, ..
+            }) => {
+                assert_eq!(expected, "flags");
+                assert_eq!(found, "frobnication");
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    #[test]
+    fn reports_errors_in_later_blocks() {
+        let input = two_cpus(
+            &SKYLAKE
+                .replace("processor	: 0", "processor	: 1")
+                .replace("yes", "maybe"),
+        );
+        match cpuinfo(&input) {
+            Err(CpuInfoError::InvalidValue {
+                field,
+                value,
+                location,
+            }) => {
+                assert_eq!(field, "fpu");
+                assert_eq!(value, "maybe");
+                assert_eq!(
+                    location,
+                    Location {
+                        line: 43,
+                        processor: Some(1),
+                    }
+                );
+                assert_eq!(
+                    CpuInfoError::InvalidValue {
+                        field,
+                        value,
+                        location
+                    }
+                    .to_string(),
+                    "invalid value `maybe` for field `fpu` at line 43 (processor 1)"
+                );
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let input = two_cpus(&SKYLAKE.replace("processor	: 0", "processor	: one"));
+        assert!(matches!(
+            cpuinfo(&input),
+            Err(CpuInfoError::InvalidValue { field, .. }) if field == "processor"
+        ));
+    }
+
+    #[test]
+    fn reports_missing_machine_section() {
+        let result = arm::cpuinfo(
+            "processor	: 0
+model name	: ARMv7 Processor rev 4 (v7l)
+BogoMIPS	: 38.40
+Features	: half thumb fastmult vfp edsp neon vfpv3 tls vfpv4 idiva idivt vfpd32 lpae evtstrm crc32
+CPU implementer	: 0x41
+CPU architecture: 7
+CPU variant	: 0x0
+CPU part	: 0xd03
+CPU revision	: 4
+
+Revision	: a02082
+",
+        );
+        assert!(matches!(
+            result,
+            Err(CpuInfoError::MissingField {
+                field: "Hardware",
+                location: Location {
+                    line: 11,
+                    processor: None,
+                },
+            })
+        ));
+    }
+
+    #[test]
+    fn reports_invalid_repeated_field() {
+        let result = s390::cpuinfo(
+            "vendor_id       : IBM/S390
+# processors    : 1
+bogomips per cpu: 3241.00
+features	: esan3 zarch stfle msa
+facilities      : 0 1 2 3
+cache0          : level=1 type=Data scope=Private size=128K line_size=256 associativity=8
+processor 0: version = FF,  identification = 233EF7,  machine =
+",
+        );
+        assert!(matches!(
+            result,
+            Err(CpuInfoError::InvalidValue {
+                field,
+                location: Location {
+                    line: 7,
+                    processor: Some(0),
+                },
+                ..
+            }) if field == "processor 0"
+        ));
+    }
+}
@@ -0,0 +1,139 @@
+#[cfg(target_os = "linux")]
+use std::io;
+use std::{fs, io::Read, path::Path};
+
+#[cfg(feature = "tokio")]
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::{cpuinfo, CpuInfo, CpuInfoOwned, Result};
+#[cfg(target_os = "linux")]
+use crate::{cpuinfo_guest, Architecture, CpuInfoError};
+
+impl CpuInfo<'_> {
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<CpuInfoOwned> {
+        let buffer = fs::read_to_string(path)?;
+        Ok(cpuinfo(&buffer)?.to_owned())
+    }
+
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<CpuInfoOwned> {
+        let mut buffer = String::new();
+        reader.read_to_string(&mut buffer)?;
+        Ok(cpuinfo(&buffer)?.to_owned())
+    }
+
+    /// Parses `/proc/cpuinfo` of the running system. Guest parsing is used
+    /// so that virtual machines, which often omit some fields, are accepted.
+    /// Only x86 hosts are supported; on others this fails with
+    /// [`CpuInfoError::UnsupportedArchitecture`].
+    #[cfg(target_os = "linux")]
+    pub fn current() -> Result<CpuInfoOwned> {
+        let buffer = fs::read_to_string("/proc/cpuinfo").map_err(not_mounted)?;
+        parse_current(&buffer)
+    }
+
+    #[cfg(feature = "tokio")]
+    pub async fn from_async_reader<R: AsyncRead + Unpin>(mut reader: R) -> Result<CpuInfoOwned> {
+        let mut buffer = String::new();
+        reader.read_to_string(&mut buffer).await?;
+        Ok(cpuinfo(&buffer)?.to_owned())
+    }
+
+    /// Like [`CpuInfo::current`], without blocking the runtime.
+    #[cfg(all(feature = "tokio", target_os = "linux"))]
+    pub async fn current_async() -> Result<CpuInfoOwned> {
+        let buffer = tokio::fs::read_to_string("/proc/cpuinfo")
+            .await
+            .map_err(not_mounted)?;
+        parse_current(&buffer)
+    }
+}
+
+/// Text that is not recognized at all is still handed to the x86 parser, so
+/// that its error points at the offending line.
+#[cfg(target_os = "linux")]
+fn parse_current(buffer: &str) -> Result<CpuInfoOwned> {
+    match Architecture::detect(buffer) {
+        Some(Architecture::X86) | None => Ok(cpuinfo_guest(buffer)?.to_owned()),
+        Some(found) => Err(CpuInfoError::UnsupportedArchitecture(found)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn not_mounted(e: io::Error) -> io::Error {
+    match e.kind() {
+        io::ErrorKind::NotFound => io::Error::new(
+            io::ErrorKind::NotFound,
+            "/proc/cpuinfo not found, is procfs mounted?",
+        ),
+        _ => e,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::SKYLAKE;
+
+    #[test]
+    fn parses_from_reader() {
+        let result = CpuInfo::from_reader(SKYLAKE.as_bytes());
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().cpus[0].model, 94);
+    }
+
+    #[test]
+    fn parses_from_path() {
+        let path = std::env::temp_dir().join(format!("cpuinfo-{}", std::process::id()));
+        fs::write(&path, SKYLAKE).unwrap();
+
+        let result = CpuInfo::from_path(&path);
+        fs::remove_file(&path).unwrap();
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().cpus[0].vendor_id, "GenuineIntel");
+    }
+
+    #[test]
+    fn fails_on_missing_path() {
+        let result = CpuInfo::from_path("/nonexistent/cpuinfo");
+        assert!(matches!(result, Err(crate::CpuInfoError::Io(_))));
+    }
+
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    #[test]
+    fn parses_current() {
+        let result = CpuInfo::current();
+        assert!(result.is_ok());
+        assert!(!result.unwrap().cpus.is_empty());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn rejects_other_architectures() {
+        assert!(parse_current(SKYLAKE).is_ok());
+        assert!(matches!(
+            parse_current("processor	: 0\nhart		: 1\nisa		: rv64imafdc\n"),
+            Err(CpuInfoError::UnsupportedArchitecture(Architecture::RiscV))
+        ));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn parses_asynchronously() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .unwrap();
+
+        let result = runtime.block_on(CpuInfo::from_async_reader(SKYLAKE.as_bytes()));
+        assert_eq!(result.unwrap().cpus[0].model, 94);
+
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        assert_eq!(
+            runtime
+                .block_on(CpuInfo::current_async())
+                .unwrap()
+                .cpus
+                .len(),
+            CpuInfo::current().unwrap().cpus.len()
+        );
+    }
+}
@@ -0,0 +1,437 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{convert::Infallible, fmt, str::FromStr};
+
+use crate::{Cpu, CpuInfo};
+
+macro_rules! cpu_flags {
+    ($($variant:ident => $name:literal,)*) => {
+        /// A flag from the `flags` line, as named by the kernel.
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum CpuFlag {
+            $($variant,)*
+            Unknown(String),
+        }
+
+        enum Index {
+            $($variant,)*
+        }
+
+        /// Every known flag, in the order of [`CpuFlag::index`].
+        pub(crate) const KNOWN: &[CpuFlag] = &[$(CpuFlag::$variant,)*];
+
+        impl CpuFlag {
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$variant => $name,)*
+                    Self::Unknown(name) => name,
+                }
+            }
+
+            /// Position among the known flags, `None` for unknown ones.
+            pub(crate) fn index(&self) -> Option<usize> {
+                match self {
+                    $(Self::$variant => Some(Index::$variant as usize),)*
+                    Self::Unknown(_) => None,
+                }
+            }
+        }
+
+        impl FromStr for CpuFlag {
+            type Err = Infallible;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Ok(match s {
+                    $($name => Self::$variant,)*
+                    _ => Self::Unknown(s.to_string()),
+                })
+            }
+        }
+    };
+}
+
+cpu_flags! {
+    Fpu => "fpu",
+    Vme => "vme",
+    De => "de",
+    Pse => "pse",
+    Tsc => "tsc",
+    Msr => "msr",
+    Pae => "pae",
+    Mce => "mce",
+    Cx8 => "cx8",
+    Apic => "apic",
+    Sep => "sep",
+    Mtrr => "mtrr",
+    Pge => "pge",
+    Mca => "mca",
+    Cmov => "cmov",
+    Pat => "pat",
+    Pse36 => "pse36",
+    Pn => "pn",
+    Clflush => "clflush",
+    Dts => "dts",
+    Acpi => "acpi",
+    Mmx => "mmx",
+    Fxsr => "fxsr",
+    Sse => "sse",
+    Sse2 => "sse2",
+    Ss => "ss",
+    Ht => "ht",
+    Tm => "tm",
+    Ia64 => "ia64",
+    Pbe => "pbe",
+    Syscall => "syscall",
+    Mp => "mp",
+    Nx => "nx",
+    Mmxext => "mmxext",
+    FxsrOpt => "fxsr_opt",
+    Pdpe1gb => "pdpe1gb",
+    Rdtscp => "rdtscp",
+    Lm => "lm",
+    ThreeDNowExt => "3dnowext",
+    ThreeDNow => "3dnow",
+    Recovery => "recovery",
+    Longrun => "longrun",
+    Lrti => "lrti",
+    Cxmmx => "cxmmx",
+    K8 => "k8",
+    K7 => "k7",
+    P3 => "p3",
+    P4 => "p4",
+    ConstantTsc => "constant_tsc",
+    Up => "up",
+    Art => "art",
+    ArchPerfmon => "arch_perfmon",
+    Pebs => "pebs",
+    Bts => "bts",
+    RepGood => "rep_good",
+    AccPower => "acc_power",
+    Nopl => "nopl",
+    Xtopology => "xtopology",
+    TscReliable => "tsc_reliable",
+    NonstopTsc => "nonstop_tsc",
+    Cpuid => "cpuid",
+    ExtdApicid => "extd_apicid",
+    AmdDcm => "amd_dcm",
+    Aperfmperf => "aperfmperf",
+    Rapl => "rapl",
+    NonstopTscS3 => "nonstop_tsc_s3",
+    TscKnownFreq => "tsc_known_freq",
+    Pni => "pni",
+    Pclmulqdq => "pclmulqdq",
+    Dtes64 => "dtes64",
+    Monitor => "monitor",
+    DsCpl => "ds_cpl",
+    Vmx => "vmx",
+    Smx => "smx",
+    Est => "est",
+    Tm2 => "tm2",
+    Ssse3 => "ssse3",
+    Cid => "cid",
+    Sdbg => "sdbg",
+    Fma => "fma",
+    Cx16 => "cx16",
+    Xtpr => "xtpr",
+    Pdcm => "pdcm",
+    Pcid => "pcid",
+    Dca => "dca",
+    Sse4_1 => "sse4_1",
+    Sse4_2 => "sse4_2",
+    X2apic => "x2apic",
+    Movbe => "movbe",
+    Popcnt => "popcnt",
+    TscDeadlineTimer => "tsc_deadline_timer",
+    Aes => "aes",
+    Xsave => "xsave",
+    Avx => "avx",
+    F16c => "f16c",
+    Rdrand => "rdrand",
+    Hypervisor => "hypervisor",
+    Rng => "rng",
+    RngEn => "rng_en",
+    Ace => "ace",
+    AceEn => "ace_en",
+    Ace2 => "ace2",
+    Ace2En => "ace2_en",
+    Phe => "phe",
+    PheEn => "phe_en",
+    Pmm => "pmm",
+    PmmEn => "pmm_en",
+    LahfLm => "lahf_lm",
+    CmpLegacy => "cmp_legacy",
+    Svm => "svm",
+    Extapic => "extapic",
+    Cr8Legacy => "cr8_legacy",
+    Abm => "abm",
+    Sse4a => "sse4a",
+    Misalignsse => "misalignsse",
+    ThreeDNowPrefetch => "3dnowprefetch",
+    Osvw => "osvw",
+    Ibs => "ibs",
+    Xop => "xop",
+    Skinit => "skinit",
+    Wdt => "wdt",
+    Lwp => "lwp",
+    Fma4 => "fma4",
+    Tce => "tce",
+    NodeidMsr => "nodeid_msr",
+    Tbm => "tbm",
+    Topoext => "topoext",
+    PerfctrCore => "perfctr_core",
+    PerfctrNb => "perfctr_nb",
+    Bpext => "bpext",
+    Ptsc => "ptsc",
+    PerfctrLlc => "perfctr_llc",
+    Mwaitx => "mwaitx",
+    Ring3mwait => "ring3mwait",
+    CpuidFault => "cpuid_fault",
+    Cpb => "cpb",
+    Epb => "epb",
+    CatL3 => "cat_l3",
+    CatL2 => "cat_l2",
+    CdpL3 => "cdp_l3",
+    InvpcidSingle => "invpcid_single",
+    HwPstate => "hw_pstate",
+    ProcFeedback => "proc_feedback",
+    Sme => "sme",
+    Pti => "pti",
+    IntelPpin => "intel_ppin",
+    CdpL2 => "cdp_l2",
+    Ssbd => "ssbd",
+    Mba => "mba",
+    Sev => "sev",
+    Ibrs => "ibrs",
+    Ibpb => "ibpb",
+    Stibp => "stibp",
+    IbrsEnhanced => "ibrs_enhanced",
+    SplitLockDetect => "split_lock_detect",
+    UserShstk => "user_shstk",
+    TprShadow => "tpr_shadow",
+    Vnmi => "vnmi",
+    Flexpriority => "flexpriority",
+    Ept => "ept",
+    Vpid => "vpid",
+    EptAd => "ept_ad",
+    Vmmcall => "vmmcall",
+    Fsgsbase => "fsgsbase",
+    TscAdjust => "tsc_adjust",
+    Sgx => "sgx",
+    Bmi1 => "bmi1",
+    Hle => "hle",
+    Avx2 => "avx2",
+    FdpExcptnOnly => "fdp_excptn_only",
+    Smep => "smep",
+    Bmi2 => "bmi2",
+    Erms => "erms",
+    Invpcid => "invpcid",
+    Rtm => "rtm",
+    Cqm => "cqm",
+    Mpx => "mpx",
+    RdtA => "rdt_a",
+    Avx512f => "avx512f",
+    Avx512dq => "avx512dq",
+    Rdseed => "rdseed",
+    Adx => "adx",
+    Smap => "smap",
+    Avx512ifma => "avx512ifma",
+    Clflushopt => "clflushopt",
+    Clwb => "clwb",
+    IntelPt => "intel_pt",
+    Avx512pf => "avx512pf",
+    Avx512er => "avx512er",
+    Avx512cd => "avx512cd",
+    ShaNi => "sha_ni",
+    Avx512bw => "avx512bw",
+    Avx512vl => "avx512vl",
+    Xsaveopt => "xsaveopt",
+    Xsavec => "xsavec",
+    Xgetbv1 => "xgetbv1",
+    Xsaves => "xsaves",
+    CqmLlc => "cqm_llc",
+    CqmOccupLlc => "cqm_occup_llc",
+    CqmMbmTotal => "cqm_mbm_total",
+    CqmMbmLocal => "cqm_mbm_local",
+    AvxVnni => "avx_vnni",
+    Avx512Bf16 => "avx512_bf16",
+    Clzero => "clzero",
+    Irperf => "irperf",
+    Xsaveerptr => "xsaveerptr",
+    Rdpru => "rdpru",
+    Wbnoinvd => "wbnoinvd",
+    AmdPpin => "amd_ppin",
+    VirtSsbd => "virt_ssbd",
+    Dtherm => "dtherm",
+    Ida => "ida",
+    Arat => "arat",
+    Pln => "pln",
+    Pts => "pts",
+    Hwp => "hwp",
+    HwpNotify => "hwp_notify",
+    HwpActWindow => "hwp_act_window",
+    HwpEpp => "hwp_epp",
+    HwpPkgReq => "hwp_pkg_req",
+    Npt => "npt",
+    Lbrv => "lbrv",
+    SvmLock => "svm_lock",
+    NripSave => "nrip_save",
+    TscScale => "tsc_scale",
+    VmcbClean => "vmcb_clean",
+    Flushbyasid => "flushbyasid",
+    Decodeassists => "decodeassists",
+    Pausefilter => "pausefilter",
+    Pfthreshold => "pfthreshold",
+    Avic => "avic",
+    VVmsaveVmload => "v_vmsave_vmload",
+    Vgif => "vgif",
+    X2avic => "x2avic",
+    VSpecCtrl => "v_spec_ctrl",
+    Avx512vbmi => "avx512vbmi",
+    Umip => "umip",
+    Pku => "pku",
+    Ospke => "ospke",
+    Waitpkg => "waitpkg",
+    Avx512Vbmi2 => "avx512_vbmi2",
+    Shstk => "shstk",
+    Gfni => "gfni",
+    Vaes => "vaes",
+    Vpclmulqdq => "vpclmulqdq",
+    Avx512Vnni => "avx512_vnni",
+    Avx512Bitalg => "avx512_bitalg",
+    Tme => "tme",
+    Avx512Vpopcntdq => "avx512_vpopcntdq",
+    La57 => "la57",
+    Rdpid => "rdpid",
+    BusLockDetect => "bus_lock_detect",
+    Cldemote => "cldemote",
+    Movdiri => "movdiri",
+    Movdir64b => "movdir64b",
+    Enqcmd => "enqcmd",
+    SgxLc => "sgx_lc",
+    OverflowRecov => "overflow_recov",
+    Succor => "succor",
+    Smca => "smca",
+    Avx512_4vnniw => "avx512_4vnniw",
+    Avx512_4fmaps => "avx512_4fmaps",
+    Fsrm => "fsrm",
+    Avx512Vp2intersect => "avx512_vp2intersect",
+    SrbdsCtrl => "srbds_ctrl",
+    MdClear => "md_clear",
+    RtmAlwaysAbort => "rtm_always_abort",
+    TsxForceAbort => "tsx_force_abort",
+    Serialize => "serialize",
+    HybridCpu => "hybrid_cpu",
+    Tsxldtrk => "tsxldtrk",
+    Pconfig => "pconfig",
+    ArchLbr => "arch_lbr",
+    Ibt => "ibt",
+    AmxBf16 => "amx_bf16",
+    Avx512Fp16 => "avx512_fp16",
+    AmxTile => "amx_tile",
+    AmxInt8 => "amx_int8",
+    FlushL1d => "flush_l1d",
+    ArchCapabilities => "arch_capabilities",
+    SmeCoherent => "sme_coherent",
+    SevEs => "sev_es",
+    VTscAux => "v_tsc_aux",
+    SevSnp => "sev_snp",
+    LfenceRdtsc => "lfence_rdtsc",
+}
+
+impl From<&str> for CpuFlag {
+    fn from(s: &str) -> Self {
+        let Ok(flag) = s.parse();
+        flag
+    }
+}
+
+impl fmt::Display for CpuFlag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Cpu<'_> {
+    /// The `flags` line as [`CpuFlag`]s, in the order the kernel printed them.
+    pub fn typed_flags(&self) -> Vec<CpuFlag> {
+        self.flags.iter().map(|&flag| CpuFlag::from(flag)).collect()
+    }
+
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.iter().any(|f| f.eq_ignore_ascii_case(flag))
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Whether every cpu has `flag`. An empty `CpuInfo` has no flags.
+    pub fn all_have_flag(&self, flag: &str) -> bool {
+        !self.cpus.is_empty() && self.cpus.iter().all(|cpu| cpu.has_flag(flag))
+    }
+
+    pub fn any_has_flag(&self, flag: &str) -> bool {
+        self.cpus.iter().any(|cpu| cpu.has_flag(flag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        CpuBuilder, CpuInfoBuilder,
+    };
+
+    #[test]
+    fn parses_known_flags() {
+        assert_eq!("avx2".parse::<CpuFlag>(), Ok(CpuFlag::Avx2));
+        assert_eq!("sse4_2".parse::<CpuFlag>(), Ok(CpuFlag::Sse4_2));
+        assert_eq!(
+            "3dnowprefetch".parse::<CpuFlag>(),
+            Ok(CpuFlag::ThreeDNowPrefetch)
+        );
+        assert_eq!(CpuFlag::Avx512Vbmi2.as_str(), "avx512_vbmi2");
+    }
+
+    #[test]
+    fn keeps_unknown_flags() {
+        let flag = CpuFlag::from("frobnicate");
+        assert_eq!(flag, CpuFlag::Unknown("frobnicate".to_string()));
+        assert_eq!(flag.to_string(), "frobnicate");
+    }
+
+    #[test]
+    fn types_every_flag_of_a_real_cpu() {
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let flags = cpuinfo.cpus[0].typed_flags();
+
+        assert_eq!(flags.len(), cpuinfo.cpus[0].flags.len());
+        assert!(flags.contains(&CpuFlag::Svm));
+        assert!(!flags.iter().any(|flag| matches!(flag, CpuFlag::Unknown(_))));
+    }
+
+    #[test]
+    fn queries_flags() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        assert!(cpuinfo.cpus[0].has_flag("vme"));
+        assert!(cpuinfo.cpus[0].has_flag("VME"));
+        assert!(!cpuinfo.cpus[0].has_flag("avx2"));
+        assert!(cpuinfo.all_have_flag("fpu"));
+    }
+
+    #[test]
+    fn queries_flags_across_cpus() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(CpuBuilder::new().flag("fpu").flag("avx2").build())
+            .cpu(CpuBuilder::new().flag("fpu").build())
+            .build();
+
+        assert!(cpuinfo.all_have_flag("fpu"));
+        assert!(!cpuinfo.all_have_flag("avx2"));
+        assert!(cpuinfo.any_has_flag("AVX2"));
+        assert!(!cpuinfo.any_has_flag("avx512f"));
+        assert!(!CpuInfoBuilder::new().build().all_have_flag("fpu"));
+    }
+}
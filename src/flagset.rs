@@ -0,0 +1,191 @@
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use crate::{flag::KNOWN, Cpu, CpuFlag, CpuInfo};
+
+const WORDS: usize = KNOWN.len().div_ceil(64);
+
+fn bit(index: usize) -> u64 {
+    1 << (index % 64)
+}
+
+/// A compact set of flags: every [`CpuFlag`] known to this crate takes one
+/// bit, other flags go to a sorted overflow list. Lookups of known flags and
+/// intersections are constant time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FlagSet {
+    known: [u64; WORDS],
+    unknown: Vec<String>,
+}
+
+impl FlagSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `flag`, returning whether it was not present yet.
+    pub fn insert(&mut self, flag: CpuFlag) -> bool {
+        if let Some(index) = flag.index() {
+            let added = !self.contains(&flag);
+            self.known[index / 64] |= bit(index);
+            return added;
+        }
+        match self.find_unknown(flag.as_str()) {
+            Ok(_) => false,
+            Err(position) => {
+                self.unknown.insert(position, flag.as_str().to_string());
+                true
+            }
+        }
+    }
+
+    pub fn contains(&self, flag: &CpuFlag) -> bool {
+        match flag.index() {
+            Some(index) => self.known[index / 64] & bit(index) != 0,
+            None => self.find_unknown(flag.as_str()).is_ok(),
+        }
+    }
+
+    fn find_unknown(&self, name: &str) -> Result<usize, usize> {
+        self.unknown
+            .binary_search_by(|unknown| unknown.as_str().cmp(name))
+    }
+
+    /// Like [`Cpu::has_flag`], case-insensitive.
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.contains(&CpuFlag::from(flag.to_ascii_lowercase().as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        let known: u32 = self.known.iter().map(|word| word.count_ones()).sum();
+        known as usize + self.unknown.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut known = self.known;
+        for (word, other) in known.iter_mut().zip(other.known) {
+            *word &= other;
+        }
+        Self {
+            known,
+            unknown: self
+                .unknown
+                .iter()
+                .filter(|name| other.find_unknown(name).is_ok())
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.known
+            .iter()
+            .zip(other.known)
+            .all(|(word, other)| word & !other == 0)
+            && self
+                .unknown
+                .iter()
+                .all(|name| other.find_unknown(name).is_ok())
+    }
+
+    /// Known flags in declaration order, followed by unknown ones sorted by
+    /// name.
+    pub fn iter(&self) -> impl Iterator<Item = CpuFlag> + '_ {
+        KNOWN
+            .iter()
+            .filter(|flag| self.contains(flag))
+            .cloned()
+            .chain(self.unknown.iter().cloned().map(CpuFlag::Unknown))
+    }
+}
+
+impl FromIterator<CpuFlag> for FlagSet {
+    fn from_iter<I: IntoIterator<Item = CpuFlag>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for flag in iter {
+            set.insert(flag);
+        }
+        set
+    }
+}
+
+impl Cpu<'_> {
+    pub fn flag_set(&self) -> FlagSet {
+        self.flags.iter().map(|&flag| CpuFlag::from(flag)).collect()
+    }
+}
+
+impl CpuInfo<'_> {
+    /// The flags every cpu has, empty when there are no cpus.
+    pub fn common_flags(&self) -> FlagSet {
+        self.cpus
+            .iter()
+            .map(Cpu::flag_set)
+            .reduce(|common, flags| common.intersection(&flags))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        CpuBuilder, CpuInfoBuilder,
+    };
+
+    #[test]
+    fn stores_known_and_unknown_flags() {
+        let mut set = FlagSet::new();
+        assert!(set.insert(CpuFlag::Avx2));
+        assert!(!set.insert(CpuFlag::Avx2));
+        assert!(set.insert(CpuFlag::from("frobnicate")));
+        assert!(set.insert(CpuFlag::LfenceRdtsc));
+
+        assert_eq!(set.len(), 3);
+        assert!(set.contains(&CpuFlag::Avx2));
+        assert!(set.has_flag("FROBNICATE"));
+        assert!(!set.has_flag("fpu"));
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [
+                CpuFlag::Avx2,
+                CpuFlag::LfenceRdtsc,
+                CpuFlag::from("frobnicate")
+            ]
+        );
+    }
+
+    #[test]
+    fn mirrors_a_real_cpu() {
+        let epyc = cpuinfo(EPYC).unwrap();
+        let cpu = &epyc.cpus[0];
+        let set = cpu.flag_set();
+
+        assert_eq!(set.len(), cpu.flags.len());
+        assert!(cpu.flags.iter().all(|flag| set.has_flag(flag)));
+        assert!(cpuinfo(SKYLAKE).unwrap().cpus[0].flag_set().is_subset(&set));
+    }
+
+    #[test]
+    fn intersects_across_cpus() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(CpuBuilder::new().flag("fpu").flag("avx2").flag("x").build())
+            .cpu(CpuBuilder::new().flag("fpu").flag("x").flag("y").build())
+            .build();
+
+        let common = cpuinfo.common_flags();
+        assert_eq!(
+            common.iter().collect::<Vec<_>>(),
+            [CpuFlag::Fpu, CpuFlag::from("x")]
+        );
+        assert!(CpuInfoBuilder::new().build().common_flags().is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use alloc::{collections::BTreeMap, string::String, vec::Vec};
+
+use crate::{CpuInfoOwned, FlagSet};
+
+/// Snapshots of many hosts, keyed by host name, for inventory queries.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Fleet {
+    hosts: BTreeMap<String, CpuInfoOwned>,
+}
+
+impl Fleet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the snapshot of `host`, returning the one it replaces.
+    pub fn insert<H: Into<String>>(&mut self, host: H, info: CpuInfoOwned) -> Option<CpuInfoOwned> {
+        self.hosts.insert(host.into(), info)
+    }
+
+    pub fn get(&self, host: &str) -> Option<&CpuInfoOwned> {
+        self.hosts.get(host)
+    }
+
+    pub fn len(&self) -> usize {
+        self.hosts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hosts.is_empty()
+    }
+
+    /// Hosts and their snapshots, ordered by host name.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &CpuInfoOwned)> {
+        self.hosts.iter().map(|(host, info)| (host.as_str(), info))
+    }
+
+    /// Hosts where at least one cpu lacks `flag`.
+    pub fn lacking_flag(&self, flag: &str) -> Vec<&str> {
+        self.iter()
+            .filter(|(_, info)| !info.as_cpuinfo().all_have_flag(flag))
+            .map(|(host, _)| host)
+            .collect()
+    }
+
+    /// The flags every cpu of every host has, i.e. what a binary built for
+    /// the whole fleet may rely on. Empty when the fleet is.
+    pub fn common_flags(&self) -> FlagSet {
+        self.hosts
+            .values()
+            .map(|info| info.as_cpuinfo().common_flags())
+            .reduce(|common, flags| common.intersection(&flags))
+            .unwrap_or_default()
+    }
+
+    /// Hosts running each microcode revision. A host whose cpus differ is
+    /// listed under every revision it runs; `None` collects cpus without a
+    /// `microcode` line.
+    pub fn microcode_versions(&self) -> BTreeMap<Option<u64>, Vec<&str>> {
+        let mut versions: BTreeMap<Option<u64>, Vec<&str>> = BTreeMap::new();
+        for (host, info) in self.iter() {
+            for cpu in &info.cpus {
+                let hosts = versions.entry(cpu.microcode).or_default();
+                if hosts.last() != Some(&host) {
+                    hosts.push(host);
+                }
+            }
+        }
+        versions
+    }
+
+    /// Hosts grouped by `model name`, e.g. to spot stragglers on old
+    /// hardware. Hosts with several models are listed under each.
+    pub fn by_model_name(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut models: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+        for (host, info) in self.iter() {
+            for cpu in &info.cpus {
+                let hosts = models.entry(&*cpu.model_name).or_default();
+                if hosts.last() != Some(&host) {
+                    hosts.push(host);
+                }
+            }
+        }
+        models
+    }
+}
+
+impl<H: Into<String>> FromIterator<(H, CpuInfoOwned)> for Fleet {
+    fn from_iter<I: IntoIterator<Item = (H, CpuInfoOwned)>>(iter: I) -> Self {
+        let mut fleet = Self::new();
+        fleet.extend(iter);
+        fleet
+    }
+}
+
+impl<H: Into<String>> Extend<(H, CpuInfoOwned)> for Fleet {
+    fn extend<I: IntoIterator<Item = (H, CpuInfoOwned)>>(&mut self, iter: I) {
+        for (host, info) in iter {
+            self.insert(host, info);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+
+    fn fleet() -> Fleet {
+        let both = alloc::format!("{}\n{}", SKYLAKE, EPYC);
+        [
+            ("web1", SKYLAKE),
+            ("db1", EPYC),
+            ("db2", EPYC),
+            ("mixed", both.as_str()),
+        ]
+        .into_iter()
+        .map(|(host, input)| (host, cpuinfo(input).unwrap().to_owned()))
+        .collect()
+    }
+
+    #[test]
+    fn answers_inventory_queries() {
+        let fleet = fleet();
+        assert_eq!(fleet.len(), 4);
+        assert_eq!(fleet.get("db1").unwrap().cpus[0].processor, 3);
+
+        assert_eq!(fleet.lacking_flag("avx2"), ["mixed", "web1"]);
+        assert!(fleet.lacking_flag("FPU").is_empty());
+
+        let versions = fleet.microcode_versions();
+        assert_eq!(versions[&Some(0xf0)], ["mixed", "web1"]);
+        assert_eq!(versions.len(), 2);
+
+        let models = fleet.by_model_name();
+        assert_eq!(
+            models["AMD EPYC 7763 64-Core Processor"],
+            ["db1", "db2", "mixed"]
+        );
+        assert_eq!(models.len(), 2);
+    }
+
+    #[test]
+    fn replaces_hosts() {
+        let mut fleet = fleet();
+        let old = fleet.insert("web1", cpuinfo(EPYC).unwrap().to_owned());
+        assert_eq!(old.unwrap().cpus[0].model, 94);
+        assert_eq!(fleet.lacking_flag("avx2"), ["mixed"]);
+    }
+
+    #[test]
+    fn intersects_flags_across_hosts() {
+        let mut fleet = Fleet::new();
+        assert!(fleet.common_flags().is_empty());
+
+        fleet.insert("db1", cpuinfo(EPYC).unwrap().to_owned());
+        assert_eq!(fleet.common_flags().len(), 101);
+
+        fleet.insert("web1", cpuinfo(SKYLAKE).unwrap().to_owned());
+        let common = fleet.common_flags();
+        assert_eq!(common.len(), 4);
+        assert!(common.has_flag("pse"));
+        assert!(!common.has_flag("avx2"));
+    }
+}
@@ -0,0 +1,200 @@
+use alloc::string::{String, ToString};
+use core::{fmt, str::FromStr};
+
+use thiserror::Error;
+
+use crate::Cpu;
+
+/// A clock frequency. Stored in Hz, so values read from cpuinfo (MHz),
+/// cpufreq (kHz) and model names (GHz) compare without unit mixups.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Frequency(u64);
+
+/// The text was not a number followed by `Hz`, `kHz`, `MHz` or `GHz`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Error)]
+#[error("invalid frequency `{0}`")]
+pub struct ParseFrequencyError(String);
+
+const UNITS: &[(&str, u32)] = &[("ghz", 9), ("mhz", 6), ("khz", 3), ("hz", 0)];
+
+impl Frequency {
+    pub const fn from_hz(hz: u64) -> Self {
+        Self(hz)
+    }
+
+    pub const fn from_khz(khz: u64) -> Self {
+        Self(khz * 1_000)
+    }
+
+    /// Rounds to the nearest Hz; negative values become zero.
+    pub fn from_mhz(mhz: f64) -> Self {
+        Self((mhz * 1e6 + 0.5) as u64)
+    }
+
+    pub fn from_ghz(ghz: f64) -> Self {
+        Self((ghz * 1e9 + 0.5) as u64)
+    }
+
+    pub const fn as_hz(&self) -> u64 {
+        self.0
+    }
+
+    pub fn as_khz(&self) -> f64 {
+        self.0 as f64 / 1e3
+    }
+
+    pub fn as_mhz(&self) -> f64 {
+        self.0 as f64 / 1e6
+    }
+
+    pub fn as_ghz(&self) -> f64 {
+        self.0 as f64 / 1e9
+    }
+
+    /// Parses a decimal like `3400.123` given in units of `10^exponent` Hz.
+    /// Digits finer than 1 Hz are dropped.
+    pub(crate) fn from_decimal(number: &str, exponent: u32) -> Option<Self> {
+        let (whole, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if whole.is_empty() && fraction.is_empty()
+            || !whole
+                .bytes()
+                .chain(fraction.bytes())
+                .all(|b| b.is_ascii_digit())
+        {
+            return None;
+        }
+        let scale = 10u64.pow(exponent);
+        let mut hz = if whole.is_empty() {
+            0
+        } else {
+            whole.parse::<u64>().ok()?.checked_mul(scale)?
+        };
+        let mut place = scale;
+        for digit in fraction.bytes().take(exponent as usize) {
+            place /= 10;
+            hz = hz.checked_add(u64::from(digit - b'0') * place)?;
+        }
+        Some(Self(hz))
+    }
+}
+
+impl FromStr for Frequency {
+    type Err = ParseFrequencyError;
+
+    /// Parses a number and a unit, e.g. `4.00GHz`, `800000 kHz` or
+    /// `3400.000 MHz`. The unit is not case sensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseFrequencyError(s.to_string());
+        let text = s.trim();
+        let lower = text.to_ascii_lowercase();
+        let &(unit, exponent) = UNITS
+            .iter()
+            .find(|(unit, _)| lower.ends_with(unit))
+            .ok_or_else(invalid)?;
+        let number = text[..text.len() - unit.len()].trim_end();
+        Self::from_decimal(number, exponent).ok_or_else(invalid)
+    }
+}
+
+impl fmt::Display for Frequency {
+    /// Uses the largest unit the value reaches, e.g. `3.40 GHz` or
+    /// `800 MHz`. A precision, as in `{:.3}`, overrides the default of two
+    /// decimals for GHz and none otherwise.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (value, unit, decimals) = match self.0 {
+            hz if hz >= 1_000_000_000 => (self.as_ghz(), "GHz", 2),
+            hz if hz >= 1_000_000 => (self.as_mhz(), "MHz", 0),
+            hz if hz >= 1_000 => (self.as_khz(), "kHz", 0),
+            hz => (hz as f64, "Hz", 0),
+        };
+        write!(
+            f,
+            "{:.*} {}",
+            f.precision().unwrap_or(decimals),
+            value,
+            unit
+        )
+    }
+}
+
+/// Serialized as a number of MHz, like the `cpu MHz` field.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Frequency {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.as_mhz())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Frequency {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        f64::deserialize(deserializer).map(Self::from_mhz)
+    }
+}
+
+impl Cpu<'_> {
+    /// The frequency in the model name, e.g. 4 GHz for `Intel(R) Core(TM)
+    /// i7-6700K CPU @ 4.00GHz`. AMD model names carry none.
+    pub fn nominal_frequency(&self) -> Option<Frequency> {
+        let (_, rated) = self.model_name.rsplit_once('@')?;
+        rated.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+    use alloc::format;
+
+    #[test]
+    fn converts_units() {
+        let frequency = Frequency::from_khz(3_400_123);
+        assert_eq!(frequency.as_hz(), 3_400_123_000);
+        assert_eq!(frequency.as_mhz(), 3400.123);
+        assert_eq!(frequency, Frequency::from_mhz(3400.123));
+        assert_eq!(Frequency::from_ghz(4.2), Frequency::from_mhz(4200.0));
+        assert!(Frequency::from_mhz(800.0) < Frequency::from_ghz(1.0));
+    }
+
+    #[test]
+    fn parses_units() {
+        let parse = |s: &str| s.parse::<Frequency>().map(|f| f.as_hz());
+        assert_eq!(parse("4.00GHz"), Ok(4_000_000_000));
+        assert_eq!(parse("800000 kHz"), Ok(800_000_000));
+        assert_eq!(parse("2445.406 mhz"), Ok(2_445_406_000));
+        assert_eq!(parse("100Hz"), Ok(100));
+        assert_eq!(parse("0.0000015 MHz"), Ok(1));
+        assert_eq!(parse(".5 GHz"), Ok(500_000_000));
+        assert!(parse("4.00").is_err());
+        assert!(parse("GHz").is_err());
+        assert!(parse("-1 MHz").is_err());
+        assert!(parse("1e3 MHz").is_err());
+        assert_eq!(
+            "fast".parse::<Frequency>().unwrap_err().to_string(),
+            "invalid frequency `fast`"
+        );
+    }
+
+    #[test]
+    fn displays_in_the_largest_unit() {
+        assert_eq!(Frequency::from_mhz(3400.0).to_string(), "3.40 GHz");
+        assert_eq!(Frequency::from_mhz(800.4).to_string(), "800 MHz");
+        assert_eq!(
+            format!("{:.3}", Frequency::from_mhz(800.004)),
+            "800.004 MHz"
+        );
+        assert_eq!(Frequency::from_khz(32).to_string(), "32 kHz");
+        assert_eq!(Frequency::default().to_string(), "0 Hz");
+    }
+
+    #[test]
+    fn reads_model_name_frequency() {
+        let nominal = |input| cpuinfo(input).unwrap().cpus[0].nominal_frequency();
+        assert_eq!(nominal(SKYLAKE), Some(Frequency::from_ghz(4.0)));
+        assert_eq!(nominal(EPYC), None);
+    }
+}
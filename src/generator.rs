@@ -0,0 +1,310 @@
+use alloc::vec::Vec;
+
+use crate::{AddressSizes, CacheSize, CpuBuilder, CpuInfo, CpuInfoBuilder, Frequency, TlbSize};
+
+const X86_64: &str = "fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 \
+                      clflush mmx fxsr sse sse2 ht syscall nx pdpe1gb rdtscp lm constant_tsc \
+                      rep_good nopl nonstop_tsc cpuid pni pclmulqdq ssse3 fma cx16 pcid sse4_1 \
+                      sse4_2 movbe popcnt aes xsave avx f16c rdrand lahf_lm abm fsgsbase bmi1 \
+                      avx2 smep bmi2 erms invpcid rdseed adx smap clflushopt xsaveopt xsavec \
+                      xgetbv1 xsaves arat";
+
+/// A processor line whose cpuinfo the [`Generator`] imitates.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Profile {
+    /// Xeon Scalable, 1st generation.
+    SkylakeServer,
+    /// Xeon Scalable, 3rd generation.
+    IceLakeServer,
+    /// EPYC 7003 (Milan).
+    Zen3,
+    /// EPYC 9004 (Genoa).
+    Zen4,
+}
+
+struct Part {
+    vendor_id: &'static str,
+    cpu_family: u32,
+    model: u32,
+    model_name: &'static str,
+    stepping: u32,
+    microcode: u64,
+    mhz: f64,
+    /// Per-core L2 (AMD) or per-socket L3 (Intel) in KiB, as the kernel
+    /// reports it.
+    cache_kb: u64,
+    cpuid_level: u32,
+    flags: &'static str,
+    virtualization: &'static str,
+    bugs: &'static str,
+    address_sizes: (u32, u32),
+    power_management: &'static str,
+}
+
+impl Profile {
+    fn part(self) -> Part {
+        match self {
+            Self::SkylakeServer => Part {
+                vendor_id: "GenuineIntel",
+                cpu_family: 6,
+                model: 0x55,
+                model_name: "Intel(R) Xeon(R) Gold 6148 CPU @ 2.40GHz",
+                stepping: 4,
+                microcode: 0x2006e05,
+                mhz: 2400.0,
+                cache_kb: 28160,
+                cpuid_level: 22,
+                flags: "vmx est tm2 xtpr pdcm dca x2apic tsc_deadline_timer 3dnowprefetch \
+                        cpuid_fault epb cat_l3 cdp_l3 pti intel_ppin ssbd mba ibrs ibpb stibp \
+                        tpr_shadow flexpriority ept vpid ept_ad hle rtm cqm mpx rdt_a avx512f \
+                        avx512dq avx512cd avx512bw avx512vl clwb intel_pt cqm_llc cqm_occup_llc \
+                        dtherm ida pln pts hwp hwp_act_window hwp_epp hwp_pkg_req pku ospke \
+                        md_clear flush_l1d arch_capabilities",
+                virtualization: "vnmi preemption_timer invvpid ept_x_only ept_ad ept_1gb \
+                                 flexpriority tsc_offset vtpr mtf vapic ept vpid \
+                                 unrestricted_guest vapic_reg vid ple shadow_vmcs pml",
+                bugs: "spectre_v1 spectre_v2 spec_store_bypass swapgs taa itlb_multihit mmio_stale_data retbleed gds",
+                address_sizes: (46, 48),
+                power_management: "",
+            },
+            Self::IceLakeServer => Part {
+                vendor_id: "GenuineIntel",
+                cpu_family: 6,
+                model: 0x6a,
+                model_name: "Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz",
+                stepping: 6,
+                microcode: 0xd0003a5,
+                mhz: 2000.0,
+                cache_kb: 49152,
+                cpuid_level: 27,
+                flags: "vmx est tm2 xtpr pdcm dca x2apic tsc_deadline_timer 3dnowprefetch \
+                        cpuid_fault epb cat_l3 invpcid_single intel_ppin ssbd mba ibrs ibpb \
+                        stibp ibrs_enhanced tpr_shadow flexpriority ept vpid ept_ad rdt_a \
+                        avx512f avx512dq avx512ifma avx512cd sha_ni avx512bw avx512vl clwb \
+                        intel_pt wbnoinvd dtherm ida pln pts hwp hwp_act_window hwp_epp \
+                        hwp_pkg_req avx512vbmi umip pku ospke avx512_vbmi2 gfni vaes \
+                        vpclmulqdq avx512_vnni avx512_bitalg tme avx512_vpopcntdq la57 rdpid \
+                        fsrm md_clear pconfig flush_l1d arch_capabilities",
+                virtualization: "vnmi preemption_timer posted_intr invvpid ept_x_only ept_ad \
+                                 ept_1gb flexpriority apicv tsc_offset vtpr mtf vapic ept vpid \
+                                 unrestricted_guest vapic_reg vid ple shadow_vmcs pml \
+                                 ept_mode_based_exec tsc_scaling",
+                bugs: "spectre_v1 spectre_v2 spec_store_bypass swapgs mmio_stale_data eibrs_pbrsb gds bhi",
+                address_sizes: (46, 57),
+                power_management: "",
+            },
+            Self::Zen3 => Part {
+                vendor_id: "AuthenticAMD",
+                cpu_family: 0x19,
+                model: 0x01,
+                model_name: "AMD EPYC 7763 64-Core Processor",
+                stepping: 1,
+                microcode: 0xa0011d1,
+                mhz: 2450.0,
+                cache_kb: 512,
+                cpuid_level: 16,
+                flags: "svm mmxext fxsr_opt extd_apicid aperfmperf rapl cmp_legacy extapic \
+                        cr8_legacy sse4a misalignsse 3dnowprefetch osvw ibs skinit wdt tce \
+                        topoext perfctr_core perfctr_nb bpext perfctr_llc mwaitx cpb cat_l3 \
+                        cdp_l3 invpcid_single hw_pstate ssbd mba ibrs ibpb stibp vmmcall \
+                        sha_ni clwb clzero irperf xsaveerptr rdpru wbnoinvd amd_ppin \
+                        cppc npt lbrv svm_lock nrip_save tsc_scale vmcb_clean flushbyasid \
+                        decodeassists pausefilter pfthreshold avic v_vmsave_vmload vgif \
+                        v_spec_ctrl umip pku ospke vaes vpclmulqdq rdpid overflow_recov \
+                        succor smca fsrm",
+                virtualization: "npt lbrv svm_lock nrip_save tsc_scale vmcb_clean \
+                                 flushbyasid decodeassists pausefilter pfthreshold avic \
+                                 v_vmsave_vmload vgif v_spec_ctrl",
+                bugs: "sysret_ss_attrs spectre_v1 spectre_v2 spec_store_bypass srso",
+                address_sizes: (48, 48),
+                power_management: "ts ttp tm hwpstate cpb eff_freq_ro [13] [14]",
+            },
+            Self::Zen4 => Part {
+                vendor_id: "AuthenticAMD",
+                cpu_family: 0x19,
+                model: 0x11,
+                model_name: "AMD EPYC 9654 96-Core Processor",
+                stepping: 1,
+                microcode: 0xa10113e,
+                mhz: 2400.0,
+                cache_kb: 1024,
+                cpuid_level: 16,
+                flags: "svm mmxext fxsr_opt extd_apicid aperfmperf rapl cmp_legacy extapic \
+                        cr8_legacy sse4a misalignsse 3dnowprefetch osvw ibs skinit wdt tce \
+                        topoext perfctr_core perfctr_nb bpext perfctr_llc mwaitx cpb cat_l3 \
+                        cdp_l3 invpcid_single hw_pstate ssbd mba perfmon_v2 ibrs ibpb stibp \
+                        ibrs_enhanced vmmcall avx512f avx512dq avx512ifma sha_ni clwb \
+                        avx512cd avx512bw avx512vl avx512_bf16 clzero irperf xsaveerptr \
+                        rdpru wbnoinvd amd_ppin cppc npt lbrv svm_lock nrip_save tsc_scale \
+                        vmcb_clean flushbyasid decodeassists pausefilter pfthreshold avic \
+                        v_vmsave_vmload vgif x2avic v_spec_ctrl vnmi avx512vbmi umip pku \
+                        ospke avx512_vbmi2 gfni vaes vpclmulqdq avx512_vnni avx512_bitalg \
+                        avx512_vpopcntdq la57 rdpid overflow_recov succor smca fsrm flush_l1d",
+                virtualization: "npt lbrv svm_lock nrip_save tsc_scale vmcb_clean \
+                                 flushbyasid decodeassists pausefilter pfthreshold avic \
+                                 v_vmsave_vmload vgif x2avic v_spec_ctrl vnmi",
+                bugs: "sysret_ss_attrs spectre_v1 spectre_v2 spec_store_bypass srso",
+                address_sizes: (52, 57),
+                power_management: "ts ttp tm hwpstate cpb eff_freq_ro [13] [14]",
+            },
+        }
+    }
+}
+
+/// Bits needed to number `count` items, as used to lay out APIC ids.
+fn bits(count: u32) -> u32 {
+    u32::BITS - count.saturating_sub(1).leading_zeros()
+}
+
+/// Produces realistic cpuinfo for machines of any size, e.g. to test
+/// schedulers against hardware one does not have.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Generator {
+    profile: Profile,
+    sockets: u32,
+    cores_per_socket: u32,
+    threads_per_core: u32,
+}
+
+impl Generator {
+    /// A single core, two thread machine of `profile`.
+    pub fn new(profile: Profile) -> Self {
+        Self {
+            profile,
+            sockets: 1,
+            cores_per_socket: 1,
+            threads_per_core: 2,
+        }
+    }
+
+    pub fn sockets(mut self, sockets: u32) -> Self {
+        self.sockets = sockets;
+        self
+    }
+
+    pub fn cores_per_socket(mut self, cores: u32) -> Self {
+        self.cores_per_socket = cores;
+        self
+    }
+
+    pub fn threads_per_core(mut self, threads: u32) -> Self {
+        self.threads_per_core = threads;
+        self
+    }
+
+    /// Lists cpus the way Linux numbers them: the first thread of every
+    /// core, socket by socket, then the second threads, and so on.
+    pub fn build(&self) -> CpuInfo<'static> {
+        let part = self.profile.part();
+        let (flags, vmx_flags, svm_flags) = {
+            let flags: Vec<&str> = X86_64
+                .split_whitespace()
+                .chain(part.flags.split_whitespace())
+                .collect();
+            let virtualization = part.virtualization.split_whitespace().collect();
+            if part.vendor_id == "GenuineIntel" {
+                (flags, virtualization, Vec::new())
+            } else {
+                (flags, Vec::new(), virtualization)
+            }
+        };
+        let template = CpuBuilder::new()
+            .vendor_id(part.vendor_id)
+            .cpu_family(part.cpu_family)
+            .model(part.model)
+            .model_name(part.model_name)
+            .stepping(Some(part.stepping))
+            .microcode(Some(part.microcode))
+            .cpu_mhz(Frequency::from_mhz(part.mhz))
+            .cache_size(Some(CacheSize::from_kib(part.cache_kb)))
+            .siblings(self.cores_per_socket * self.threads_per_core)
+            .cpu_cores(self.cores_per_socket)
+            .cpuid_level(part.cpuid_level)
+            .flags(flags)
+            .vmx_flags(vmx_flags)
+            .svm_flags(svm_flags)
+            .bugs(part.bugs.split_whitespace().collect())
+            .bogomips(part.mhz * 2.0)
+            .tlb_size((part.vendor_id == "AuthenticAMD").then_some(TlbSize {
+                entries: 3584,
+                page_size: 4 << 10,
+            }))
+            .address_sizes(AddressSizes {
+                physical_size: part.address_sizes.0,
+                virtual_size: part.address_sizes.1,
+            })
+            .power_management(part.power_management.split_whitespace().collect());
+
+        let thread_bits = bits(self.threads_per_core);
+        let socket_bits = thread_bits + bits(self.cores_per_socket);
+        let mut builder = CpuInfoBuilder::new();
+        let mut processor = 0;
+        for thread in 0..self.threads_per_core {
+            for socket in 0..self.sockets {
+                for core in 0..self.cores_per_socket {
+                    let apicid = socket << socket_bits | core << thread_bits | thread;
+                    let cpu = template
+                        .clone()
+                        .processor(processor)
+                        .physical_id(Some(socket))
+                        .core_id(core)
+                        .apicid(apicid)
+                        .initial_apicid(apicid)
+                        .build();
+                    builder = builder.cpu(cpu);
+                    processor += 1;
+                }
+            }
+        }
+        builder.build()
+    }
+
+    /// The `/proc/cpuinfo` text of the generated machine.
+    pub fn to_proc_string(&self) -> alloc::string::String {
+        self.build().to_proc_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpuinfo;
+
+    #[test]
+    fn generates_parseable_machines() {
+        for profile in [
+            Profile::SkylakeServer,
+            Profile::IceLakeServer,
+            Profile::Zen3,
+            Profile::Zen4,
+        ] {
+            let generator = Generator::new(profile).sockets(2).cores_per_socket(4);
+            let text = generator.to_proc_string();
+            let parsed = cpuinfo(&text).unwrap();
+            assert_eq!(parsed, generator.build());
+
+            let topology = parsed.topology();
+            assert_eq!(topology.sockets, 2);
+            assert_eq!(topology.cores_per_socket, 4);
+            assert_eq!(topology.listed_threads_per_core, 2);
+            assert!(parsed[0].microarchitecture().is_some(), "{:?}", profile);
+        }
+    }
+
+    #[test]
+    fn numbers_cpus_like_linux() {
+        let cpuinfo = Generator::new(Profile::Zen4)
+            .sockets(2)
+            .cores_per_socket(3)
+            .build();
+        assert_eq!(cpuinfo.len(), 12);
+
+        let cpu = &cpuinfo[7];
+        assert_eq!((cpu.physical_id, cpu.core_id), (Some(0), 1));
+        // Socket, core and thread bits.
+        assert_eq!(cpu.apicid, 0b0011);
+        assert_eq!(cpuinfo[5].apicid, 0b1100);
+        assert_eq!(cpu.siblings, 6);
+        assert!(cpu.has_flag("avx512f") && cpu.svm_flags.contains(&"x2avic"));
+    }
+}
@@ -0,0 +1,95 @@
+use alloc::{vec, vec::Vec};
+use core::fmt;
+
+use crate::{Cpu, CpuInfo};
+
+/// Cpus that differ only in where they sit, e.g. the 32 identical threads of
+/// one socket.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuGroup<'a> {
+    /// Members in the order they were listed. Never empty.
+    pub cpus: Vec<&'a Cpu<'a>>,
+}
+
+impl<'a> CpuGroup<'a> {
+    /// The first member, standing in for the group.
+    pub fn cpu(&self) -> &'a Cpu<'a> {
+        self.cpus[0]
+    }
+
+    pub fn count(&self) -> usize {
+        self.cpus.len()
+    }
+
+    pub fn processors(&self) -> Vec<u32> {
+        self.cpus.iter().map(|cpu| cpu.processor).collect()
+    }
+}
+
+impl fmt::Display for CpuGroup<'_> {
+    /// Prints e.g. `32x Intel(R) Xeon(R) Gold 6338 CPU @ 2.00GHz`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x {}", self.count(), self.cpu().model_name)
+    }
+}
+
+/// Whether two cpus are the same part with the same features, ignoring
+/// topology, clocks and other per-thread fields.
+fn same_kind(a: &Cpu, b: &Cpu) -> bool {
+    a.vendor_id == b.vendor_id
+        && a.cpu_family == b.cpu_family
+        && a.model == b.model
+        && a.stepping == b.stepping
+        && a.model_name == b.model_name
+        && a.microcode == b.microcode
+        && a.cache_size == b.cache_size
+        && a.flags == b.flags
+        && a.bugs == b.bugs
+}
+
+impl CpuInfo<'_> {
+    /// Collapses identical cpus into groups, ordered by their first member.
+    pub fn groups(&self) -> Vec<CpuGroup<'_>> {
+        let mut groups: Vec<CpuGroup> = Vec::new();
+        for cpu in &self.cpus {
+            match groups.iter_mut().find(|group| same_kind(group.cpu(), cpu)) {
+                Some(group) => group.cpus.push(cpu),
+                None => groups.push(CpuGroup { cpus: vec![cpu] }),
+            }
+        }
+        groups
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString};
+
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+
+    #[test]
+    fn groups_identical_cpus() {
+        // Clocks differ between threads but do not split a group.
+        let mut input = format!("{}\n", EPYC);
+        for (processor, mhz) in [(0, "4000.000"), (1, "800.000"), (2, "4200.000")] {
+            let skylake = SKYLAKE
+                .replace("processor\t: 0", &format!("processor\t: {}", processor))
+                .replace("4000.000", mhz);
+            input.push_str(&format!("{}\n", skylake));
+        }
+        let cpuinfo = cpuinfo(&input).unwrap();
+
+        let groups = cpuinfo.groups();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].processors(), [3]);
+        assert_eq!(groups[1].processors(), [0, 1, 2]);
+        assert_eq!(
+            groups[1].to_string(),
+            "3x Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz"
+        );
+        assert_eq!(groups[0].cpu().model, 1);
+    }
+}
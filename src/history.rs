@@ -0,0 +1,172 @@
+use std::{
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use crate::{CpuInfoOwned, Result};
+
+/// A cpuinfo snapshot with the time it was taken.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct Snapshot {
+    pub taken_at: SystemTime,
+    pub info: CpuInfoOwned,
+}
+
+impl Snapshot {
+    /// Stamps `info` with the current time.
+    pub fn new(info: CpuInfoOwned) -> Self {
+        Self {
+            taken_at: SystemTime::now(),
+            info,
+        }
+    }
+}
+
+/// An append-only history of snapshots, stored as one TOML file per
+/// snapshot named after the time it was taken.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SnapshotStore {
+    dir: PathBuf,
+}
+
+impl SnapshotStore {
+    /// Opens the store in `dir`, creating the directory if needed.
+    pub fn open<P: Into<PathBuf>>(dir: P) -> Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn path(&self, taken_at: SystemTime) -> PathBuf {
+        let since_epoch = taken_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        self.dir.join(format!(
+            "{:012}.{:09}.toml",
+            since_epoch.as_secs(),
+            since_epoch.subsec_nanos()
+        ))
+    }
+
+    /// Writes `snapshot`, failing if one taken at the same time exists.
+    pub fn append(&self, snapshot: &Snapshot) -> Result<PathBuf> {
+        let path = self.path(snapshot.taken_at);
+        let toml = toml::to_string(snapshot)?;
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .and_then(|mut file| file.write_all(toml.as_bytes()))?;
+        Ok(path)
+    }
+
+    /// The times of all stored snapshots, oldest first. Files not written
+    /// by the store are ignored.
+    pub fn list(&self) -> Result<Vec<SystemTime>> {
+        let mut times = Vec::new();
+        for entry in fs::read_dir(&self.dir)? {
+            let name = entry?.file_name();
+            if let Some(taken_at) = name.to_str().and_then(parse_name) {
+                times.push(taken_at);
+            }
+        }
+        times.sort();
+        Ok(times)
+    }
+
+    pub fn load(&self, taken_at: SystemTime) -> Result<Snapshot> {
+        let mut snapshot: Snapshot = toml::from_str(&fs::read_to_string(self.path(taken_at))?)?;
+        snapshot.info.intern();
+        Ok(snapshot)
+    }
+
+    pub fn latest(&self) -> Result<Option<Snapshot>> {
+        self.list()?
+            .pop()
+            .map(|taken_at| self.load(taken_at))
+            .transpose()
+    }
+
+    /// Removes all but the `keep` most recent snapshots, returning how many
+    /// were removed.
+    pub fn prune(&self, keep: usize) -> Result<usize> {
+        let times = self.list()?;
+        let remove = times.len().saturating_sub(keep);
+        for &taken_at in &times[..remove] {
+            fs::remove_file(self.path(taken_at))?;
+        }
+        Ok(remove)
+    }
+
+    /// Removes the snapshots taken before `cutoff`.
+    pub fn prune_before(&self, cutoff: SystemTime) -> Result<usize> {
+        let keep = self.list()?.iter().filter(|&&t| t >= cutoff).count();
+        self.prune(keep)
+    }
+}
+
+fn parse_name(name: &str) -> Option<SystemTime> {
+    let (secs, nanos) = name.strip_suffix(".toml")?.split_once('.')?;
+    let since_epoch = Duration::new(secs.parse().ok()?, nanos.parse().ok()?);
+    SystemTime::UNIX_EPOCH.checked_add(since_epoch)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{FakeSysfs, EPYC, SKYLAKE},
+    };
+
+    fn at(secs: u64) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_millis(secs * 1000 + 250)
+    }
+
+    fn snapshot(input: &str, secs: u64) -> Snapshot {
+        Snapshot {
+            taken_at: at(secs),
+            info: cpuinfo(input).unwrap().to_owned(),
+        }
+    }
+
+    #[test]
+    fn keeps_a_history() {
+        let fake = FakeSysfs::new("history", &[("history/README", "not a snapshot")]);
+        let store = SnapshotStore::open(fake.root.join("history")).unwrap();
+        assert_eq!(store.latest().unwrap().map(|s| s.taken_at), None);
+
+        let path = store.append(&snapshot(SKYLAKE, 20)).unwrap();
+        assert_eq!(path.file_name().unwrap(), "000000000020.250000000.toml");
+        store.append(&snapshot(EPYC, 30)).unwrap();
+        store.append(&snapshot(SKYLAKE, 10)).unwrap();
+        assert!(store.append(&snapshot(EPYC, 10)).is_err());
+
+        assert_eq!(store.list().unwrap(), [at(10), at(20), at(30)]);
+        let latest = store.latest().unwrap().unwrap();
+        assert_eq!(latest.taken_at, at(30));
+        assert_eq!(latest.info.cpus[0].processor, 3);
+        assert_eq!(store.load(at(20)).unwrap().info.cpus[0].model, 94);
+    }
+
+    #[test]
+    fn prunes_old_snapshots() {
+        let fake = FakeSysfs::new("history-prune", &[]);
+        let store = SnapshotStore::open(fake.root.join("history")).unwrap();
+        for secs in 1..=5 {
+            store.append(&snapshot(SKYLAKE, secs)).unwrap();
+        }
+
+        assert_eq!(store.prune_before(at(2)).unwrap(), 1);
+        assert_eq!(store.prune(2).unwrap(), 2);
+        assert_eq!(store.list().unwrap(), [at(4), at(5)]);
+        assert_eq!(store.prune(5).unwrap(), 0);
+        assert!(store.load(at(1)).is_err());
+    }
+}
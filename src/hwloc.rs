@@ -0,0 +1,475 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+use crate::{report::escape, CpuInfo};
+#[cfg(feature = "std")]
+use crate::{CacheType, NumaTopology, Result, Sysfs};
+
+/// One hwloc object before it is placed in the tree.
+struct Object {
+    kind: String,
+    os_index: Option<u32>,
+    /// Sorted logical cpus.
+    cpus: Vec<u32>,
+    attributes: Vec<(&'static str, String)>,
+    infos: Vec<(&'static str, String)>,
+}
+
+impl Object {
+    fn new(kind: &str, os_index: Option<u32>, mut cpus: Vec<u32>) -> Self {
+        cpus.sort_unstable();
+        cpus.dedup();
+        Self {
+            kind: kind.to_string(),
+            os_index,
+            cpus,
+            attributes: Vec::new(),
+            infos: Vec::new(),
+        }
+    }
+
+    fn contains(&self, other: &Object) -> bool {
+        other
+            .cpus
+            .iter()
+            .all(|cpu| self.cpus.binary_search(cpu).is_ok())
+    }
+}
+
+/// What sysfs adds to the cpuinfo-derived tree.
+#[derive(Default)]
+struct Extra {
+    /// Processor to die id, left empty unless a package has several dies.
+    dies: Vec<(u32, u32)>,
+    /// Caches, outermost first.
+    caches: Vec<Object>,
+    /// NUMA nodes; `None` counts every cpu as node 0.
+    nodes: Option<Vec<Object>>,
+}
+
+/// A bitmap in hwloc's format: 32-bit hex words, most significant first,
+/// e.g. `0x00000001,0x000000ff` for cpus 0-7 and 32.
+fn bitmap(bits: &[u32]) -> String {
+    let words = bits.iter().max().map_or(1, |&max| max as usize / 32 + 1);
+    let mut mask = vec![0u32; words];
+    for &bit in bits {
+        mask[bit as usize / 32] |= 1 << (bit % 32);
+    }
+    mask.iter()
+        .rev()
+        .map(|word| format!("0x{:08x}", word))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl CpuInfo<'_> {
+    /// The topology as an hwloc 2 XML document, as written by `lstopo
+    /// topology.xml`, for tools that load hwloc topologies. Only packages,
+    /// cores and PUs are known from cpuinfo alone.
+    pub fn to_hwloc_xml(&self) -> String {
+        self.hwloc_xml(Extra::default())
+    }
+
+    /// Like [`to_hwloc_xml`](Self::to_hwloc_xml), adding dies, caches and
+    /// NUMA nodes from sysfs.
+    #[cfg(feature = "std")]
+    pub fn to_hwloc_xml_with(&self, sysfs: &Sysfs) -> Result<String> {
+        let mut extra = Extra::default();
+
+        let mut packages: Vec<(u32, u32)> = Vec::new();
+        for cpu in &self.cpus {
+            let die_id = sysfs.parse(Sysfs::cpu_path(cpu.processor, "topology/die_id"));
+            if let Some(die_id) = die_id {
+                extra.dies.push((cpu.processor, die_id));
+                packages.push((cpu.physical_id.unwrap_or_default(), die_id));
+            }
+        }
+        packages.sort_unstable();
+        packages.dedup();
+        if !packages.windows(2).any(|pair| pair[0].0 == pair[1].0) {
+            extra.dies.clear();
+        }
+
+        let mut caches: Vec<_> = self
+            .cache_hierarchies(sysfs)?
+            .into_iter()
+            .flat_map(|hierarchy| hierarchy.caches)
+            .collect();
+        caches.sort_by(|a, b| {
+            (b.level, a.cache_type as u8, &a.shared_cpus).cmp(&(
+                a.level,
+                b.cache_type as u8,
+                &b.shared_cpus,
+            ))
+        });
+        caches.dedup();
+        extra.caches = caches
+            .into_iter()
+            .map(|cache| {
+                let (suffix, cache_type) = match cache.cache_type {
+                    CacheType::Unified => ("", 0),
+                    CacheType::Data => ("", 1),
+                    CacheType::Instruction => ("i", 2),
+                };
+                let mut object = Object::new(
+                    &format!("L{}{}Cache", cache.level, suffix),
+                    None,
+                    cache.shared_cpus,
+                );
+                object.attributes = vec![
+                    ("cache_size", cache.size.as_bytes().to_string()),
+                    ("depth", cache.level.to_string()),
+                    ("cache_type", cache_type.to_string()),
+                ];
+                object
+            })
+            .collect();
+
+        extra.nodes = Some(
+            NumaTopology::read(sysfs)?
+                .nodes
+                .into_iter()
+                .map(|node| {
+                    let mut object = Object::new("NUMANode", Some(node.id), node.cpus);
+                    if let Some(memory) = node.memory_total {
+                        object.attributes.push(("local_memory", memory.to_string()));
+                    }
+                    object
+                })
+                .collect(),
+        );
+
+        Ok(self.hwloc_xml(extra))
+    }
+
+    fn hwloc_xml(&self, extra: Extra) -> String {
+        let topology = self.topology();
+        let mut machine = Object::new(
+            "Machine",
+            Some(0),
+            topology.cpus.iter().map(|cpu| cpu.processor).collect(),
+        );
+        machine.infos.push(("Backend", "Linux".to_string()));
+
+        // Objects in the order they nest when their cpus are the same.
+        let mut objects = vec![machine];
+        let mut sockets: Vec<u32> = topology.cpus.iter().map(|cpu| cpu.socket).collect();
+        sockets.sort_unstable();
+        sockets.dedup();
+        for socket in sockets {
+            let cpus = topology
+                .cpus
+                .iter()
+                .filter(|cpu| cpu.socket == socket)
+                .map(|cpu| cpu.processor)
+                .collect();
+            let mut package = Object::new("Package", Some(socket), cpus);
+            if let Some(cpu) = self
+                .cpus
+                .iter()
+                .find(|cpu| cpu.physical_id.unwrap_or_default() == socket)
+            {
+                package.infos = vec![
+                    ("CPUVendor", cpu.vendor_id.to_string()),
+                    ("CPUFamilyNumber", cpu.cpu_family.to_string()),
+                    ("CPUModelNumber", cpu.model.to_string()),
+                    ("CPUModel", cpu.model_name.to_string()),
+                ];
+                if let Some(stepping) = cpu.stepping {
+                    package.infos.push(("CPUStepping", stepping.to_string()));
+                }
+            }
+            objects.push(package);
+        }
+        let mut dies: Vec<(u32, u32)> = extra
+            .dies
+            .iter()
+            .filter_map(|&(processor, die)| Some((topology.placement(processor)?.socket, die)))
+            .collect();
+        dies.sort_unstable();
+        dies.dedup();
+        for (socket, die) in dies {
+            let cpus = extra
+                .dies
+                .iter()
+                .filter(|&&(processor, d)| {
+                    d == die && topology.placement(processor).map(|p| p.socket) == Some(socket)
+                })
+                .map(|&(processor, _)| processor)
+                .collect();
+            objects.push(Object::new("Die", Some(die), cpus));
+        }
+        objects.extend(extra.caches);
+        let mut cores: Vec<(u32, u32)> = topology
+            .cpus
+            .iter()
+            .map(|cpu| (cpu.socket, cpu.core))
+            .collect();
+        cores.sort_unstable();
+        cores.dedup();
+        for (socket, core) in cores {
+            let cpus = topology
+                .cpus
+                .iter()
+                .filter(|cpu| (cpu.socket, cpu.core) == (socket, core))
+                .map(|cpu| cpu.processor)
+                .collect();
+            objects.push(Object::new("Core", Some(core), cpus));
+        }
+        let mut processors: Vec<u32> = topology.cpus.iter().map(|cpu| cpu.processor).collect();
+        processors.sort_unstable();
+        objects.extend(
+            processors
+                .into_iter()
+                .map(|processor| Object::new("PU", Some(processor), vec![processor])),
+        );
+
+        // Each object goes under the smallest earlier one holding its cpus,
+        // the last such one on ties.
+        let mut parents = vec![None];
+        for (i, object) in objects.iter().enumerate().skip(1) {
+            let parent = (0..i)
+                .filter(|&p| objects[p].contains(object))
+                .min_by_key(|&p| (objects[p].cpus.len(), usize::MAX - p));
+            parents.push(parent);
+        }
+        let nodes = extra
+            .nodes
+            .unwrap_or_else(|| vec![Object::new("NUMANode", Some(0), objects[0].cpus.clone())]);
+        // Memory hangs off the machine, a package or a die.
+        let memory_parent: Vec<usize> = nodes
+            .iter()
+            .map(|node| {
+                (0..objects.len())
+                    .filter(|&p| matches!(objects[p].kind.as_str(), "Machine" | "Package" | "Die"))
+                    .filter(|&p| objects[p].contains(node))
+                    .min_by_key(|&p| (objects[p].cpus.len(), usize::MAX - p))
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let mut writer = Writer {
+            out: String::from(
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+                 <!DOCTYPE topology SYSTEM \"hwloc2.dtd\">\n\
+                 <topology version=\"2.0\">\n",
+            ),
+            objects: &objects,
+            parents: &parents,
+            nodes: &nodes,
+            memory_parent: &memory_parent,
+            gp_index: 0,
+        };
+        writer.object(0, 1);
+        writer.out.push_str("</topology>\n");
+        writer.out
+    }
+}
+
+struct Writer<'a> {
+    out: String,
+    objects: &'a [Object],
+    parents: &'a [Option<usize>],
+    nodes: &'a [Object],
+    memory_parent: &'a [usize],
+    gp_index: u32,
+}
+
+impl Writer<'_> {
+    fn nodeset(&self, object: &Object) -> String {
+        let ids: Vec<u32> = self
+            .nodes
+            .iter()
+            .filter(|node| {
+                node.cpus.iter().any(|cpu| object.cpus.contains(cpu))
+                    || object.kind == "Machine"
+                    || core::ptr::eq(*node, object)
+            })
+            .filter_map(|node| node.os_index)
+            .collect();
+        bitmap(&ids)
+    }
+
+    fn open(&mut self, object: &Object, depth: usize) {
+        self.gp_index += 1;
+        let indent = "  ".repeat(depth);
+        let cpuset = bitmap(&object.cpus);
+        let nodeset = self.nodeset(object);
+        let _ = write!(self.out, "{}<object type=\"{}\"", indent, object.kind);
+        if let Some(os_index) = object.os_index {
+            let _ = write!(self.out, " os_index=\"{}\"", os_index);
+        }
+        let _ = write!(
+            self.out,
+            " cpuset=\"{0}\" complete_cpuset=\"{0}\" allowed_cpuset=\"{0}\" \
+             nodeset=\"{1}\" complete_nodeset=\"{1}\" allowed_nodeset=\"{1}\"",
+            cpuset, nodeset
+        );
+        for (name, value) in &object.attributes {
+            let _ = write!(self.out, " {}=\"{}\"", name, escape(value));
+        }
+        let _ = write!(self.out, " gp_index=\"{}\"", self.gp_index);
+    }
+
+    fn object(&mut self, index: usize, depth: usize) {
+        let (objects, nodes) = (self.objects, self.nodes);
+        let object = &objects[index];
+        self.open(object, depth);
+        let indent = "  ".repeat(depth);
+        let memory: Vec<usize> = (0..nodes.len())
+            .filter(|&node| self.memory_parent[node] == index)
+            .collect();
+        let children: Vec<usize> = (0..self.objects.len())
+            .filter(|&child| self.parents[child] == Some(index))
+            .collect();
+        if object.infos.is_empty() && memory.is_empty() && children.is_empty() {
+            self.out.push_str("/>\n");
+            return;
+        }
+
+        self.out.push_str(">\n");
+        for (name, value) in &object.infos {
+            let _ = writeln!(
+                self.out,
+                "{}  <info name=\"{}\" value=\"{}\"/>",
+                indent,
+                name,
+                escape(value)
+            );
+        }
+        for node in memory {
+            self.open(&nodes[node], depth + 1);
+            self.out.push_str("/>\n");
+        }
+        for child in children {
+            self.object(child, depth + 1);
+        }
+        let _ = writeln!(self.out, "{}</object>", indent);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuBuilder, CpuInfoBuilder};
+
+    fn two_sockets() -> CpuInfo<'static> {
+        let mut builder = CpuInfoBuilder::new();
+        for processor in 0..8 {
+            builder = builder.cpu(
+                CpuBuilder::new()
+                    .processor(processor)
+                    .vendor_id("GenuineIntel")
+                    .model_name("Xeon <test>")
+                    .physical_id(Some(processor / 4))
+                    .core_id(processor % 4 / 2)
+                    .build(),
+            );
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn formats_bitmaps() {
+        assert_eq!(bitmap(&[]), "0x00000000");
+        assert_eq!(bitmap(&[0, 1, 2, 3]), "0x0000000f");
+        assert_eq!(bitmap(&[0, 32, 63]), "0x80000001,0x00000001");
+    }
+
+    #[test]
+    fn exports_packages_cores_and_pus() {
+        let xml = two_sockets().to_hwloc_xml();
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+        assert!(xml.contains("<topology version=\"2.0\">\n  <object type=\"Machine\" os_index=\"0\" cpuset=\"0x000000ff\""));
+        assert_eq!(xml.matches("<object type=\"Package\"").count(), 2);
+        assert_eq!(xml.matches("<object type=\"Core\"").count(), 4);
+        assert_eq!(xml.matches("<object type=\"PU\"").count(), 8);
+        assert_eq!(xml.matches("<object type=\"NUMANode\"").count(), 1);
+        assert!(xml.contains("    <object type=\"Package\" os_index=\"1\" cpuset=\"0x000000f0\""));
+        assert!(xml.contains("<info name=\"CPUModel\" value=\"Xeon &lt;test&gt;\"/>"));
+        assert!(xml.contains("      <object type=\"Core\" os_index=\"1\" cpuset=\"0x000000c0\""));
+        assert!(xml.contains("        <object type=\"PU\" os_index=\"7\" cpuset=\"0x00000080\""));
+        assert_eq!(
+            xml.matches("<object").count(),
+            xml.matches("gp_index=").count()
+        );
+        assert!(xml.ends_with("  </object>\n</topology>\n"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn adds_caches_and_numa_from_sysfs() {
+        use crate::fixtures::FakeSysfs;
+
+        let mut files = Vec::new();
+        for cpu in 0..8u32 {
+            let l3 = if cpu < 4 { "0-3" } else { "4-7" };
+            let core = if cpu % 2 == 0 {
+                [cpu, cpu + 1]
+            } else {
+                [cpu - 1, cpu]
+            };
+            let core = format!("{},{}", core[0], core[1]);
+            for (index, level, cache_type, size, shared) in [
+                (0, "1", "Data", "48K", core.clone()),
+                (1, "1", "Instruction", "32K", core.clone()),
+                (2, "2", "Unified", "2048K", core),
+                (3, "3", "Unified", "16384K", l3.to_string()),
+            ] {
+                let dir = format!("devices/system/cpu/cpu{}/cache/index{}", cpu, index);
+                files.push((format!("{}/level", dir), level.to_string()));
+                files.push((format!("{}/type", dir), cache_type.to_string()));
+                files.push((format!("{}/size", dir), size.to_string()));
+                files.push((format!("{}/shared_cpu_list", dir), shared));
+            }
+            files.push((
+                format!("devices/system/cpu/cpu{}/topology/die_id", cpu),
+                "0".to_string(),
+            ));
+        }
+        for (node, cpus) in [(0, "0-3"), (1, "4-7")] {
+            let dir = format!("devices/system/node/node{}", node);
+            files.push((format!("{}/cpulist", dir), cpus.to_string()));
+            files.push((
+                format!("{}/meminfo", dir),
+                format!("Node {} MemTotal:       1024 kB\n", node),
+            ));
+        }
+        let files: Vec<(&str, &str)> = files
+            .iter()
+            .map(|(p, v)| (p.as_str(), v.as_str()))
+            .collect();
+        let fake = FakeSysfs::new("hwloc", &files);
+
+        let xml = two_sockets()
+            .to_hwloc_xml_with(&Sysfs::new(&fake.root))
+            .unwrap();
+        assert_eq!(xml.matches("<object type=\"L3Cache\"").count(), 2);
+        assert_eq!(xml.matches("<object type=\"L2Cache\"").count(), 4);
+        assert_eq!(xml.matches("<object type=\"L1iCache\"").count(), 4);
+        // One die per package is left out, as hwloc does.
+        assert!(!xml.contains("type=\"Die\""));
+        assert!(xml.contains(
+            "      <object type=\"L3Cache\" cpuset=\"0x000000f0\" complete_cpuset=\"0x000000f0\" \
+             allowed_cpuset=\"0x000000f0\" nodeset=\"0x00000002\" complete_nodeset=\"0x00000002\" \
+             allowed_nodeset=\"0x00000002\" cache_size=\"16777216\" depth=\"3\" cache_type=\"0\""
+        ));
+        assert!(
+            xml.contains("      <object type=\"NUMANode\" os_index=\"1\" cpuset=\"0x000000f0\"")
+        );
+        assert!(xml.contains("local_memory=\"1048576\""));
+        // Caches nest outermost first, down to the core and its PUs.
+        let l1i = xml.find("<object type=\"L1iCache\"").unwrap();
+        let l1d = xml.find("<object type=\"L1Cache\"").unwrap();
+        let l2 = xml.find("<object type=\"L2Cache\"").unwrap();
+        let core = xml.find("<object type=\"Core\"").unwrap();
+        assert!(l2 < l1d && l1d < l1i && l1i < core);
+        assert!(xml.contains("              <object type=\"Core\" os_index=\"0\""));
+        assert!(xml.contains("                <object type=\"PU\" os_index=\"1\""));
+    }
+}
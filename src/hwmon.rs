@@ -0,0 +1,174 @@
+use std::path::Path;
+
+use crate::{Placement, Result, Sysfs};
+
+const HWMON: &str = "class/hwmon";
+
+/// Cpu temperatures in degrees Celsius, from the `coretemp` (Intel) and
+/// `k10temp` (AMD) hwmon drivers.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Temperatures {
+    /// `(socket, °C)` pairs. For `k10temp` this is `Tctl`, and sockets are
+    /// numbered in hwmon order since the driver does not report them.
+    pub packages: Vec<(u32, f64)>,
+    /// `(socket, core id, °C)`, only reported by `coretemp`.
+    pub cores: Vec<(u32, u32, f64)>,
+}
+
+impl Temperatures {
+    /// Reads every supported sensor below `class/hwmon`. Machines without
+    /// one, such as most guests, yield no readings rather than an error.
+    pub fn read(sysfs: &Sysfs) -> Result<Self> {
+        let mut temperatures = Self::default();
+        if !sysfs.root().join(HWMON).is_dir() {
+            return Ok(temperatures);
+        }
+
+        let mut k10temp = 0;
+        for id in sysfs.numbered(HWMON, "hwmon")? {
+            let dir = Path::new(HWMON).join(format!("hwmon{}", id));
+            let sensors = sensors(sysfs, &dir)?;
+            match sysfs.read(dir.join("name")).ok().as_deref() {
+                Some("coretemp") => temperatures.add_coretemp(&sensors),
+                Some("k10temp") => {
+                    if let Some((_, celsius)) = sensors.iter().find(|(label, _)| label == "Tctl") {
+                        temperatures.packages.push((k10temp, *celsius));
+                    }
+                    k10temp += 1;
+                }
+                _ => {}
+            }
+        }
+        Ok(temperatures)
+    }
+
+    /// coretemp labels sensors `Package id N` and `Core N`, the latter with
+    /// the core id within that package.
+    fn add_coretemp(&mut self, sensors: &[(String, f64)]) {
+        let package = sensors.iter().find_map(|(label, celsius)| {
+            Some((label.strip_prefix("Package id ")?.parse().ok()?, *celsius))
+        });
+        let Some((socket, celsius)) = package else {
+            return;
+        };
+        self.packages.push((socket, celsius));
+        for (label, celsius) in sensors {
+            if let Some(core) = label.strip_prefix("Core ").and_then(|id| id.parse().ok()) {
+                self.cores.push((socket, core, *celsius));
+            }
+        }
+    }
+
+    pub fn package(&self, socket: u32) -> Option<f64> {
+        self.packages
+            .iter()
+            .find(|(s, _)| *s == socket)
+            .map(|(_, celsius)| *celsius)
+    }
+
+    pub fn core(&self, socket: u32, core: u32) -> Option<f64> {
+        self.cores
+            .iter()
+            .find(|(s, c, _)| (*s, *c) == (socket, core))
+            .map(|(_, _, celsius)| *celsius)
+    }
+
+    /// The temperature of the core a logical cpu runs on, or of its package
+    /// where cores are not reported.
+    pub fn of(&self, placement: &Placement) -> Option<f64> {
+        self.core(placement.socket, placement.core)
+            .or_else(|| self.package(placement.socket))
+    }
+}
+
+/// The labelled `tempN_input` readings of one hwmon device.
+fn sensors(sysfs: &Sysfs, dir: &Path) -> Result<Vec<(String, f64)>> {
+    let mut sensors = Vec::new();
+    for entry in std::fs::read_dir(sysfs.root().join(dir))? {
+        let name = entry?.file_name();
+        let Some(index) = name.to_str().and_then(|name| {
+            name.strip_prefix("temp")?
+                .strip_suffix("_input")?
+                .parse()
+                .ok()
+        }) else {
+            continue;
+        };
+        let Some(millidegrees) = sysfs.parse::<i64, _>(dir.join(format!("temp{}_input", index)))
+        else {
+            continue;
+        };
+        let label = sysfs
+            .read(dir.join(format!("temp{}_label", index)))
+            .unwrap_or_default();
+        sensors.push((index, label, millidegrees as f64 / 1000.0));
+    }
+    sensors.sort_by_key(|&(index, _, _): &(u32, _, _)| index);
+    Ok(sensors
+        .into_iter()
+        .map(|(_, label, celsius)| (label, celsius))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::FakeSysfs;
+
+    #[test]
+    fn reads_coretemp() {
+        let fake = FakeSysfs::new(
+            "hwmon-coretemp",
+            &[
+                ("class/hwmon/hwmon0/name", "acpitz\n"),
+                ("class/hwmon/hwmon0/temp1_input", "27800\n"),
+                ("class/hwmon/hwmon2/name", "coretemp\n"),
+                ("class/hwmon/hwmon2/temp1_label", "Package id 1\n"),
+                ("class/hwmon/hwmon2/temp1_input", "52000\n"),
+                ("class/hwmon/hwmon2/temp2_label", "Core 0\n"),
+                ("class/hwmon/hwmon2/temp2_input", "48000\n"),
+                ("class/hwmon/hwmon2/temp3_label", "Core 4\n"),
+                ("class/hwmon/hwmon2/temp3_input", "51500\n"),
+            ],
+        );
+        let temperatures = Temperatures::read(&Sysfs::new(&fake.root)).unwrap();
+
+        assert_eq!(temperatures.packages, [(1, 52.0)]);
+        assert_eq!(temperatures.core(1, 4), Some(51.5));
+        let placement = |core| Placement {
+            processor: 0,
+            socket: 1,
+            core,
+            thread: 0,
+        };
+        assert_eq!(temperatures.of(&placement(0)), Some(48.0));
+        assert_eq!(temperatures.of(&placement(2)), Some(52.0));
+    }
+
+    #[test]
+    fn reads_k10temp() {
+        let fake = FakeSysfs::new(
+            "hwmon-k10temp",
+            &[
+                ("class/hwmon/hwmon1/name", "k10temp\n"),
+                ("class/hwmon/hwmon1/temp1_label", "Tctl\n"),
+                ("class/hwmon/hwmon1/temp1_input", "61250\n"),
+                ("class/hwmon/hwmon1/temp3_label", "Tccd1\n"),
+                ("class/hwmon/hwmon1/temp3_input", "58000\n"),
+                ("class/hwmon/hwmon3/name", "k10temp\n"),
+                ("class/hwmon/hwmon3/temp1_label", "Tctl\n"),
+                ("class/hwmon/hwmon3/temp1_input", "40000\n"),
+            ],
+        );
+        let temperatures = Temperatures::read(&Sysfs::new(&fake.root)).unwrap();
+        assert_eq!(temperatures.packages, [(0, 61.25), (1, 40.0)]);
+        assert!(temperatures.cores.is_empty());
+
+        let empty = FakeSysfs::new("hwmon-none", &[]);
+        assert_eq!(
+            Temperatures::read(&Sysfs::new(&empty.root)).unwrap(),
+            Temperatures::default()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+
+#[cfg(feature = "std")]
+use crate::Sysfs;
+use crate::{CpuInfo, Vendor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CoreType {
+    Performance,
+    Efficiency,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CoreTypes {
+    /// Core type of each logical cpu, keyed by processor number.
+    pub cpus: Vec<(u32, CoreType)>,
+}
+
+impl CoreTypes {
+    pub fn get(&self, processor: u32) -> Option<CoreType> {
+        self.cpus
+            .iter()
+            .find(|(p, _)| *p == processor)
+            .map(|(_, core_type)| *core_type)
+    }
+
+    pub fn count(&self, core_type: CoreType) -> usize {
+        self.cpus.iter().filter(|(_, t)| *t == core_type).count()
+    }
+
+    pub fn performance(&self) -> usize {
+        self.count(CoreType::Performance)
+    }
+
+    pub fn efficiency(&self) -> usize {
+        self.count(CoreType::Efficiency)
+    }
+}
+
+/// Intel family 6 models that mix P-cores and E-cores.
+const HYBRID_MODELS: &[u32] = &[
+    0x97, 0x9a, // Alder Lake
+    0xb7, 0xba, 0xbf, // Raptor Lake
+    0xaa, 0xac, // Meteor Lake
+    0xbd, // Lunar Lake
+    0xc5, 0xc6, // Arrow Lake
+];
+
+// E-cores top out well below P-cores, while favoured P-cores are only a few
+// hundred MHz above the rest.
+#[cfg(feature = "std")]
+const EFFICIENCY_RATIO: f64 = 0.85;
+
+impl CpuInfo<'_> {
+    pub fn is_hybrid(&self) -> bool {
+        self.cpus.iter().any(|cpu| {
+            cpu.vendor() == Vendor::Intel
+                && cpu.cpu_family == 6
+                && HYBRID_MODELS.contains(&cpu.model)
+        })
+    }
+
+    /// Classifies cpus from cpuinfo alone: on hybrid parts only P-cores
+    /// have SMT, so cores listed twice are P-cores. Returns `None` for
+    /// non-hybrid parts and when SMT is off or absent.
+    pub fn core_types(&self) -> Option<CoreTypes> {
+        if !self.is_hybrid() {
+            return None;
+        }
+
+        let mut threads: BTreeMap<(Option<u32>, u32), usize> = BTreeMap::new();
+        for cpu in &self.cpus {
+            *threads.entry((cpu.physical_id, cpu.core_id)).or_default() += 1;
+        }
+        if !threads.values().any(|&count| count > 1) {
+            return None;
+        }
+
+        Some(CoreTypes {
+            cpus: self
+                .cpus
+                .iter()
+                .map(|cpu| {
+                    let core_type = match threads[&(cpu.physical_id, cpu.core_id)] {
+                        1 => CoreType::Efficiency,
+                        _ => CoreType::Performance,
+                    };
+                    (cpu.processor, core_type)
+                })
+                .collect(),
+        })
+    }
+
+    /// Like [`CpuInfo::core_types`], but prefers the maximum frequency from
+    /// cpufreq, which also works when SMT is disabled.
+    #[cfg(feature = "std")]
+    pub fn core_types_with(&self, sysfs: &Sysfs) -> Option<CoreTypes> {
+        if !self.is_hybrid() {
+            return None;
+        }
+
+        let max_freqs: Option<Vec<u64>> = self
+            .cpus
+            .iter()
+            .map(|cpu| sysfs.parse(Sysfs::cpu_path(cpu.processor, "cpufreq/cpuinfo_max_freq")))
+            .collect();
+        let Some(max_freqs) = max_freqs else {
+            return self.core_types();
+        };
+
+        let highest = max_freqs.iter().max().copied().unwrap_or_default();
+        let threshold = highest as f64 * EFFICIENCY_RATIO;
+        if max_freqs.iter().all(|&freq| freq as f64 >= threshold) {
+            return self.core_types();
+        }
+
+        Some(CoreTypes {
+            cpus: self
+                .cpus
+                .iter()
+                .zip(max_freqs)
+                .map(|(cpu, freq)| {
+                    let core_type = if freq as f64 >= threshold {
+                        CoreType::Performance
+                    } else {
+                        CoreType::Efficiency
+                    };
+                    (cpu.processor, core_type)
+                })
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::fixtures::FakeSysfs;
+    use crate::{cpuinfo, fixtures::SKYLAKE, CpuBuilder, CpuInfoBuilder};
+
+    // An i5-12600K: six P-cores with SMT listed first, then four E-cores.
+    fn alder_lake() -> CpuInfo<'static> {
+        let template = CpuBuilder::new().cpu_family(6).model(0x97);
+        let mut builder = CpuInfoBuilder::new();
+        for processor in 0..16 {
+            let core_id = if processor < 12 {
+                processor / 2 * 4
+            } else {
+                processor + 12
+            };
+            builder = builder.cpu(
+                template
+                    .clone()
+                    .processor(processor)
+                    .physical_id(Some(0))
+                    .core_id(core_id)
+                    .build(),
+            );
+        }
+        builder.build()
+    }
+
+    #[test]
+    fn classifies_by_core_layout() {
+        let core_types = alder_lake().core_types().unwrap();
+        assert_eq!(core_types.performance(), 12);
+        assert_eq!(core_types.efficiency(), 4);
+        assert_eq!(core_types.get(1), Some(CoreType::Performance));
+        assert_eq!(core_types.get(15), Some(CoreType::Efficiency));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn classifies_by_max_frequency() {
+        let files: Vec<(String, &str)> = (0..16)
+            .map(|processor| {
+                let freq = match processor {
+                    0 | 1 => "4900000",
+                    p if p < 12 => "4800000",
+                    _ => "3600000",
+                };
+                (
+                    format!(
+                        "devices/system/cpu/cpu{}/cpufreq/cpuinfo_max_freq",
+                        processor
+                    ),
+                    freq,
+                )
+            })
+            .collect();
+        let files: Vec<(&str, &str)> = files.iter().map(|(p, f)| (p.as_str(), *f)).collect();
+        let fake = FakeSysfs::new("hybrid", &files);
+
+        // Without SMT only frequencies tell the cores apart.
+        let mut cpuinfo = alder_lake();
+        for cpu in &mut cpuinfo.cpus {
+            cpu.core_id = cpu.processor;
+        }
+        assert_eq!(cpuinfo.core_types(), None);
+
+        let core_types = cpuinfo.core_types_with(&Sysfs::new(&fake.root)).unwrap();
+        assert_eq!(core_types.performance(), 12);
+        assert_eq!(core_types.efficiency(), 4);
+    }
+
+    #[test]
+    fn ignores_non_hybrid_parts() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        assert!(!cpuinfo.is_hybrid());
+        assert_eq!(cpuinfo.core_types(), None);
+        #[cfg(feature = "std")]
+        assert_eq!(cpuinfo.core_types_with(&Sysfs::default()), None);
+    }
+}
@@ -0,0 +1,34 @@
+use crate::{CpuInfo, Result};
+
+/// The JSON mirrors the structs field by field: one object per cpu below
+/// `cpus`, keyed by the Rust field names, with sizes in bytes.
+impl CpuInfo<'_> {
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn to_json_pretty(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cpuinfo, fixtures::SKYLAKE};
+
+    #[test]
+    fn emits_json() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        let json = cpuinfo.to_json().unwrap();
+        assert!(json.starts_with(r#"{"cpus":[{"processor":0,"vendor_id":"GenuineIntel","#));
+        assert!(json.contains(r#""cache_size":8388608,"#));
+        assert!(json.contains(r#""address_sizes":{"physical_size":39,"virtual_size":48}"#));
+
+        let pretty = cpuinfo.to_json_pretty().unwrap();
+        assert!(pretty.contains("\n      \"model\": 94,\n"));
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&json).unwrap()
+        );
+    }
+}
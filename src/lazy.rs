@@ -0,0 +1,196 @@
+use alloc::vec::Vec;
+use core::cell::OnceCell;
+
+use crate::{
+    cpu, finish, guest_cpu,
+    partial::{blocks, shift, Blocks},
+    Cpu, CpuInfo, Result, SCHEMA,
+};
+
+/// Cpuinfo split into processor blocks, each parsed the first time it is
+/// looked at. Counting the cpus or reading the first one's model costs one
+/// pass over the text, not a parse of every block.
+///
+/// Parsed cpus are kept, so a block is parsed at most once. A block that
+/// fails is not, and reports its error, with lines counted from the start
+/// of the input, every time it is accessed. Errors in blocks that are
+/// never accessed go unnoticed; [`into_cpuinfo`](Self::into_cpuinfo)
+/// checks them all.
+#[derive(Debug, Clone)]
+pub struct LazyCpuInfo<'a> {
+    /// Each block with the 1-based line it starts at.
+    blocks: Vec<(usize, &'a str, OnceCell<Cpu<'a>>)>,
+    guest: bool,
+}
+
+/// Splits `input` into processor blocks without parsing them yet.
+pub fn cpuinfo_lazy(input: &str) -> LazyCpuInfo<'_> {
+    LazyCpuInfo {
+        blocks: blocks(input)
+            .map(|(line, text)| (line, text, OnceCell::new()))
+            .collect(),
+        guest: false,
+    }
+}
+
+impl<'a> LazyCpuInfo<'a> {
+    /// Parses blocks like [`cpuinfo_guest`](crate::cpuinfo_guest).
+    pub fn guest(mut self) -> Self {
+        self.guest = true;
+        self
+    }
+
+    /// The number of blocks, which is the number of cpus when they all
+    /// parse.
+    pub fn len(&self) -> usize {
+        self.blocks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.blocks.is_empty()
+    }
+
+    /// The cpu of the `index`th block, parsing it on first access.
+    pub fn get(&self, index: usize) -> Option<Result<&Cpu<'a>>> {
+        let (line, text, cell) = self.blocks.get(index)?;
+        if let Some(cpu) = cell.get() {
+            return Some(Ok(cpu));
+        }
+        Some(parse(self.guest, *line, text).map(|cpu| cell.get_or_init(|| cpu)))
+    }
+
+    pub fn first(&self) -> Option<Result<&Cpu<'a>>> {
+        self.get(0)
+    }
+
+    /// Parses blocks as the iterator reaches them.
+    pub fn iter(&self) -> impl Iterator<Item = Result<&Cpu<'a>>> + '_ {
+        (0..self.len()).filter_map(|index| self.get(index))
+    }
+
+    /// Parses the remaining blocks, failing on the first that does not.
+    pub fn into_cpuinfo(self) -> Result<CpuInfo<'a>> {
+        let guest = self.guest;
+        let cpus = self
+            .blocks
+            .into_iter()
+            .map(|(line, text, cell)| match cell.into_inner() {
+                Some(cpu) => Ok(cpu),
+                None => parse(guest, line, text),
+            })
+            .collect::<Result<_>>()?;
+        Ok(CpuInfo { cpus })
+    }
+}
+
+/// Parses one cpu at a time as the iterator is advanced, never collecting
+/// them into a vector, for pipelines that handle each cpu and drop it.
+///
+/// The first error ends the iteration. Blocks are found as with
+/// [`cpuinfo_lazy`], so any number of blank lines may separate them.
+pub fn parse_iter(input: &str) -> CpuIter<'_> {
+    CpuIter {
+        blocks: blocks(input),
+        done: false,
+    }
+}
+
+/// The iterator returned by [`parse_iter`].
+#[derive(Debug, Clone)]
+pub struct CpuIter<'a> {
+    blocks: Blocks<'a>,
+    done: bool,
+}
+
+impl<'a> Iterator for CpuIter<'a> {
+    type Item = Result<Cpu<'a>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let (line, text) = self.blocks.next()?;
+        let cpu = parse(false, line, text);
+        self.done = cpu.is_err();
+        Some(cpu)
+    }
+}
+
+fn parse(guest: bool, line: usize, text: &str) -> Result<Cpu<'_>> {
+    let parser = if guest { guest_cpu } else { cpu };
+    finish(text, parser, &SCHEMA, guest).map_err(|e| shift(e, line - 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        CpuInfoError,
+    };
+    use alloc::{format, string::ToString, vec::Vec};
+
+    #[test]
+    fn parses_blocks_on_access() {
+        let input = format!(
+            "{}\n{}\n",
+            SKYLAKE,
+            EPYC.replace("model\t\t: 1", "model\t\t: x")
+        );
+        let lazy = cpuinfo_lazy(&input);
+        assert_eq!(lazy.len(), 2);
+        assert_eq!(lazy.first().unwrap().unwrap().model, 94);
+        assert!(lazy.blocks[0].2.get().is_some());
+
+        // The broken second block only fails once it is looked at, at the
+        // line the full parser reports.
+        let error = lazy.get(1).unwrap().unwrap_err();
+        assert!(matches!(
+            error,
+            CpuInfoError::InvalidValue { location, .. } if location.line == 31
+        ));
+        assert_eq!(error.to_string(), cpuinfo(&input).unwrap_err().to_string());
+        assert!(lazy.get(1).unwrap().is_err());
+        assert!(lazy.get(2).is_none());
+        assert_eq!(lazy.iter().filter(Result::is_ok).count(), 1);
+        assert!(lazy.into_cpuinfo().is_err());
+    }
+
+    #[test]
+    fn matches_the_full_parser() {
+        let input = format!("{}\n{}", SKYLAKE, EPYC);
+        let lazy = cpuinfo_lazy(&input);
+        assert_eq!(lazy.get(1).unwrap().unwrap().processor, 3);
+        assert_eq!(lazy.into_cpuinfo().unwrap(), cpuinfo(&input).unwrap());
+
+        let guest = SKYLAKE.replace("microcode\t: 0xf0\n", "");
+        assert!(cpuinfo_lazy(&guest).first().unwrap().is_err());
+        let lazy = cpuinfo_lazy(&guest).guest();
+        assert_eq!(lazy.first().unwrap().unwrap().microcode, None);
+        assert!(cpuinfo_lazy("").is_empty());
+    }
+
+    #[test]
+    fn iterates_without_collecting() {
+        let input = format!("\n{}\n\n{}", SKYLAKE, EPYC);
+        let processors: Vec<u32> = parse_iter(&input)
+            .map(|cpu| cpu.unwrap().processor)
+            .collect();
+        assert_eq!(processors, [0, 3]);
+
+        let input = format!(
+            "{}\n{}\n{}",
+            EPYC.replace("model\t\t: 1", "model\t\t: x"),
+            SKYLAKE,
+            SKYLAKE
+        );
+        let mut cpus = parse_iter(&input);
+        assert!(matches!(
+            cpus.next(),
+            Some(Err(CpuInfoError::InvalidValue { location, .. })) if location.line == 4
+        ));
+        assert!(cpus.next().is_none());
+        assert_eq!(parse_iter("").count(), 0);
+    }
+}
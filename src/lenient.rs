@@ -0,0 +1,229 @@
+use alloc::vec::Vec;
+use nom::{branch::alt, combinator::map, IResult};
+
+use crate::{
+    address_sizes, apicid, bogomips, bugs, cache_alignment, cache_size, clflush_size, core_id,
+    cpu_cores, cpu_family, cpu_mhz, cpuid_level, flags, fpu, fpu_exception, initial_apicid,
+    microcode, model, model_name, physical_id, power_management, processor, siblings, stepping,
+    svm_flags, tlb_size, vendor_id, vmx_flags, wp, AddressSizes, CacheSize, Cpu, CpuInfo,
+    Frequency, SizeUnit, TlbSize,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Parsed<'a> {
+    pub cpuinfo: CpuInfo<'a>,
+    pub skipped: Vec<SkippedLine<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SkippedLine<'a> {
+    /// 1-based line number within the input.
+    pub line: usize,
+    pub text: &'a str,
+}
+
+enum Field<'a> {
+    Processor(u32),
+    VendorId(&'a str),
+    CpuFamily(u32),
+    Model(u32),
+    ModelName(&'a str),
+    Stepping(Option<u32>),
+    Microcode(u64),
+    CpuMhz(Frequency),
+    CacheSize((CacheSize, SizeUnit)),
+    PhysicalId(u32),
+    Siblings(u32),
+    CoreId(u32),
+    CpuCores(u32),
+    Apicid(u32),
+    InitialApicid(u32),
+    Fpu(bool),
+    FpuException(bool),
+    CpuidLevel(u32),
+    Wp(bool),
+    Flags(Vec<&'a str>),
+    VmxFlags(Vec<&'a str>),
+    SvmFlags(Vec<&'a str>),
+    Bugs(Vec<&'a str>),
+    Bogomips(f64),
+    TlbSize(TlbSize),
+    ClflushSize(u32),
+    CacheAlignment(u32),
+    AddressSizes(AddressSizes),
+    PowerManagement(Vec<&'a str>),
+}
+
+/// Parses as much as possible, skipping lines that are not recognised or
+/// whose value is malformed instead of failing. Fields missing from a block
+/// are left at their default value.
+pub fn cpuinfo_lenient(input: &str) -> Parsed<'_> {
+    let mut cpus = Vec::new();
+    let mut skipped = Vec::new();
+    let mut current: Option<Cpu> = None;
+
+    for (index, text) in input.lines().enumerate() {
+        if text.trim().is_empty() {
+            cpus.extend(current.take());
+            continue;
+        }
+
+        match field(text) {
+            Ok(("", field)) => apply(current.get_or_insert_with(Cpu::default), field),
+            _ => skipped.push(SkippedLine {
+                line: index + 1,
+                text,
+            }),
+        }
+    }
+    cpus.extend(current);
+
+    Parsed {
+        cpuinfo: CpuInfo { cpus },
+        skipped,
+    }
+}
+
+fn field(input: &str) -> IResult<&str, Field<'_>> {
+    alt((
+        alt((
+            map(processor, Field::Processor),
+            map(vendor_id, Field::VendorId),
+            map(cpu_family, Field::CpuFamily),
+            map(model, Field::Model),
+            map(model_name, Field::ModelName),
+            map(stepping, Field::Stepping),
+            map(microcode, Field::Microcode),
+            map(cpu_mhz, Field::CpuMhz),
+            map(cache_size, Field::CacheSize),
+            map(physical_id, Field::PhysicalId),
+            map(siblings, Field::Siblings),
+            map(core_id, Field::CoreId),
+            map(cpu_cores, Field::CpuCores),
+            map(apicid, Field::Apicid),
+            map(initial_apicid, Field::InitialApicid),
+        )),
+        alt((
+            map(fpu, Field::Fpu),
+            map(fpu_exception, Field::FpuException),
+            map(cpuid_level, Field::CpuidLevel),
+            map(wp, Field::Wp),
+            map(flags, Field::Flags),
+            map(vmx_flags, Field::VmxFlags),
+            map(svm_flags, Field::SvmFlags),
+            map(bugs, Field::Bugs),
+            map(bogomips, Field::Bogomips),
+            map(tlb_size, Field::TlbSize),
+            map(clflush_size, Field::ClflushSize),
+            map(cache_alignment, Field::CacheAlignment),
+            map(address_sizes, Field::AddressSizes),
+            map(power_management, Field::PowerManagement),
+        )),
+    ))(input)
+}
+
+fn apply<'a>(cpu: &mut Cpu<'a>, field: Field<'a>) {
+    match field {
+        Field::Processor(v) => cpu.processor = v,
+        Field::VendorId(v) => cpu.vendor_id = v,
+        Field::CpuFamily(v) => cpu.cpu_family = v,
+        Field::Model(v) => cpu.model = v,
+        Field::ModelName(v) => cpu.model_name = v,
+        Field::Stepping(v) => cpu.stepping = v,
+        Field::Microcode(v) => cpu.microcode = Some(v),
+        Field::CpuMhz(v) => cpu.cpu_mhz = v,
+        Field::CacheSize((size, unit)) => {
+            cpu.cache_size = Some(size);
+            cpu.cache_size_unit = unit;
+        }
+        Field::PhysicalId(v) => cpu.physical_id = Some(v),
+        Field::Siblings(v) => cpu.siblings = v,
+        Field::CoreId(v) => cpu.core_id = v,
+        Field::CpuCores(v) => cpu.cpu_cores = v,
+        Field::Apicid(v) => cpu.apicid = v,
+        Field::InitialApicid(v) => cpu.initial_apicid = v,
+        Field::Fpu(v) => cpu.fpu = v,
+        Field::FpuException(v) => cpu.fpu_exception = v,
+        Field::CpuidLevel(v) => cpu.cpuid_level = v,
+        Field::Wp(v) => cpu.wp = v,
+        Field::Flags(v) => cpu.flags = v,
+        Field::VmxFlags(v) => cpu.vmx_flags = v,
+        Field::SvmFlags(v) => cpu.svm_flags = v,
+        Field::Bugs(v) => cpu.bugs = v,
+        Field::Bogomips(v) => cpu.bogomips = v,
+        Field::TlbSize(v) => cpu.tlb_size = Some(v),
+        Field::ClflushSize(v) => cpu.clflush_size = v,
+        Field::CacheAlignment(v) => cpu.cache_alignment = v,
+        Field::AddressSizes(v) => cpu.address_sizes = v,
+        Field::PowerManagement(v) => cpu.power_management = v,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn skips_malformed_lines() {
+        let result = cpuinfo_lenient(
+            "processor	: 0
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 94
+model name	: Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz
+stepping	: 3
+microcode	: 0xf0
+cpu MHz		: fast
+cache size	: 8192 KB
+flags		: fpu vme de pse
+frobnication	: enabled
+bogomips	: 8003.30
+
+processor	: 1
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 94
+cpu MHz		: 4000.000
+",
+        );
+
+        assert_eq!(result.cpuinfo.cpus.len(), 2);
+        assert_eq!(
+            result.skipped,
+            vec![
+                SkippedLine {
+                    line: 8,
+                    text: "cpu MHz		: fast",
+                },
+                SkippedLine {
+                    line: 11,
+                    text: "frobnication	: enabled",
+                },
+            ]
+        );
+
+        let first = &result.cpuinfo.cpus[0];
+        assert_eq!(first.microcode, Some(0xf0));
+        assert_eq!(first.cpu_mhz, Frequency::default());
+        assert_eq!(first.flags, vec!["fpu", "vme", "de", "pse"]);
+
+        let second = &result.cpuinfo.cpus[1];
+        assert_eq!(second.processor, 1);
+        assert_eq!(second.cpu_mhz, Frequency::from_mhz(4000.0));
+        assert!(second.cache_size.is_none());
+    }
+
+    #[test]
+    fn ignores_blocks_without_known_fields() {
+        let result = cpuinfo_lenient(
+            "garbage
+more garbage
+
+",
+        );
+
+        assert!(result.cpuinfo.cpus.is_empty());
+        assert_eq!(result.skipped.len(), 2);
+    }
+}
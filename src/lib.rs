@@ -1,40 +1,243 @@
-use anyhow::Result;
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+
 use nom::{
     branch::alt,
-    bytes::complete::tag,
-    character::complete::{
-        self, alpha1, alphanumeric1, line_ending, not_line_ending, one_of, space0,
-    },
-    combinator::{map, map_res, opt, recognize, value},
-    multi::{many1, separated_list0, separated_list1},
-    number::complete::float,
-    sequence::{delimited, pair, preceded, separated_pair, terminated, tuple},
+    bytes::complete::{tag, tag_no_case, take_till1, take_while1},
+    character::complete::{self, line_ending, multispace0, space0, space1},
+    combinator::{all_consuming, eof, map, map_opt, map_res, opt, recognize, value},
+    multi::separated_list0,
+    number::complete::double,
+    sequence::{delimited, pair, preceded, separated_pair, terminated},
     IResult,
 };
 
-#[derive(Debug, PartialEq)]
+/// Implements `PartialEq`, `Eq` and `Hash` for a struct with `f64` fields,
+/// which are compared and hashed by their bits. Parsed values are never NaN,
+/// so this only differs from `==` in telling `0.0` and `-0.0` apart. Every
+/// field has to be listed, as either a float or not.
+macro_rules! impl_eq_hash {
+    (
+        $type:ty {
+            floats: [$($float:ident),*],
+            fields: [$($field:ident),* $(,)?] $(,)?
+        }
+    ) => {
+        impl PartialEq for $type {
+            fn eq(&self, other: &Self) -> bool {
+                let Self { $($float,)* $($field,)* } = self;
+                $($float.to_bits() == other.$float.to_bits() &&)* $(*$field == other.$field &&)* true
+            }
+        }
+
+        impl Eq for $type {}
+
+        impl core::hash::Hash for $type {
+            fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+                let Self { $($float,)* $($field,)* } = self;
+                $($float.to_bits().hash(state);)*
+                $($field.hash(state);)*
+            }
+        }
+    };
+}
+
+#[cfg(all(feature = "affinity", target_os = "linux"))]
+mod affinity;
+#[cfg(feature = "postcard")]
+mod binary;
+mod bug;
+mod builder;
+#[cfg(feature = "std")]
+mod cache;
+mod category;
+mod collection;
+mod compat;
+#[cfg(feature = "std")]
+mod cpufreq;
+#[cfg(all(feature = "cpuid", any(target_arch = "x86", target_arch = "x86_64")))]
+mod cpuid;
+mod diff;
+mod display;
+mod error;
+#[cfg(feature = "std")]
+mod file;
+#[cfg(test)]
+mod fixtures;
+mod flag;
+mod flagset;
+mod fleet;
+mod frequency;
+mod generator;
+mod group;
+#[cfg(feature = "toml")]
+mod history;
+mod hwloc;
+#[cfg(feature = "std")]
+mod hwmon;
+mod hybrid;
+#[cfg(feature = "json")]
+mod json;
+mod lazy;
+mod lenient;
+mod lscpu;
+mod microarch;
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "std")]
+mod monitor;
+#[cfg(all(feature = "msr", target_os = "linux"))]
+mod msr;
+#[cfg(feature = "std")]
+mod numa;
+#[cfg(feature = "std")]
+mod online;
+mod options;
+mod owned;
+mod partial;
+mod psabi;
+mod query;
+mod report;
+mod rustc;
+mod size;
+#[cfg(feature = "toml")]
+mod snapshot;
+mod socket;
+#[cfg(feature = "specs")]
+mod specs;
+mod stat;
+mod stats;
+#[cfg(feature = "proptest")]
+mod strategy;
+#[cfg(feature = "std")]
+mod stream;
+#[cfg(feature = "std")]
+mod sysfs;
+mod topology;
+mod validate;
+mod vendor;
+#[cfg(feature = "std")]
+mod vulnerability;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod writer;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+pub mod arm;
+pub mod loongarch;
+pub mod mips;
+pub mod powerpc;
+pub mod riscv;
+pub mod s390;
+pub mod sparc;
+
+use error::{diagnose, FieldSpec, Schema};
+
+#[cfg(all(feature = "affinity", target_os = "linux"))]
+pub use affinity::{allowed_cpus, set_affinity};
+pub use bug::CpuBug;
+pub use builder::{CpuBuilder, CpuInfoBuilder};
+#[cfg(feature = "std")]
+pub use cache::{Cache, CacheHierarchy, CacheType};
+pub use category::FlagCategory;
+pub use compat::{Compatibility, FeatureSet};
+#[cfg(feature = "std")]
+pub use cpufreq::{cpufreq_policies, turbo_enabled, CpuFreq, CpufreqPolicy};
+pub use diff::{CpuDiff, CpuInfoDiff, FieldChange};
+pub use display::Colored;
+pub use error::{CpuInfoError, Location, Result};
+pub use flag::CpuFlag;
+pub use flagset::FlagSet;
+pub use fleet::Fleet;
+pub use frequency::{Frequency, ParseFrequencyError};
+pub use generator::{Generator, Profile};
+pub use group::CpuGroup;
+#[cfg(feature = "toml")]
+pub use history::{Snapshot, SnapshotStore};
+#[cfg(feature = "std")]
+pub use hwmon::Temperatures;
+pub use hybrid::{CoreType, CoreTypes};
+pub use lazy::{cpuinfo_lazy, parse_iter, CpuIter, LazyCpuInfo};
+pub use lenient::{cpuinfo_lenient, Parsed, SkippedLine};
+pub use lscpu::{CacheSummary, LscpuSummary};
+pub use microarch::Microarchitecture;
+#[cfg(feature = "mmap")]
+pub use mmap::MappedFile;
+#[cfg(feature = "std")]
+pub use monitor::{Monitor, Sample, Samples};
+#[cfg(all(feature = "msr", target_os = "linux"))]
+pub use msr::{MsrReader, Msrs};
+#[cfg(feature = "std")]
+pub use numa::{NumaNode, NumaTopology};
+#[cfg(feature = "std")]
+pub use online::CpuPresence;
+pub use options::{cpuinfo_with_options, Architecture, Mode, ParserOptions};
+pub use owned::{CpuInfoOwned, CpuOwned};
+pub use partial::{cpuinfo_partial, ParseIssue};
+pub use psabi::PsAbiLevel;
+pub use report::{Report, ReportFormat};
+pub use size::{CacheSize, SizeUnit};
+pub use socket::SocketSummary;
+#[cfg(feature = "specs")]
+pub use specs::CpuSpec;
+pub use stat::{stat, CpuTimes, Stat};
+pub use stats::SocketStats;
+#[cfg(feature = "proptest")]
+pub use strategy::cpuinfo_text;
+#[cfg(feature = "std")]
+pub use stream::CpuStream;
+#[cfg(feature = "std")]
+pub use sysfs::Sysfs;
+pub use topology::{Placement, Topology};
+pub use validate::TopologyWarning;
+pub use vendor::Vendor;
+#[cfg(feature = "std")]
+pub use vulnerability::{
+    Mitigation, MitigationReport, MitigationState, Vulnerabilities, Vulnerability,
+};
+#[cfg(feature = "wasm")]
+pub use wasm::WasmCpuInfo;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddressSizes {
     pub physical_size: u32,
     pub virtual_size: u32,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TlbSize {
+    pub entries: u32,
+    pub page_size: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuInfo<'a> {
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub cpus: Vec<Cpu<'a>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Cpu<'a> {
     pub processor: u32,
     pub vendor_id: &'a str,
     pub cpu_family: u32,
     pub model: u32,
     pub model_name: &'a str,
-    pub stepping: u32,
-    pub microcode: u32,
-    pub cpu_mhz: f32,
-    pub cache_size: u32,
-    pub physical_id: u32,
+    pub stepping: Option<u32>,
+    pub microcode: Option<u64>,
+    pub cpu_mhz: Frequency,
+    pub cache_size: Option<CacheSize>,
+    /// What `cache size` was printed in, so it can be written back the same.
+    pub cache_size_unit: SizeUnit,
+    pub physical_id: Option<u32>,
     pub siblings: u32,
     pub core_id: u32,
     pub cpu_cores: u32,
@@ -44,25 +247,139 @@ pub struct Cpu<'a> {
     pub fpu_exception: bool,
     pub cpuid_level: u32,
     pub wp: bool,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub flags: Vec<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub vmx_flags: Vec<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub svm_flags: Vec<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
     pub bugs: Vec<&'a str>,
-    pub bogomips: f32,
+    pub bogomips: f64,
+    pub tlb_size: Option<TlbSize>,
     pub clflush_size: u32,
     pub cache_alignment: u32,
     pub address_sizes: AddressSizes,
-    pub power_management: Option<&'a str>,
+    #[cfg_attr(feature = "serde", serde(borrow))]
+    pub power_management: Vec<&'a str>,
 }
 
-pub fn cpuinfo(input: &'static str) -> Result<CpuInfo> {
-    let (_, cpus) = cpus(input)?;
+impl_eq_hash!(Cpu<'_> {
+    floats: [bogomips],
+    fields: [
+        processor, vendor_id, cpu_family, model, model_name, stepping, microcode, cpu_mhz, cache_size,
+        cache_size_unit, physical_id, siblings, core_id, cpu_cores, apicid, initial_apicid, fpu,
+        fpu_exception, cpuid_level, wp, flags, vmx_flags, svm_flags, bugs, tlb_size, clflush_size,
+        cache_alignment, address_sizes, power_management,
+    ],
+});
+
+impl Cpu<'_> {
+    pub fn is_virtualized(&self) -> bool {
+        self.flags.contains(&"hypervisor")
+    }
+}
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("processor"),
+        FieldSpec::required("vendor_id"),
+        FieldSpec::required("cpu family"),
+        FieldSpec::required("model"),
+        FieldSpec::required("model name"),
+        FieldSpec::required("stepping"),
+        FieldSpec::host("microcode"),
+        FieldSpec::required("cpu MHz"),
+        FieldSpec::host("cache size"),
+        FieldSpec::host("physical id"),
+        FieldSpec::required("siblings"),
+        FieldSpec::required("core id"),
+        FieldSpec::required("cpu cores"),
+        FieldSpec::required("apicid"),
+        FieldSpec::required("initial apicid"),
+        FieldSpec::required("fpu"),
+        FieldSpec::required("fpu_exception"),
+        FieldSpec::required("cpuid level"),
+        FieldSpec::required("wp"),
+        FieldSpec::required("flags"),
+        FieldSpec::optional("vmx flags"),
+        FieldSpec::optional("svm flags"),
+        FieldSpec::required("bugs"),
+        FieldSpec::required("bogomips"),
+        FieldSpec::optional("TLB size"),
+        FieldSpec::required("clflush size"),
+        FieldSpec::required("cache_alignment"),
+        FieldSpec::required("address sizes"),
+        FieldSpec::required("power management"),
+    ],
+    restart: 0,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    let cpus = finish(input, cpus, &SCHEMA, false)?;
     Ok(CpuInfo { cpus })
 }
 
+/// Parses cpuinfo as reported inside virtual machines, where hypervisors
+/// commonly omit `microcode`, `cache size` and `physical id`.
+pub fn cpuinfo_guest(input: &str) -> Result<CpuInfo<'_>> {
+    let cpus = finish(input, guest_cpus, &SCHEMA, true)?;
+    Ok(CpuInfo { cpus })
+}
+
+/// Runs `parser` over the whole input, allowing only trailing blank lines,
+/// and explains any failure in terms of `schema`.
+fn finish<'a, O, F>(input: &'a str, parser: F, schema: &Schema, guest: bool) -> Result<O>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    all_consuming(terminated(parser, multispace0))(input)
+        .map(|(_, output)| output)
+        .map_err(|e| diagnose(input, e, schema, guest))
+}
+
+/// Like `separated_list1(line_ending, block)`, but only stops quietly when a
+/// block fails on its first line. A block that breaks further down is
+/// reported, rather than silently dropped along with everything after it.
+fn blocks<'a, O, F>(mut block: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    move |input| {
+        let (rest, first) = block(input)?;
+        // The blocks of one file are about the same size, so the first one
+        // tells how many there are, saving the vector regrowing on machines
+        // with many cpus.
+        let size = input.len() - rest.len();
+        let mut output = Vec::with_capacity(rest.len() / size.max(1) + 1);
+        let mut input = rest;
+        output.push(first);
+
+        while let Ok((rest, _)) = line_ending::<_, nom::error::Error<&str>>(input) {
+            match block(rest) {
+                Ok((rest, value)) => {
+                    output.push(value);
+                    input = rest;
+                }
+                Err(nom::Err::Error(e)) if !rest[..rest.len() - e.input.len()].contains('\n') => {
+                    break
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok((input, output))
+    }
+}
+
 fn separator(input: &str) -> IResult<&str, ()> {
     value((), delimited(space0, tag(":"), space0))(input)
 }
 
+fn line_end(input: &str) -> IResult<&str, &str> {
+    alt((line_ending, eof))(input)
+}
+
 fn field_value<'a, F, V, T>(
     field_name: F,
     field_value: V,
@@ -72,14 +389,27 @@ where
     V: FnMut(&'a str) -> IResult<&'a str, T>,
 {
     map(
-        terminated(
-            separated_pair(field_name, separator, field_value),
-            line_ending,
-        ),
+        terminated(separated_pair(field_name, separator, field_value), line_end),
         |(_, v)| v,
     )
 }
 
+fn optional_if<'a, O, F>(
+    optional: bool,
+    mut parser: F,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Option<O>>
+where
+    F: FnMut(&'a str) -> IResult<&'a str, O>,
+{
+    move |input| {
+        if optional {
+            opt(&mut parser)(input)
+        } else {
+            map(&mut parser, Some)(input)
+        }
+    }
+}
+
 fn boolean(input: &str) -> IResult<&str, bool> {
     map(alt((tag("yes"), tag("no"))), |v| match v {
         "yes" => true,
@@ -88,20 +418,53 @@ fn boolean(input: &str) -> IResult<&str, bool> {
     })(input)
 }
 
+/// The rest of the line, like `not_line_ending`, found with memchr instead
+/// of testing one character at a time.
+fn rest_of_line(input: &str) -> IResult<&str, &str> {
+    let end = memchr::memchr2(b'\n', b'\r', input.as_bytes()).unwrap_or(input.len());
+    if input[end..].starts_with('\r') && !input[end..].starts_with("\r\n") {
+        return Err(nom::Err::Error(nom::error::Error::new(
+            &input[end..],
+            nom::error::ErrorKind::Tag,
+        )));
+    }
+    Ok((&input[end..], &input[..end]))
+}
+
+/// Space separated words of lowercase letters, digits and `_`, like
+/// `separated_list0(tag(" "), take_while1(..))` but splitting the line at
+/// spaces found with memchr. Stops after the first word that does not run
+/// up to the next space.
 fn list(input: &str) -> IResult<&str, Vec<&str>> {
-    separated_list0(
-        tag(" "),
-        recognize(many1(one_of("abcdefghijklmnopqrstuvwxyz01234567890_"))),
-    )(input)
+    let (_, line) = rest_of_line(input)?;
+    let mut words = Vec::new();
+    let mut end = 0;
+    let mut start = 0;
+    for space in memchr::memchr_iter(b' ', line.as_bytes()).chain([line.len()]) {
+        let word = &line[start..space];
+        let len = word
+            .bytes()
+            .take_while(|&b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+            .count();
+        if len > 0 {
+            words.push(&word[..len]);
+            end = start + len;
+        }
+        if len == 0 || len < word.len() {
+            break;
+        }
+        start = space + 1;
+    }
+    Ok((&input[end..], words))
 }
 
-fn hexadecimal(input: &str) -> IResult<&str, u32> {
+fn hexadecimal(input: &str) -> IResult<&str, u64> {
     map_res(
         preceded(
             alt((tag("0x"), tag("0X"))),
-            recognize(many1(one_of("0123456789abcdefABCDEF"))),
+            take_while1(|c: char| c.is_ascii_hexdigit()),
         ),
-        |out: &str| u32::from_str_radix(out, 16),
+        |out: &str| u64::from_str_radix(out, 16),
     )(input)
 }
 
@@ -110,7 +473,7 @@ fn processor(input: &str) -> IResult<&str, u32> {
 }
 
 fn vendor_id(input: &str) -> IResult<&str, &str> {
-    field_value(tag("vendor_id"), alpha1)(input)
+    field_value(tag("vendor_id"), map(rest_of_line, str::trim_end))(input)
 }
 
 fn cpu_family(input: &str) -> IResult<&str, u32> {
@@ -122,28 +485,55 @@ fn model(input: &str) -> IResult<&str, u32> {
 }
 
 fn model_name(input: &str) -> IResult<&str, &str> {
-    field_value(tag("model name"), not_line_ending)(input)
+    field_value(tag("model name"), rest_of_line)(input)
 }
 
-fn stepping(input: &str) -> IResult<&str, u32> {
-    field_value(tag("stepping"), complete::u32)(input)
+fn stepping(input: &str) -> IResult<&str, Option<u32>> {
+    field_value(
+        tag("stepping"),
+        alt((map(complete::u32, Some), value(None, tag("unknown")))),
+    )(input)
 }
 
-fn microcode(input: &str) -> IResult<&str, u32> {
+fn microcode(input: &str) -> IResult<&str, u64> {
     field_value(tag("microcode"), hexadecimal)(input)
 }
 
-fn cpu_mhz(input: &str) -> IResult<&str, f32> {
-    field_value(tag("cpu MHz"), float)(input)
+fn cpu_mhz(input: &str) -> IResult<&str, Frequency> {
+    field_value(tag("cpu MHz"), mhz)(input)
 }
 
-fn cache_size(input: &str) -> IResult<&str, u32> {
-    map(
+/// A decimal number of MHz, parsed exactly rather than through a float.
+fn mhz(input: &str) -> IResult<&str, Frequency> {
+    map_opt(
+        recognize(pair(
+            complete::digit1,
+            opt(pair(complete::char('.'), complete::digit0)),
+        )),
+        |number| Frequency::from_decimal(number, 6),
+    )(input)
+}
+
+fn cache_size_unit(input: &str) -> IResult<&str, SizeUnit> {
+    alt((
+        value(SizeUnit::Kilobytes, tag("KB")),
+        value(SizeUnit::Megabytes, tag("MB")),
+        value(SizeUnit::Gigabytes, tag("GB")),
+        value(SizeUnit::Bytes, tag("B")),
+    ))(input)
+}
+
+fn cache_size(input: &str) -> IResult<&str, (CacheSize, SizeUnit)> {
+    map_opt(
         terminated(
-            separated_pair(tag("cache size"), separator, complete::u32),
-            tuple((space0, tag("KB"), line_ending)),
+            separated_pair(
+                tag("cache size"),
+                separator,
+                separated_pair(complete::u64, space0, cache_size_unit),
+            ),
+            line_end,
         ),
-        |(_, cache_size)| cache_size * 1024,
+        |(_, (size, unit))| Some((unit.size().checked_mul(size)?, unit)),
     )(input)
 }
 
@@ -195,12 +585,45 @@ fn vmx_flags(input: &str) -> IResult<&str, Vec<&str>> {
     field_value(tag("vmx flags"), list)(input)
 }
 
+fn svm_flags(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("svm flags"), list)(input)
+}
+
 fn bugs(input: &str) -> IResult<&str, Vec<&str>> {
     field_value(tag("bugs"), list)(input)
 }
 
-fn bogomips(input: &str) -> IResult<&str, f32> {
-    field_value(tag("bogomips"), float)(input)
+fn bogomips(input: &str) -> IResult<&str, f64> {
+    field_value(tag("bogomips"), double)(input)
+}
+
+fn tlb_page_size(input: &str) -> IResult<&str, u64> {
+    map_opt(
+        pair(
+            complete::u64,
+            alt((
+                value(1 << 10, tag("K")),
+                value(1 << 20, tag("M")),
+                value(1 << 30, tag("G")),
+                value(1, tag("B")),
+            )),
+        ),
+        |(size, unit)| size.checked_mul(unit),
+    )(input)
+}
+
+fn tlb_size(input: &str) -> IResult<&str, TlbSize> {
+    field_value(
+        tag_no_case("TLB size"),
+        map(
+            separated_pair(
+                complete::u32,
+                tag(" "),
+                terminated(tlb_page_size, tag(" pages")),
+            ),
+            |(entries, page_size)| TlbSize { entries, page_size },
+        ),
+    )(input)
 }
 
 fn clflush_size(input: &str) -> IResult<&str, u32> {
@@ -232,21 +655,32 @@ fn address_sizes(input: &str) -> IResult<&str, AddressSizes> {
     )(input)
 }
 
-fn power_management(input: &str) -> IResult<&str, Option<&str>> {
-    field_value(tag("power management"), opt(alphanumeric1))(input)
+fn power_management(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(
+        tag("power management"),
+        separated_list0(space1, take_till1(|c: char| c.is_ascii_whitespace())),
+    )(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    parse_cpu(input, false)
+}
+
+fn guest_cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    parse_cpu(input, true)
 }
 
-fn cpu(input: &str) -> IResult<&str, Cpu> {
+fn parse_cpu(input: &str, guest: bool) -> IResult<&str, Cpu<'_>> {
     let (input, processor) = processor(input)?;
     let (input, vendor_id) = vendor_id(input)?;
     let (input, cpu_family) = cpu_family(input)?;
     let (input, model) = model(input)?;
     let (input, model_name) = model_name(input)?;
     let (input, stepping) = stepping(input)?;
-    let (input, microcode) = microcode(input)?;
+    let (input, microcode) = optional_if(guest, microcode)(input)?;
     let (input, cpu_mhz) = cpu_mhz(input)?;
-    let (input, cache_size) = cache_size(input)?;
-    let (input, physical_id) = physical_id(input)?;
+    let (input, cache) = optional_if(guest, cache_size)(input)?;
+    let (input, physical_id) = optional_if(guest, physical_id)(input)?;
     let (input, siblings) = siblings(input)?;
     let (input, core_id) = core_id(input)?;
     let (input, cpu_cores) = cpu_cores(input)?;
@@ -257,9 +691,11 @@ fn cpu(input: &str) -> IResult<&str, Cpu> {
     let (input, cpuid_level) = cpuid_level(input)?;
     let (input, wp) = wp(input)?;
     let (input, flags) = flags(input)?;
-    let (input, vmx_flags) = vmx_flags(input)?;
+    let (input, vmx_flags) = map(opt(vmx_flags), Option::unwrap_or_default)(input)?;
+    let (input, svm_flags) = map(opt(svm_flags), Option::unwrap_or_default)(input)?;
     let (input, bugs) = bugs(input)?;
     let (input, bogomips) = bogomips(input)?;
+    let (input, tlb_size) = opt(tlb_size)(input)?;
     let (input, clflush_size) = clflush_size(input)?;
     let (input, cache_alignment) = cache_alignment(input)?;
     let (input, address_sizes) = address_sizes(input)?;
@@ -274,7 +710,8 @@ fn cpu(input: &str) -> IResult<&str, Cpu> {
         stepping,
         microcode,
         cpu_mhz,
-        cache_size,
+        cache_size: cache.map(|(size, _)| size),
+        cache_size_unit: cache.map(|(_, unit)| unit).unwrap_or_default(),
         physical_id,
         siblings,
         core_id,
@@ -287,8 +724,10 @@ fn cpu(input: &str) -> IResult<&str, Cpu> {
         wp,
         flags,
         vmx_flags,
+        svm_flags,
         bugs,
         bogomips,
+        tlb_size,
         clflush_size,
         cache_alignment,
         address_sizes,
@@ -298,13 +737,19 @@ fn cpu(input: &str) -> IResult<&str, Cpu> {
     Ok((input, cpu))
 }
 
-fn cpus(input: &str) -> IResult<&str, Vec<Cpu>> {
-    separated_list1(line_ending, cpu)(input)
+fn cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(cpu)(input)
+}
+
+fn guest_cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(guest_cpu)(input)
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use super::*;
+    use alloc::vec;
 
     #[test]
     fn parses_processor() {
@@ -326,6 +771,23 @@ mod tests {
         assert_eq!(result.unwrap().1, "GenuineIntel");
     }
 
+    #[test]
+    fn parses_vendor_id_with_spaces_and_digits() {
+        let result = vendor_id(
+            "vendor_id	: Vortex86 SoC
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "Vortex86 SoC");
+    }
+
+    #[test]
+    fn parses_vendor_id_with_padding() {
+        let result = vendor_id("vendor_id\t:   Shanghai  \n");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "Shanghai");
+    }
+
     #[test]
     fn parses_cpu_family() {
         let result = cpu_family(
@@ -366,7 +828,17 @@ mod tests {
 ",
         );
         assert!(result.is_ok());
-        assert_eq!(result.unwrap().1, 3);
+        assert_eq!(result.unwrap().1, Some(3));
+    }
+
+    #[test]
+    fn parses_unknown_stepping() {
+        let result = stepping(
+            "stepping	: unknown
+",
+        );
+        assert!(result.is_ok());
+        assert!(result.unwrap().1.is_none());
     }
 
     #[test]
@@ -379,6 +851,32 @@ mod tests {
         assert_eq!(result.unwrap().1, 240);
     }
 
+    #[test]
+    fn parses_large_microcode() {
+        let result = microcode(
+            "microcode	: 0x8301055
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0x8301055);
+
+        let result = microcode(
+            "microcode	: 0xffffffff00000001
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0xffffffff00000001);
+    }
+
+    #[test]
+    fn rejects_overflowing_microcode() {
+        let result = microcode(
+            "microcode	: 0x10000000000000000
+",
+        );
+        assert!(result.is_err());
+    }
+
     #[test]
     fn parses_cpu_mhz() {
         let result = cpu_mhz(
@@ -386,7 +884,10 @@ mod tests {
 ",
         );
         assert!(result.is_ok());
-        assert_eq!(result.unwrap().1, 4000.00);
+        assert_eq!(result.unwrap().1, Frequency::from_mhz(4000.0));
+
+        let (_, mhz) = cpu_mhz("cpu MHz\t\t: 2445.406123\n").unwrap();
+        assert_eq!(mhz.as_hz(), 2_445_406_123);
     }
 
     #[test]
@@ -396,7 +897,36 @@ mod tests {
 ",
         );
         assert!(result.is_ok());
-        assert_eq!(result.unwrap().1, 8192 * 1024);
+        assert_eq!(
+            result.unwrap().1,
+            (CacheSize::from_kib(8192), SizeUnit::Kilobytes)
+        );
+    }
+
+    #[test]
+    fn parses_cache_size_in_megabytes() {
+        let result = cache_size(
+            "cache size	: 32 MB
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            (CacheSize::from_mib(32), SizeUnit::Megabytes)
+        );
+    }
+
+    #[test]
+    fn parses_large_cache_size() {
+        let result = cache_size(
+            "cache size	: 4194304 KB
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            (CacheSize::from_gib(4), SizeUnit::Kilobytes)
+        );
     }
 
     #[test]
@@ -656,6 +1186,31 @@ mod tests {
         )
     }
 
+    #[test]
+    fn splits_lists_like_separated_list0() {
+        let reference = |input| {
+            separated_list0(
+                tag::<_, _, nom::error::Error<&str>>(" "),
+                take_while1(|c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+            )(input)
+        };
+        for input in [
+            "fpu vme de\nbugs",
+            "fpu vme \n",
+            "fpu  vme\n",
+            "fpu vme-x de\n",
+            "Fpu\n",
+            "\n",
+            "",
+            "fpu\r\n",
+            "avx512_bf16 sha_ni",
+        ] {
+            assert_eq!(list(input), reference(input), "{:?}", input);
+        }
+        assert!(rest_of_line("model\rname").is_err());
+        assert_eq!(rest_of_line("model\r\n"), Ok(("\r\n", "model")));
+    }
+
     #[test]
     fn parses_bugs() {
         let result = bugs(
@@ -682,6 +1237,34 @@ mod tests {
         )
     }
 
+    #[test]
+    fn parses_svm_flags() {
+        let result = svm_flags(
+            "svm flags	: npt lbrv svm_lock nrip_save tsc_scale vmcb_clean flushbyasid decodeassists pausefilter pfthreshold avic v_vmsave_vmload vgif v_spec_ctrl
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec![
+                "npt",
+                "lbrv",
+                "svm_lock",
+                "nrip_save",
+                "tsc_scale",
+                "vmcb_clean",
+                "flushbyasid",
+                "decodeassists",
+                "pausefilter",
+                "pfthreshold",
+                "avic",
+                "v_vmsave_vmload",
+                "vgif",
+                "v_spec_ctrl",
+            ]
+        )
+    }
+
     #[test]
     fn parses_bogomips() {
         let result = bogomips(
@@ -692,6 +1275,60 @@ mod tests {
         assert_eq!(result.unwrap().1, 8003.3);
     }
 
+    #[test]
+    fn parses_bogomips_with_many_decimals() {
+        let (_, value) = bogomips("bogomips\t: 4890.812345678\n").unwrap();
+        assert_eq!(value, 4890.812345678);
+    }
+
+    #[test]
+    fn parses_tlb_size() {
+        let result = tlb_size(
+            "TLB size	: 2560 4K pages
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            TlbSize {
+                entries: 2560,
+                page_size: 4096,
+            }
+        )
+    }
+
+    #[test]
+    fn parses_lowercase_tlb_size() {
+        let result = tlb_size(
+            "tlb size	: 1536 4K pages
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            TlbSize {
+                entries: 1536,
+                page_size: 4096,
+            }
+        )
+    }
+
+    #[test]
+    fn parses_tlb_size_with_large_pages() {
+        let result = tlb_size(
+            "TLB size	: 32 2M pages
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            TlbSize {
+                entries: 32,
+                page_size: 2 * 1024 * 1024,
+            }
+        )
+    }
+
     #[test]
     fn parses_clflush_size() {
         let result = clflush_size(
@@ -735,7 +1372,29 @@ mod tests {
 ",
         );
         assert!(result.is_ok());
-        assert!(result.unwrap().1.is_none());
+        assert!(result.unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn parses_power_management_list() {
+        let result = power_management(
+            "power management: ts ttp tm hwpstate cpb eff_freq_ro [13] [14]
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec![
+                "ts",
+                "ttp",
+                "tm",
+                "hwpstate",
+                "cpb",
+                "eff_freq_ro",
+                "[13]",
+                "[14]"
+            ]
+        )
     }
 
     #[test]
@@ -773,6 +1432,193 @@ power management:
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn parses_amd_cpu() {
+        let result = cpu(
+	    "processor	: 3
+vendor_id	: AuthenticAMD
+cpu family	: 25
+model		: 1
+model name	: AMD EPYC 7763 64-Core Processor
+stepping	: 1
+microcode	: 0xa0011d1
+cpu MHz		: 2445.406
+cache size	: 512 KB
+physical id	: 0
+siblings	: 4
+core id		: 1
+cpu cores	: 2
+apicid		: 3
+initial apicid	: 3
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 13
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 clflush mmx fxsr sse sse2 ht syscall nx mmxext fxsr_opt pdpe1gb rdtscp lm constant_tsc rep_good nopl tsc_reliable nonstop_tsc cpuid extd_apicid aperfmperf pni pclmulqdq ssse3 fma cx16 pcid sse4_1 sse4_2 movbe popcnt aes xsave avx f16c rdrand hypervisor lahf_lm cmp_legacy svm cr8_legacy abm sse4a misalignsse 3dnowprefetch osvw topoext invpcid_single vmmcall fsgsbase bmi1 avx2 smep bmi2 erms invpcid rdseed adx smap clflushopt clwb sha_ni xsaveopt xsavec xgetbv1 xsaves clzero xsaveerptr rdpru arat npt nrip_save tsc_scale vmcb_clean flushbyasid decodeassists pausefilter pfthreshold v_vmsave_vmload umip vaes vpclmulqdq rdpid fsrm
+svm flags	: npt nrip_save tsc_scale vmcb_clean flushbyasid decodeassists pausefilter pfthreshold v_vmsave_vmload
+bugs		: sysret_ss_attrs null_seg spectre_v1 spectre_v2 spec_store_bypass srso
+bogomips	: 4890.81
+TLB size	: 2560 4K pages
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 48 bits physical, 48 bits virtual
+power management: ts ttp tm hwpstate cpb eff_freq_ro [13] [14]
+"
+	);
+        assert!(result.is_ok());
+
+        let cpu = result.unwrap().1;
+        assert!(cpu.vmx_flags.is_empty());
+        assert_eq!(cpu.svm_flags.len(), 9);
+        assert_eq!(cpu.power_management.len(), 8);
+        assert_eq!(
+            cpu.tlb_size,
+            Some(TlbSize {
+                entries: 2560,
+                page_size: 4096,
+            })
+        );
+    }
+
+    #[test]
+    fn parses_cpu_with_crlf_line_endings() {
+        let input = "processor	: 0
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 94
+model name	: Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz
+stepping	: 3
+microcode	: 0xf0
+cpu MHz		: 800.004
+cache size	: 8192 KB
+physical id	: 0
+siblings	: 8
+core id		: 0
+cpu cores	: 4
+apicid		: 0
+initial apicid	: 0
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 22
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov
+bugs		: cpu_meltdown spectre_v1 spectre_v2
+bogomips	: 8003.30
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 39 bits physical, 48 bits virtual
+power management:
+"
+        .replace('\n', "\r\n");
+
+        let result = cpu(&input);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1.flags.len(), 15);
+    }
+
+    #[test]
+    fn parses_cpus_without_trailing_newline() {
+        let result = cpus(
+            "processor	: 0
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 94
+model name	: Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz
+stepping	: 3
+microcode	: 0xf0
+cpu MHz		: 800.004
+cache size	: 8192 KB
+physical id	: 0
+siblings	: 8
+core id		: 0
+cpu cores	: 4
+apicid		: 0
+initial apicid	: 0
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 22
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov
+bugs		: cpu_meltdown spectre_v1 spectre_v2
+bogomips	: 8003.30
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 39 bits physical, 48 bits virtual
+power management: ts",
+        );
+        assert!(result.is_ok());
+
+        let (rest, cpus) = result.unwrap();
+        assert!(rest.is_empty());
+        assert_eq!(cpus[0].power_management, vec!["ts"]);
+    }
+
+    #[test]
+    fn parses_guest_cpu() {
+        let result = guest_cpu(
+	    "processor	: 0
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 85
+model name	: Intel(R) Xeon(R) Platinum 8259CL CPU @ 2.50GHz
+stepping	: 7
+cpu MHz		: 2499.998
+siblings	: 2
+core id		: 0
+cpu cores	: 1
+apicid		: 0
+initial apicid	: 0
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 13
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 clflush mmx fxsr sse sse2 ss ht syscall nx pdpe1gb rdtscp lm constant_tsc rep_good nopl xtopology nonstop_tsc cpuid tsc_known_freq pni pclmulqdq ssse3 fma cx16 pcid sse4_1 sse4_2 x2apic movbe popcnt tsc_deadline_timer aes xsave avx f16c rdrand hypervisor lahf_lm abm 3dnowprefetch invpcid_single pti fsgsbase tsc_adjust bmi1 avx2 smep bmi2 erms invpcid mpx avx512f avx512dq rdseed adx smap clflushopt clwb avx512cd avx512bw avx512vl xsaveopt xsavec xgetbv1 xsaves ida arat pku ospke
+bugs		: cpu_meltdown spectre_v1 spectre_v2 spec_store_bypass l1tf mds swapgs itlb_multihit mmio_stale_data retbleed gds
+bogomips	: 4999.99
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 46 bits physical, 48 bits virtual
+power management:
+");
+        assert!(result.is_ok());
+
+        let cpu = result.unwrap().1;
+        assert!(cpu.microcode.is_none());
+        assert!(cpu.cache_size.is_none());
+        assert!(cpu.physical_id.is_none());
+        assert!(cpu.is_virtualized());
+    }
+
+    #[test]
+    fn rejects_guest_cpu_in_strict_mode() {
+        let result = cpu(
+	    "processor	: 0
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 85
+model name	: Intel(R) Xeon(R) Platinum 8259CL CPU @ 2.50GHz
+stepping	: 7
+cpu MHz		: 2499.998
+siblings	: 2
+core id		: 0
+cpu cores	: 1
+apicid		: 0
+initial apicid	: 0
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 13
+wp		: yes
+flags		: fpu vme de pse tsc msr pae mce cx8 apic sep mtrr pge mca cmov pat pse36 clflush mmx fxsr sse sse2 ss ht syscall nx pdpe1gb rdtscp lm constant_tsc rep_good nopl xtopology nonstop_tsc cpuid tsc_known_freq pni pclmulqdq ssse3 fma cx16 pcid sse4_1 sse4_2 x2apic movbe popcnt tsc_deadline_timer aes xsave avx f16c rdrand hypervisor lahf_lm abm 3dnowprefetch invpcid_single pti fsgsbase tsc_adjust bmi1 avx2 smep bmi2 erms invpcid mpx avx512f avx512dq rdseed adx smap clflushopt clwb avx512cd avx512bw avx512vl xsaveopt xsavec xgetbv1 xsaves ida arat pku ospke
+bugs		: cpu_meltdown spectre_v1 spectre_v2 spec_store_bypass l1tf mds swapgs itlb_multihit mmio_stale_data retbleed gds
+bogomips	: 4999.99
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 46 bits physical, 48 bits virtual
+power management:
+");
+        assert!(result.is_err());
+    }
+
     #[test]
     fn parses_cpus() {
         let result = cpus(
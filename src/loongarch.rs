@@ -0,0 +1,326 @@
+use alloc::vec::Vec;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, line_ending, not_line_ending},
+    combinator::{map, map_res, opt},
+    number::complete::double,
+    sequence::{pair, separated_pair, terminated},
+    IResult,
+};
+
+use crate::{
+    blocks, field_value, finish, hexadecimal, list, mhz, physical_size, processor, virtual_size,
+    AddressSizes, FieldSpec, Frequency, Result, Schema,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuInfo<'a> {
+    pub system_type: &'a str,
+    pub cpus: Vec<Cpu<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cpu<'a> {
+    pub processor: u32,
+    pub package: u32,
+    pub core: u32,
+    pub global_id: Option<u32>,
+    pub cpu_family: &'a str,
+    pub model_name: &'a str,
+    pub cpu_revision: u32,
+    pub fpu_revision: u32,
+    pub cpu_mhz: Frequency,
+    pub bogomips: f64,
+    pub tlb_entries: u32,
+    pub address_sizes: AddressSizes,
+    pub isa: Vec<&'a str>,
+    pub features: Vec<&'a str>,
+    pub hardware_watchpoint: &'a str,
+}
+
+impl_eq_hash!(Cpu<'_> {
+    floats: [bogomips],
+    fields: [
+        processor, package, core, global_id, cpu_family, model_name, cpu_revision, fpu_revision, cpu_mhz,
+        tlb_entries, address_sizes, isa, features, hardware_watchpoint,
+    ],
+});
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("system type"),
+        FieldSpec::required("processor"),
+        FieldSpec::required("package"),
+        FieldSpec::required("core"),
+        FieldSpec::optional("global_id"),
+        FieldSpec::required("CPU Family"),
+        FieldSpec::required("Model Name"),
+        FieldSpec::required("CPU Revision"),
+        FieldSpec::required("FPU Revision"),
+        FieldSpec::required("CPU MHz"),
+        FieldSpec::required("BogoMIPS"),
+        FieldSpec::required("TLB Entries"),
+        FieldSpec::required("Address Sizes"),
+        FieldSpec::required("ISA"),
+        FieldSpec::required("Features"),
+        FieldSpec::required("Hardware Watchpoint"),
+    ],
+    restart: 1,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    let (system_type, cpus) = finish(
+        input,
+        pair(terminated(system_type, line_ending), cpus),
+        &SCHEMA,
+        false,
+    )?;
+    Ok(CpuInfo { system_type, cpus })
+}
+
+fn system_type(input: &str) -> IResult<&str, &str> {
+    field_value(tag("system type"), not_line_ending)(input)
+}
+
+fn package(input: &str) -> IResult<&str, u32> {
+    field_value(tag("package"), complete::u32)(input)
+}
+
+fn core(input: &str) -> IResult<&str, u32> {
+    field_value(tag("core"), complete::u32)(input)
+}
+
+fn global_id(input: &str) -> IResult<&str, u32> {
+    field_value(tag("global_id"), complete::u32)(input)
+}
+
+fn cpu_family(input: &str) -> IResult<&str, &str> {
+    field_value(tag("CPU Family"), not_line_ending)(input)
+}
+
+fn model_name(input: &str) -> IResult<&str, &str> {
+    field_value(tag("Model Name"), not_line_ending)(input)
+}
+
+fn cpu_revision(input: &str) -> IResult<&str, u32> {
+    field_value(tag("CPU Revision"), map_res(hexadecimal, u32::try_from))(input)
+}
+
+fn fpu_revision(input: &str) -> IResult<&str, u32> {
+    field_value(tag("FPU Revision"), map_res(hexadecimal, u32::try_from))(input)
+}
+
+fn cpu_mhz(input: &str) -> IResult<&str, Frequency> {
+    field_value(tag("CPU MHz"), mhz)(input)
+}
+
+fn bogomips(input: &str) -> IResult<&str, f64> {
+    field_value(tag("BogoMIPS"), double)(input)
+}
+
+fn tlb_entries(input: &str) -> IResult<&str, u32> {
+    field_value(tag("TLB Entries"), complete::u32)(input)
+}
+
+fn address_sizes(input: &str) -> IResult<&str, AddressSizes> {
+    field_value(
+        tag("Address Sizes"),
+        map(
+            separated_pair(physical_size, tag(", "), virtual_size),
+            |(physical_size, virtual_size)| AddressSizes {
+                physical_size,
+                virtual_size,
+            },
+        ),
+    )(input)
+}
+
+fn isa(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("ISA"), list)(input)
+}
+
+fn features(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("Features"), list)(input)
+}
+
+fn hardware_watchpoint(input: &str) -> IResult<&str, &str> {
+    field_value(tag("Hardware Watchpoint"), not_line_ending)(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    let (input, processor) = processor(input)?;
+    let (input, package) = package(input)?;
+    let (input, core) = core(input)?;
+    let (input, global_id) = opt(global_id)(input)?;
+    let (input, cpu_family) = cpu_family(input)?;
+    let (input, model_name) = model_name(input)?;
+    let (input, cpu_revision) = cpu_revision(input)?;
+    let (input, fpu_revision) = fpu_revision(input)?;
+    let (input, cpu_mhz) = cpu_mhz(input)?;
+    let (input, bogomips) = bogomips(input)?;
+    let (input, tlb_entries) = tlb_entries(input)?;
+    let (input, address_sizes) = address_sizes(input)?;
+    let (input, isa) = isa(input)?;
+    let (input, features) = features(input)?;
+    let (input, hardware_watchpoint) = hardware_watchpoint(input)?;
+
+    let cpu = Cpu {
+        processor,
+        package,
+        core,
+        global_id,
+        cpu_family,
+        model_name,
+        cpu_revision,
+        fpu_revision,
+        cpu_mhz,
+        bogomips,
+        tlb_entries,
+        address_sizes,
+        isa,
+        features,
+        hardware_watchpoint,
+    };
+
+    Ok((input, cpu))
+}
+
+fn cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(cpu)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn parses_cpu_family() {
+        let result = cpu_family(
+            "CPU Family		: Loongson-64bit
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "Loongson-64bit");
+    }
+
+    #[test]
+    fn parses_model_name() {
+        let result = model_name(
+            "Model Name		: Loongson-3A5000
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "Loongson-3A5000");
+    }
+
+    #[test]
+    fn parses_cpu_revision() {
+        let result = cpu_revision(
+            "CPU Revision		: 0x10
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0x10);
+    }
+
+    #[test]
+    fn parses_fpu_revision() {
+        let result = fpu_revision(
+            "FPU Revision		: 0x00
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 0);
+    }
+
+    #[test]
+    fn parses_cpu_mhz() {
+        let result = cpu_mhz(
+            "CPU MHz			: 2500.00
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, Frequency::from_mhz(2500.0));
+    }
+
+    #[test]
+    fn parses_address_sizes() {
+        let result = address_sizes(
+            "Address Sizes		: 48 bits physical, 48 bits virtual
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            AddressSizes {
+                physical_size: 48,
+                virtual_size: 48,
+            }
+        )
+    }
+
+    #[test]
+    fn parses_features() {
+        let result = features(
+            "Features		: cpucfg lam ual fpu lsx lasx crc32 complex crypto lvz lbt_x86 lbt_arm lbt_mips
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec![
+                "cpucfg", "lam", "ual", "fpu", "lsx", "lasx", "crc32", "complex", "crypto", "lvz",
+                "lbt_x86", "lbt_arm", "lbt_mips",
+            ]
+        )
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "system type		: generic-loongson-machine
+
+processor		: 0
+package			: 0
+core			: 0
+global_id		: 0
+CPU Family		: Loongson-64bit
+Model Name		: Loongson-3A5000
+CPU Revision		: 0x10
+FPU Revision		: 0x00
+CPU MHz			: 2500.00
+BogoMIPS		: 5000.00
+TLB Entries		: 2112
+Address Sizes		: 48 bits physical, 48 bits virtual
+ISA			: loongarch32 loongarch64
+Features		: cpucfg lam ual fpu lsx lasx crc32 complex crypto lvz lbt_x86 lbt_arm lbt_mips
+Hardware Watchpoint	: yes, iwatch count: 8, dwatch count: 8
+
+processor		: 1
+package			: 0
+core			: 1
+global_id		: 1
+CPU Family		: Loongson-64bit
+Model Name		: Loongson-3A5000
+CPU Revision		: 0x10
+FPU Revision		: 0x00
+CPU MHz			: 2500.00
+BogoMIPS		: 5000.00
+TLB Entries		: 2112
+Address Sizes		: 48 bits physical, 48 bits virtual
+ISA			: loongarch32 loongarch64
+Features		: cpucfg lam ual fpu lsx lasx crc32 complex crypto lvz lbt_x86 lbt_arm lbt_mips
+Hardware Watchpoint	: yes, iwatch count: 8, dwatch count: 8
+
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.system_type, "generic-loongson-machine");
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.cpus[1].core, 1);
+        assert_eq!(cpuinfo.cpus[1].isa, vec!["loongarch32", "loongarch64"]);
+    }
+}
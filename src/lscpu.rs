@@ -0,0 +1,260 @@
+use alloc::{
+    format,
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::fmt;
+
+use crate::{CacheSize, CpuInfo, Frequency};
+#[cfg(feature = "std")]
+use crate::{CacheType, NumaTopology, Sysfs};
+
+const WIDTH: usize = 24;
+
+/// Total size and number of instances of one cache level, e.g. `L2:
+/// 2 MiB (8 instances)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CacheSummary {
+    pub size: CacheSize,
+    pub instances: usize,
+}
+
+/// The fields `lscpu` prints, for the machine as a whole.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LscpuSummary {
+    pub architecture: &'static str,
+    pub cpus: usize,
+    pub vendor_id: String,
+    pub model_name: String,
+    pub cpu_family: u32,
+    pub model: u32,
+    pub stepping: Option<u32>,
+    pub threads_per_core: usize,
+    pub cores_per_socket: usize,
+    pub sockets: usize,
+    pub max_frequency: Option<Frequency>,
+    pub bogomips: f64,
+    pub virtualization: Option<&'static str>,
+    pub address_sizes: (u32, u32),
+    /// Cache summaries and NUMA nodes come from sysfs, see
+    /// [`CpuInfo::lscpu_summary_with`].
+    pub l1d: Option<CacheSummary>,
+    pub l1i: Option<CacheSummary>,
+    pub l2: Option<CacheSummary>,
+    pub l3: Option<CacheSummary>,
+    pub numa_nodes: Option<usize>,
+    pub flags: Vec<String>,
+}
+
+fn row(f: &mut fmt::Formatter<'_>, label: &str, value: impl fmt::Display) -> fmt::Result {
+    writeln!(f, "{:<WIDTH$}{}", format!("{}:", label), value)
+}
+
+impl fmt::Display for CacheSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.instances == 1 { "" } else { "s" };
+        write!(f, "{} ({} instance{})", self.size, self.instances, plural)
+    }
+}
+
+impl fmt::Display for LscpuSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        row(f, "Architecture", self.architecture)?;
+        row(
+            f,
+            "Address sizes",
+            format!(
+                "{} bits physical, {} bits virtual",
+                self.address_sizes.0, self.address_sizes.1
+            ),
+        )?;
+        row(f, "CPU(s)", self.cpus)?;
+        row(f, "Vendor ID", &self.vendor_id)?;
+        row(f, "Model name", &self.model_name)?;
+        row(f, "CPU family", self.cpu_family)?;
+        row(f, "Model", self.model)?;
+        row(f, "Thread(s) per core", self.threads_per_core)?;
+        row(f, "Core(s) per socket", self.cores_per_socket)?;
+        row(f, "Socket(s)", self.sockets)?;
+        if let Some(stepping) = self.stepping {
+            row(f, "Stepping", stepping)?;
+        }
+        if let Some(mhz) = self.max_frequency {
+            row(f, "CPU max MHz", format!("{:.4}", mhz.as_mhz()))?;
+        }
+        row(f, "BogoMIPS", format!("{:.2}", self.bogomips))?;
+        row(f, "Flags", self.flags.join(" "))?;
+        if let Some(virtualization) = self.virtualization {
+            row(f, "Virtualization", virtualization)?;
+        }
+        for (label, cache) in [
+            ("L1d", self.l1d),
+            ("L1i", self.l1i),
+            ("L2", self.l2),
+            ("L3", self.l3),
+        ] {
+            if let Some(cache) = cache {
+                row(f, label, cache)?;
+            }
+        }
+        if let Some(nodes) = self.numa_nodes {
+            row(f, "NUMA node(s)", nodes)?;
+        }
+        Ok(())
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Summarizes from cpuinfo alone. Returns `None` without cpus.
+    pub fn lscpu_summary(&self) -> Option<LscpuSummary> {
+        let cpu = self.cpus.first()?;
+        let topology = self.topology();
+
+        Some(LscpuSummary {
+            architecture: if cpu.has_flag("lm") { "x86_64" } else { "i686" },
+            cpus: self.cpus.len(),
+            vendor_id: cpu.vendor_id.to_string(),
+            model_name: cpu.model_name.to_string(),
+            cpu_family: cpu.cpu_family,
+            model: cpu.model,
+            stepping: cpu.stepping,
+            threads_per_core: topology.threads_per_core(),
+            cores_per_socket: topology.cores_per_socket,
+            sockets: topology.sockets,
+            max_frequency: None,
+            bogomips: cpu.bogomips,
+            virtualization: if cpu.has_flag("vmx") {
+                Some("VT-x")
+            } else if cpu.has_flag("svm") {
+                Some("AMD-V")
+            } else {
+                None
+            },
+            address_sizes: (
+                cpu.address_sizes.physical_size,
+                cpu.address_sizes.virtual_size,
+            ),
+            l1d: None,
+            l1i: None,
+            l2: None,
+            l3: None,
+            numa_nodes: None,
+            flags: cpu.flags.iter().map(|flag| flag.to_string()).collect(),
+        })
+    }
+
+    /// Like [`CpuInfo::lscpu_summary`], adding caches, NUMA nodes and the
+    /// hardware maximum frequency from sysfs where available.
+    #[cfg(feature = "std")]
+    pub fn lscpu_summary_with(&self, sysfs: &Sysfs) -> Option<LscpuSummary> {
+        let mut summary = self.lscpu_summary()?;
+
+        if let Ok(hierarchies) = self.cache_hierarchies(sysfs) {
+            let mut seen = Vec::new();
+            for cache in hierarchies.iter().flat_map(|h| &h.caches) {
+                let key = (cache.level, cache.cache_type, &cache.shared_cpus);
+                if !seen.contains(&key) {
+                    seen.push(key);
+                    let slot = match (cache.level, cache.cache_type) {
+                        (1, CacheType::Data) => &mut summary.l1d,
+                        (1, CacheType::Instruction) => &mut summary.l1i,
+                        (2, _) => &mut summary.l2,
+                        (3, _) => &mut summary.l3,
+                        _ => continue,
+                    };
+                    let entry = slot.get_or_insert(CacheSummary {
+                        size: CacheSize::default(),
+                        instances: 0,
+                    });
+                    entry.size += cache.size;
+                    entry.instances += 1;
+                }
+            }
+        }
+
+        summary.numa_nodes = NumaTopology::read(sysfs).ok().map(|numa| numa.nodes.len());
+        summary.max_frequency = self
+            .cpufreq(sysfs)
+            .into_iter()
+            .filter_map(|freq| freq?.turbo)
+            .max();
+
+        Some(summary)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::fixtures::FakeSysfs;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+    use alloc::string::ToString;
+
+    #[test]
+    fn summarizes_cpuinfo() {
+        let summary = cpuinfo(SKYLAKE).unwrap().lscpu_summary().unwrap();
+        assert_eq!(summary.architecture, "i686");
+        assert_eq!(summary.cpus, 1);
+        assert_eq!(summary.threads_per_core, 2);
+        assert_eq!(summary.l3, None);
+
+        let text = summary.to_string();
+        assert!(text.contains("CPU(s):                 1\n"));
+        assert!(text.contains("Model name:             Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz\n"));
+        assert!(!text.contains("L1d"));
+
+        let summary = cpuinfo(EPYC).unwrap().lscpu_summary().unwrap();
+        assert_eq!(summary.architecture, "x86_64");
+        assert_eq!(summary.virtualization, Some("AMD-V"));
+        assert!(CpuInfo { cpus: Vec::new() }.lscpu_summary().is_none());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn summarizes_with_sysfs() {
+        let fake = FakeSysfs::new(
+            "lscpu",
+            &[
+                ("devices/system/cpu/cpu0/cache/index0/level", "1"),
+                ("devices/system/cpu/cpu0/cache/index0/type", "Data"),
+                ("devices/system/cpu/cpu0/cache/index0/size", "32K"),
+                ("devices/system/cpu/cpu0/cache/index0/shared_cpu_list", "0"),
+                ("devices/system/cpu/cpu0/cache/index1/level", "3"),
+                ("devices/system/cpu/cpu0/cache/index1/type", "Unified"),
+                ("devices/system/cpu/cpu0/cache/index1/size", "8192K"),
+                ("devices/system/cpu/cpu0/cache/index1/shared_cpu_list", "0"),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_max_freq",
+                    "3000000",
+                ),
+                (
+                    "devices/system/cpu/cpu0/cpufreq/cpuinfo_max_freq",
+                    "4200000",
+                ),
+                ("devices/system/node/node0/cpulist", "0"),
+            ],
+        );
+        let summary = cpuinfo(SKYLAKE)
+            .unwrap()
+            .lscpu_summary_with(&Sysfs::new(&fake.root))
+            .unwrap();
+
+        assert_eq!(
+            summary.l1d,
+            Some(CacheSummary {
+                size: CacheSize::from_kib(32),
+                instances: 1,
+            })
+        );
+        assert_eq!(summary.numa_nodes, Some(1));
+        assert_eq!(summary.max_frequency, Some(Frequency::from_mhz(4200.0)));
+
+        let text = summary.to_string();
+        assert!(text.contains("L3:                     8 MiB (1 instance)\n"));
+        assert!(text.contains("NUMA node(s):           1\n"));
+    }
+}
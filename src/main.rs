@@ -0,0 +1,844 @@
+use std::{
+    env,
+    fmt::Write,
+    fs,
+    io::{self, IsTerminal},
+    path::PathBuf,
+    process::ExitCode,
+    time::Duration,
+};
+
+use cpuinfo::{
+    cpufreq_policies, cpuinfo_guest, stat, CpuInfo, CpuInfoDiff, CpuInfoOwned, CpufreqPolicy,
+    FieldChange, Monitor, Sample, Stat, Sysfs, Topology,
+};
+
+const USAGE: &str = "usage: cpuinfo <command> [options]
+
+commands:
+  summary [--file PATH]    sockets, cores, frequencies and caches
+  show [--file PATH]       every field of every cpu
+  json [--file PATH] [--query PATH]
+                           the parsed document, or the part selected by a
+                           path such as `.cpus[0].flags`
+  diff BEFORE AFTER        per-field differences between two cpuinfo files
+  watch [--interval 1s] [--count N]
+                           per-cpu frequency and load, refreshed until
+                           interrupted or N updates were shown
+  check-flag FLAG... [--any] [--verbose] [--file PATH]
+                           succeeds if every cpu (or with --any, at least
+                           one) has all the flags
+
+options:
+  --no-color               never color the output; by default it is colored
+                           when writing to a terminal and NO_COLOR is unset
+
+exit status: 0 on success, 1 if check-flag fails, 2 on errors
+";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Summary {
+        file: Option<PathBuf>,
+    },
+    Show {
+        file: Option<PathBuf>,
+    },
+    Json {
+        file: Option<PathBuf>,
+        query: Option<String>,
+    },
+    Diff {
+        before: PathBuf,
+        after: PathBuf,
+    },
+    Watch {
+        interval: Duration,
+        count: Option<usize>,
+    },
+    CheckFlag {
+        flags: Vec<String>,
+        any: bool,
+        verbose: bool,
+        file: Option<PathBuf>,
+    },
+}
+
+/// Parses `500ms`, `2s`, `1m`, or plain seconds.
+fn parse_duration(text: &str) -> Result<Duration, String> {
+    let invalid = || format!("invalid interval `{}`", text);
+    let (number, unit) = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .map_or((text, ""), |i| text.split_at(i));
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        _ => return Err(invalid()),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|_| invalid())
+}
+
+#[derive(Debug, PartialEq)]
+struct Args {
+    command: Command,
+    no_color: bool,
+}
+
+fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Args, String> {
+    let command = args.next().ok_or("missing command")?;
+    let mut no_color = false;
+    let mut file = None;
+    let mut query = None;
+    let mut positional: Vec<String> = Vec::new();
+    let mut interval = Duration::from_secs(1);
+    let mut count = None;
+    let mut any = false;
+    let mut verbose = false;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--no-color" => no_color = true,
+            "--file" | "-f" => file = Some(args.next().ok_or("--file needs a path")?.into()),
+            "--query" | "-q" if command == "json" => {
+                query = Some(args.next().ok_or("--query needs a path")?)
+            }
+            "--interval" | "-n" if command == "watch" => {
+                interval = parse_duration(&args.next().ok_or("--interval needs a duration")?)?
+            }
+            "--count" | "-c" if command == "watch" => {
+                let text = args.next().ok_or("--count needs a number")?;
+                count = Some(
+                    text.parse()
+                        .map_err(|_| format!("invalid count `{}`", text))?,
+                )
+            }
+            "--any" if command == "check-flag" => any = true,
+            "--verbose" | "-v" if command == "check-flag" => verbose = true,
+            _ if !arg.starts_with('-') => positional.push(arg),
+            _ => return Err(format!("unexpected argument `{}`", arg)),
+        }
+    }
+
+    if command != "diff" && command != "check-flag" {
+        if let Some(arg) = positional.first() {
+            return Err(format!("unexpected argument `{}`", arg));
+        }
+    }
+
+    let command = match command.as_str() {
+        "summary" => Ok(Command::Summary { file }),
+        "show" => Ok(Command::Show { file }),
+        "json" => Ok(Command::Json { file, query }),
+        "diff" => match <[String; 2]>::try_from(positional) {
+            Ok([before, after]) if file.is_none() => Ok(Command::Diff {
+                before: before.into(),
+                after: after.into(),
+            }),
+            _ => Err("diff needs two files".to_string()),
+        },
+        "watch" if file.is_none() => Ok(Command::Watch { interval, count }),
+        "check-flag" if positional.is_empty() => Err("check-flag needs a flag".to_string()),
+        "check-flag" => Ok(Command::CheckFlag {
+            flags: positional,
+            any,
+            verbose,
+            file,
+        }),
+        _ => Err(format!("unknown command `{}`", command)),
+    }?;
+
+    Ok(Args { command, no_color })
+}
+
+/// Reads the given file, or the running system's cpuinfo. Guest parsing is
+/// used for files too, since they are often captured in virtual machines.
+fn load(file: Option<&PathBuf>) -> cpuinfo::Result<CpuInfoOwned> {
+    match file {
+        Some(path) => Ok(cpuinfo_guest(&fs::read_to_string(path)?)?.to_owned()),
+        None => CpuInfo::current(),
+    }
+}
+
+/// Caches come from sysfs when given, which is only meaningful for the
+/// running system.
+fn summary(cpuinfo: &CpuInfo, sysfs: Option<&Sysfs>) -> String {
+    let mut out = String::new();
+    let Some(cpu) = cpuinfo.cpus.first() else {
+        return "no cpus\n".to_string();
+    };
+    let topology = cpuinfo.topology();
+    let mut row = |label: &str, value: String| {
+        let _ = writeln!(out, "{:<16}{}", format!("{}:", label), value);
+    };
+
+    row("Model name", cpu.model_name.to_string());
+    row("CPUs", cpuinfo.cpus.len().to_string());
+    row("Sockets", topology.sockets.to_string());
+    row("Cores/socket", topology.cores_per_socket.to_string());
+    row("Threads/core", topology.threads_per_core().to_string());
+    if let (Some(min), Some(max), Some(mean)) = (
+        cpuinfo.min_frequency(),
+        cpuinfo.max_frequency(),
+        cpuinfo.mean_frequency(),
+    ) {
+        row(
+            "Frequency",
+            format!(
+                "{:.0} MHz mean ({:.0}-{:.0} MHz)",
+                mean.as_mhz(),
+                min.as_mhz(),
+                max.as_mhz()
+            ),
+        );
+    }
+    let policies = sysfs.and_then(|sysfs| cpufreq_policies(sysfs).ok());
+    if let Some(governors) = policies.as_deref().and_then(governors) {
+        row("Governor", governors);
+    }
+
+    let caches = sysfs.and_then(|sysfs| cpuinfo.lscpu_summary_with(sysfs));
+    let caches = caches
+        .as_ref()
+        .map(|s| [("L1d", s.l1d), ("L1i", s.l1i), ("L2", s.l2), ("L3", s.l3)]);
+    match caches {
+        Some(caches) if caches.iter().any(|(_, cache)| cache.is_some()) => {
+            for (label, cache) in caches {
+                if let Some(cache) = cache {
+                    row(label, cache.to_string());
+                }
+            }
+        }
+        _ => {
+            if let Some(cache_size) = cpu.cache_size {
+                row("Cache size", cache_size.to_string());
+            }
+        }
+    }
+
+    out
+}
+
+/// The governor of each policy, with the cpus it covers when they differ,
+/// e.g. `performance (cpus 0-3), powersave (cpus 4-7)`.
+fn governors(policies: &[CpufreqPolicy]) -> Option<String> {
+    let mut governors: Vec<(&str, Vec<u32>)> = Vec::new();
+    for policy in policies {
+        let Some(governor) = policy.governor.as_deref() else {
+            continue;
+        };
+        match governors.iter_mut().find(|(g, _)| *g == governor) {
+            Some((_, cpus)) => cpus.extend(&policy.cpus),
+            None => governors.push((governor, policy.cpus.clone())),
+        }
+    }
+    match governors.as_slice() {
+        [] => None,
+        [(governor, _)] => Some(governor.to_string()),
+        _ => Some(
+            governors
+                .iter()
+                .map(|(governor, cpus)| format!("{} (cpus {})", governor, ranges(cpus)))
+                .collect::<Vec<_>>()
+                .join(", "),
+        ),
+    }
+}
+
+/// Formats cpus the way the kernel lists them, e.g. `0-3,8`.
+fn ranges(cpus: &[u32]) -> String {
+    let mut cpus = cpus.to_vec();
+    cpus.sort_unstable();
+    cpus.dedup();
+    let mut out: Vec<String> = Vec::new();
+    let mut i = 0;
+    while i < cpus.len() {
+        let start = cpus[i];
+        while i + 1 < cpus.len() && cpus[i + 1] == cpus[i] + 1 {
+            i += 1;
+        }
+        out.push(match cpus[i] {
+            end if end == start => start.to_string(),
+            end => format!("{}-{}", start, end),
+        });
+        i += 1;
+    }
+    out.join(",")
+}
+
+/// Turns a jq-like path, e.g. `.cpus[0].flags`, into a JSON pointer.
+fn pointer(query: &str) -> Result<String, String> {
+    let invalid = || format!("invalid query `{}`", query);
+    let mut pointer = String::new();
+    let mut rest = query.strip_prefix('.').ok_or_else(invalid)?;
+
+    while !rest.is_empty() {
+        if let Some(index) = rest.strip_prefix('[') {
+            let (index, tail) = index.split_once(']').ok_or_else(invalid)?;
+            index.parse::<usize>().map_err(|_| invalid())?;
+            pointer.push('/');
+            pointer.push_str(index);
+            rest = tail.strip_prefix('.').unwrap_or(tail);
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let (key, tail) = rest.split_at(end);
+            if key.is_empty() {
+                return Err(invalid());
+            }
+            pointer.push('/');
+            pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+            rest = tail.strip_prefix('.').unwrap_or(tail);
+        }
+    }
+
+    Ok(pointer)
+}
+
+/// Prints strings bare, like `jq -r`, so scripts get the plain value.
+fn json(cpuinfo: &CpuInfo, query: Option<&str>) -> Result<String, String> {
+    let document = cpuinfo.to_json().map_err(|e| e.to_string())?;
+    let document: serde_json::Value = serde_json::from_str(&document).map_err(|e| e.to_string())?;
+
+    let value = match query {
+        Some(query) => document
+            .pointer(&pointer(query)?)
+            .ok_or_else(|| format!("`{}` not found", query))?,
+        None => &document,
+    };
+    Ok(match value {
+        serde_json::Value::String(s) => format!("{}\n", s),
+        value => format!("{:#}\n", value),
+    })
+}
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const CYAN: &str = "\x1b[36m";
+const RESET: &str = "\x1b[0m";
+
+/// Renders a diff in the style of `diff -u`, one `-`/`+` pair per field.
+fn render_diff(diff: &CpuInfoDiff, before: &str, after: &str, color: bool) -> String {
+    let mut out = String::new();
+    let mut line = |prefix: char, text: String| {
+        let color = match (color, prefix) {
+            (false, _) => "",
+            (true, '-') => RED,
+            (true, '+') => GREEN,
+            (true, _) => CYAN,
+        };
+        let reset = if color.is_empty() { "" } else { RESET };
+        let _ = writeln!(out, "{}{}{}{}", color, prefix, text, reset);
+    };
+    let hex =
+        |value: Option<u64>| value.map_or_else(|| "none".to_string(), |v| format!("{:#x}", v));
+
+    line('-', format!("-- {}", before));
+    line('+', format!("++ {}", after));
+    for processor in &diff.removed {
+        line('-', format!("processor {}", processor));
+    }
+    for processor in &diff.added {
+        line('+', format!("processor {}", processor));
+    }
+    for cpu in &diff.changed {
+        line('@', format!("@ processor {} @@", cpu.processor));
+        for change in &cpu.changes {
+            match change {
+                FieldChange::Microcode { old, new } => {
+                    line('-', format!("microcode: {}", hex(*old)));
+                    line('+', format!("microcode: {}", hex(*new)));
+                }
+                FieldChange::Mhz { old, new } => {
+                    line('-', format!("cpu MHz: {:.3}", old.as_mhz()));
+                    line('+', format!("cpu MHz: {:.3}", new.as_mhz()));
+                }
+                FieldChange::FlagsGained(flags) => line('+', format!("flags: {}", flags.join(" "))),
+                FieldChange::FlagsLost(flags) => line('-', format!("flags: {}", flags.join(" "))),
+                FieldChange::BugsGained(bugs) => line('+', format!("bugs: {}", bugs.join(" "))),
+                FieldChange::BugsLost(bugs) => line('-', format!("bugs: {}", bugs.join(" "))),
+                FieldChange::Other { field, old, new } => {
+                    line('-', format!("{}: {}", field, old));
+                    line('+', format!("{}: {}", field, new));
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn render_watch(
+    sample: &Sample,
+    load: &[(u32, Option<f64>)],
+    policies: &[CpufreqPolicy],
+    topology: &Topology,
+) -> String {
+    let temperatures = sample
+        .temperatures
+        .as_ref()
+        .filter(|temperatures| !temperatures.packages.is_empty());
+    let mut out = format!("{:>4} {:>9} {:>6}", "CPU", "MHz", "Load");
+    if temperatures.is_some() {
+        out.push_str("   Temp");
+    }
+    if !policies.is_empty() {
+        out.push_str(" Governor");
+    }
+    out.push('\n');
+
+    for (processor, frequency) in &sample.frequencies {
+        let load = load
+            .iter()
+            .find(|(p, _)| p == processor)
+            .and_then(|(_, load)| *load)
+            .map_or_else(|| "-".to_string(), |load| format!("{:.1}%", load * 100.0));
+        let _ = write!(
+            out,
+            "{:>4} {:>9.1} {:>6}",
+            processor,
+            frequency.as_mhz(),
+            load
+        );
+        if let Some(temperatures) = temperatures {
+            let celsius = topology
+                .placement(*processor)
+                .and_then(|placement| temperatures.of(placement))
+                .map_or_else(|| "-".to_string(), |celsius| format!("{:.0}°C", celsius));
+            let _ = write!(out, " {:>6}", celsius);
+        }
+        if !policies.is_empty() {
+            let governor = policies
+                .iter()
+                .find(|policy| policy.cpus.contains(processor))
+                .and_then(|policy| policy.governor.as_deref())
+                .unwrap_or("-");
+            let _ = write!(out, " {}", governor);
+        }
+        out.push('\n');
+    }
+    out
+}
+
+fn read_stat() -> Option<Stat> {
+    stat(&fs::read_to_string("/proc/stat").ok()?).ok()
+}
+
+fn watch(interval: Duration, count: Option<usize>) -> Result<(), String> {
+    let terminal = io::stdout().is_terminal();
+    let mut previous = read_stat();
+    let mut shown = 0;
+    let topology = CpuInfo::current()
+        .map(|cpuinfo| cpuinfo.as_cpuinfo().topology())
+        .unwrap_or_default();
+    let sysfs = Sysfs::default();
+
+    Monitor::new(interval)
+        .temperatures(sysfs.clone())
+        .run(|sample| {
+            let current = read_stat();
+            let load = match (&previous, &current) {
+                (Some(before), Some(after)) if shown > 0 => before.utilization(after),
+                _ => Vec::new(),
+            };
+            previous = current;
+            let policies = cpufreq_policies(&sysfs).unwrap_or_default();
+
+            if terminal {
+                print!("\x1b[H\x1b[2J");
+            } else if shown > 0 {
+                println!();
+            }
+            print!("{}", render_watch(sample, &load, &policies, &topology));
+
+            shown += 1;
+            count.is_none_or(|count| shown < count)
+        })
+        .map_err(|e| e.to_string())
+}
+
+/// Whether the flags are all present, with a line per flag for `--verbose`.
+fn check_flags(cpuinfo: &CpuInfo, flags: &[String], any: bool) -> (bool, String) {
+    let mut report = String::new();
+    let mut ok = true;
+
+    for flag in flags {
+        let count = cpuinfo.cpus.iter().filter(|cpu| cpu.has_flag(flag)).count();
+        let present = if any {
+            cpuinfo.any_has_flag(flag)
+        } else {
+            cpuinfo.all_have_flag(flag)
+        };
+        ok &= present;
+        let _ = writeln!(
+            report,
+            "{}: {} ({} of {} cpus)",
+            flag,
+            if present { "ok" } else { "missing" },
+            count,
+            cpuinfo.cpus.len()
+        );
+    }
+
+    (ok, report)
+}
+
+/// Whether to emit ANSI colors, following <https://no-color.org>.
+fn use_color(no_color: bool) -> bool {
+    !no_color && env::var_os("NO_COLOR").is_none() && io::stdout().is_terminal()
+}
+
+fn run(command: Command, color: bool) -> Result<bool, String> {
+    match command {
+        Command::Summary { file } => {
+            let owned = load(file.as_ref()).map_err(|e| e.to_string())?;
+            let sysfs = file.is_none().then(Sysfs::default);
+            print!("{}", summary(&owned.as_cpuinfo(), sysfs.as_ref()));
+        }
+        Command::Show { file } => {
+            let owned = load(file.as_ref()).map_err(|e| e.to_string())?;
+            let cpuinfo = owned.as_cpuinfo();
+            if color {
+                print!("{}", cpuinfo.colored());
+            } else {
+                print!("{}", cpuinfo);
+            }
+        }
+        Command::Json { file, query } => {
+            let owned = load(file.as_ref()).map_err(|e| e.to_string())?;
+            print!("{}", json(&owned.as_cpuinfo(), query.as_deref())?);
+        }
+        Command::Diff { before, after } => {
+            let old = load(Some(&before)).map_err(|e| format!("{}: {}", before.display(), e))?;
+            let new = load(Some(&after)).map_err(|e| format!("{}: {}", after.display(), e))?;
+            let diff = old.as_cpuinfo().diff(&new.as_cpuinfo());
+            if !diff.is_empty() {
+                print!(
+                    "{}",
+                    render_diff(
+                        &diff,
+                        &before.display().to_string(),
+                        &after.display().to_string(),
+                        color,
+                    )
+                );
+            }
+        }
+        Command::Watch { interval, count } => watch(interval, count)?,
+        Command::CheckFlag {
+            flags,
+            any,
+            verbose,
+            file,
+        } => {
+            let owned = load(file.as_ref()).map_err(|e| e.to_string())?;
+            let (ok, report) = check_flags(&owned.as_cpuinfo(), &flags, any);
+            if verbose {
+                print!("{}", report);
+            }
+            return Ok(ok);
+        }
+    }
+    Ok(true)
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("cpuinfo: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(args.command, use_color(args.no_color)) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(e) => {
+            eprintln!("cpuinfo: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cpuinfo::{Frequency, Temperatures};
+
+    fn args(line: &str) -> Result<Command, String> {
+        parse_args(line.split_whitespace().map(String::from)).map(|args| args.command)
+    }
+
+    #[test]
+    fn parses_arguments() {
+        assert_eq!(args("summary"), Ok(Command::Summary { file: None }));
+        assert_eq!(
+            args("summary --file /tmp/cpuinfo"),
+            Ok(Command::Summary {
+                file: Some("/tmp/cpuinfo".into()),
+            })
+        );
+        assert!(args("").is_err());
+        assert!(args("summary --file").is_err());
+        assert!(args("frobnicate").is_err());
+        assert_eq!(
+            args("json -q .cpus[0]"),
+            Ok(Command::Json {
+                file: None,
+                query: Some(".cpus[0]".to_string()),
+            })
+        );
+        assert!(args("summary --query .cpus").is_err());
+        assert_eq!(
+            args("diff a b"),
+            Ok(Command::Diff {
+                before: "a".into(),
+                after: "b".into(),
+            })
+        );
+        assert!(args("diff a").is_err());
+        assert!(args("diff a b c").is_err());
+        assert_eq!(
+            args("watch --interval 250ms -c 3"),
+            Ok(Command::Watch {
+                interval: Duration::from_millis(250),
+                count: Some(3),
+            })
+        );
+        assert!(args("watch --interval soon").is_err());
+        assert_eq!(
+            args("check-flag avx2 fma --any -v"),
+            Ok(Command::CheckFlag {
+                flags: vec!["avx2".to_string(), "fma".to_string()],
+                any: true,
+                verbose: true,
+                file: None,
+            })
+        );
+        assert!(args("check-flag").is_err());
+        assert!(args("summary extra").is_err());
+    }
+
+    #[test]
+    fn parses_no_color() {
+        let parse = |line: &str| parse_args(line.split_whitespace().map(String::from));
+        assert_eq!(
+            parse("show --no-color"),
+            Ok(Args {
+                command: Command::Show { file: None },
+                no_color: true,
+            })
+        );
+        assert_eq!(parse("diff a b").map(|args| args.no_color), Ok(false));
+        assert!(!use_color(true));
+    }
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("2"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("100ms"), Ok(Duration::from_millis(100)));
+        assert_eq!(parse_duration("1m"), Ok(Duration::from_secs(60)));
+        assert!(parse_duration("1h").is_err());
+        assert!(parse_duration("-1s").is_err());
+    }
+
+    #[test]
+    fn converts_queries() {
+        assert_eq!(pointer(".cpus[0].flags").as_deref(), Ok("/cpus/0/flags"));
+        assert_eq!(pointer(".cpus[12][3]").as_deref(), Ok("/cpus/12/3"));
+        assert_eq!(pointer(".").as_deref(), Ok(""));
+        assert!(pointer("cpus").is_err());
+        assert!(pointer(".cpus[x]").is_err());
+        assert!(pointer(".cpus..flags").is_err());
+    }
+
+    const INPUT: &str = "processor	: 0
+vendor_id	: GenuineIntel
+cpu family	: 6
+model		: 94
+model name	: Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz
+stepping	: 3
+cpu MHz		: 4000.000
+cache size	: 8192 KB
+siblings	: 1
+core id		: 0
+cpu cores	: 1
+apicid		: 0
+initial apicid	: 0
+fpu		: yes
+fpu_exception	: yes
+cpuid level	: 22
+wp		: yes
+flags		: fpu vme de pse
+bugs		:
+bogomips	: 8016.00
+clflush size	: 64
+cache_alignment	: 64
+address sizes	: 39 bits physical, 48 bits virtual
+power management:
+";
+
+    #[test]
+    fn prints_summary() {
+        let cpuinfo = cpuinfo_guest(INPUT).unwrap();
+        assert_eq!(
+            summary(&cpuinfo, None),
+            "Model name:     Intel(R) Core(TM) i7-6700K CPU @ 4.00GHz
+CPUs:           1
+Sockets:        1
+Cores/socket:   1
+Threads/core:   1
+Frequency:      4000 MHz mean (4000-4000 MHz)
+Cache size:     8 MiB
+"
+        );
+    }
+
+    #[test]
+    fn summarizes_governors() {
+        let policy = |id, cpus: &[u32], governor: &str| CpufreqPolicy {
+            id,
+            cpus: cpus.to_vec(),
+            governor: Some(governor.to_string()),
+            ..Default::default()
+        };
+        assert_eq!(governors(&[]), None);
+        assert_eq!(
+            governors(&[policy(0, &[0], "powersave"), policy(1, &[1], "powersave")]).as_deref(),
+            Some("powersave")
+        );
+        assert_eq!(
+            governors(&[
+                policy(0, &[0, 1, 2], "performance"),
+                policy(3, &[3], "powersave"),
+                policy(4, &[4, 6], "performance"),
+            ])
+            .as_deref(),
+            Some("performance (cpus 0-2,4,6), powersave (cpus 3)")
+        );
+    }
+
+    #[test]
+    fn prints_json() {
+        let cpuinfo = cpuinfo_guest(INPUT).unwrap();
+        assert!(json(&cpuinfo, None)
+            .unwrap()
+            .starts_with("{\n  \"cpus\": [\n"));
+        assert_eq!(
+            json(&cpuinfo, Some(".cpus[0].vendor_id")).unwrap(),
+            "GenuineIntel\n"
+        );
+        assert_eq!(json(&cpuinfo, Some(".cpus[0].model")).unwrap(), "94\n");
+        assert_eq!(
+            json(&cpuinfo, Some(".cpus[0].flags")).unwrap(),
+            "[\n  \"fpu\",\n  \"vme\",\n  \"de\",\n  \"pse\"\n]\n"
+        );
+        assert!(json(&cpuinfo, Some(".cpus[1]")).is_err());
+    }
+
+    #[test]
+    fn renders_diff() {
+        let before = cpuinfo_guest(INPUT).unwrap();
+        let mut both = before.clone();
+        let mut second = before.cpus[0].clone();
+        second.processor = 1;
+        both.cpus.push(second);
+
+        let diff = before.diff(&both);
+        assert_eq!(
+            render_diff(&diff, "before", "after", false),
+            "--- before\n+++ after\n+processor 1\n"
+        );
+
+        let mut after = before.clone();
+        after.cpus[0].cpu_mhz = Frequency::from_mhz(800.0);
+        after.cpus[0].flags.retain(|&flag| flag != "vme");
+        after.cpus[0].microcode = Some(0xf4);
+        let diff = before.diff(&after);
+        assert_eq!(
+            render_diff(&diff, "before", "after", false),
+            "--- before
++++ after
+@@ processor 0 @@
+-microcode: none
++microcode: 0xf4
+-cpu MHz: 4000.000
++cpu MHz: 800.000
+-flags: vme
+"
+        );
+        assert!(
+            render_diff(&diff, "before", "after", true).contains("\x1b[31m-flags: vme\x1b[0m\n")
+        );
+    }
+
+    #[test]
+    fn renders_watch_table() {
+        let mut sample = Sample {
+            at: std::time::Instant::now(),
+            frequencies: vec![
+                (0, Frequency::from_mhz(800.0)),
+                (1, Frequency::from_mhz(4200.5)),
+            ],
+            temperatures: None,
+        };
+        let topology = cpuinfo_guest(INPUT).unwrap().topology();
+        assert_eq!(
+            render_watch(&sample, &[(0, Some(0.25))], &[], &topology),
+            " CPU       MHz   Load
+   0     800.0  25.0%
+   1    4200.5      -
+"
+        );
+
+        sample.temperatures = Some(Temperatures {
+            packages: vec![(0, 55.0)],
+            cores: vec![(0, 0, 48.4)],
+        });
+        assert_eq!(
+            render_watch(&sample, &[], &[], &topology),
+            " CPU       MHz   Load   Temp
+   0     800.0      -   48°C
+   1    4200.5      -      -
+"
+        );
+
+        sample.temperatures = None;
+        let policies = [CpufreqPolicy {
+            id: 0,
+            cpus: vec![0],
+            governor: Some("powersave".to_string()),
+            ..Default::default()
+        }];
+        assert_eq!(
+            render_watch(&sample, &[], &policies, &topology),
+            " CPU       MHz   Load Governor
+   0     800.0      - powersave
+   1    4200.5      - -
+"
+        );
+    }
+
+    #[test]
+    fn checks_flags() {
+        let mut cpuinfo = cpuinfo_guest(INPUT).unwrap();
+        let mut second = cpuinfo.cpus[0].clone();
+        second.processor = 1;
+        second.flags.push("avx2");
+        cpuinfo.cpus.push(second);
+
+        let flags =
+            |names: &[&str]| -> Vec<String> { names.iter().map(|s| s.to_string()).collect() };
+        assert!(check_flags(&cpuinfo, &flags(&["fpu", "VME"]), false).0);
+        assert!(!check_flags(&cpuinfo, &flags(&["fpu", "avx2"]), false).0);
+        assert_eq!(
+            check_flags(&cpuinfo, &flags(&["avx2", "sse"]), true),
+            (
+                false,
+                "avx2: ok (1 of 2 cpus)\nsse: missing (0 of 2 cpus)\n".to_string()
+            )
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{Cpu, Vendor};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Microarchitecture {
+    pub codename: &'static str,
+    /// Generation number as used in marketing, e.g. 6 for 6th generation
+    /// Core (Skylake) or 3 for Zen 3, where the line has one.
+    pub generation: Option<u32>,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Maker {
+    Intel,
+    Amd,
+    Hygon,
+}
+
+struct Entry {
+    maker: Maker,
+    family: u32,
+    models: (u32, u32),
+    steppings: (u32, u32),
+    codename: &'static str,
+    generation: Option<u32>,
+}
+
+const ANY: (u32, u32) = (0, u32::MAX);
+
+const fn intel(model: u32, codename: &'static str, generation: Option<u32>) -> Entry {
+    Entry {
+        maker: Maker::Intel,
+        family: 6,
+        models: (model, model),
+        steppings: ANY,
+        codename,
+        generation,
+    }
+}
+
+const fn amd(
+    family: u32,
+    models: (u32, u32),
+    codename: &'static str,
+    generation: Option<u32>,
+) -> Entry {
+    Entry {
+        maker: Maker::Amd,
+        family,
+        models,
+        steppings: ANY,
+        codename,
+        generation,
+    }
+}
+
+const fn stepped(entry: Entry, steppings: (u32, u32)) -> Entry {
+    Entry { steppings, ..entry }
+}
+
+// Earlier entries win, so narrower stepping ranges come first.
+const TABLE: &[Entry] = &[
+    intel(0x0f, "Merom", None),
+    intel(0x16, "Merom", None),
+    intel(0x17, "Penryn", None),
+    intel(0x1d, "Penryn", None),
+    intel(0x1a, "Nehalem", Some(1)),
+    intel(0x1e, "Nehalem", Some(1)),
+    intel(0x1f, "Nehalem", Some(1)),
+    intel(0x2e, "Nehalem", Some(1)),
+    intel(0x25, "Westmere", Some(1)),
+    intel(0x2c, "Westmere", Some(1)),
+    intel(0x2f, "Westmere", Some(1)),
+    intel(0x2a, "Sandy Bridge", Some(2)),
+    intel(0x2d, "Sandy Bridge", Some(2)),
+    intel(0x3a, "Ivy Bridge", Some(3)),
+    intel(0x3e, "Ivy Bridge", Some(3)),
+    intel(0x3c, "Haswell", Some(4)),
+    intel(0x3f, "Haswell", Some(4)),
+    intel(0x45, "Haswell", Some(4)),
+    intel(0x46, "Haswell", Some(4)),
+    intel(0x3d, "Broadwell", Some(5)),
+    intel(0x47, "Broadwell", Some(5)),
+    intel(0x4f, "Broadwell", Some(5)),
+    intel(0x56, "Broadwell", Some(5)),
+    intel(0x4e, "Skylake", Some(6)),
+    intel(0x5e, "Skylake", Some(6)),
+    stepped(intel(0x55, "Cascade Lake", Some(2)), (5, 7)),
+    stepped(intel(0x55, "Cooper Lake", Some(3)), (10, 11)),
+    intel(0x55, "Skylake", Some(1)),
+    stepped(intel(0x8e, "Kaby Lake", Some(8)), (10, 10)),
+    stepped(intel(0x8e, "Whiskey Lake", Some(8)), (11, 11)),
+    stepped(intel(0x8e, "Comet Lake", Some(10)), (12, 12)),
+    intel(0x8e, "Kaby Lake", Some(7)),
+    stepped(intel(0x9e, "Coffee Lake", Some(8)), (10, 13)),
+    intel(0x9e, "Kaby Lake", Some(7)),
+    intel(0xa5, "Comet Lake", Some(10)),
+    intel(0xa6, "Comet Lake", Some(10)),
+    intel(0x66, "Cannon Lake", Some(8)),
+    intel(0x7d, "Ice Lake", Some(10)),
+    intel(0x7e, "Ice Lake", Some(10)),
+    intel(0x6a, "Ice Lake", Some(3)),
+    intel(0x6c, "Ice Lake", Some(3)),
+    intel(0x8c, "Tiger Lake", Some(11)),
+    intel(0x8d, "Tiger Lake", Some(11)),
+    intel(0xa7, "Rocket Lake", Some(11)),
+    intel(0x97, "Alder Lake", Some(12)),
+    intel(0x9a, "Alder Lake", Some(12)),
+    intel(0xb7, "Raptor Lake", Some(13)),
+    intel(0xba, "Raptor Lake", Some(13)),
+    intel(0xbf, "Raptor Lake", Some(13)),
+    intel(0xaa, "Meteor Lake", Some(1)),
+    intel(0xac, "Meteor Lake", Some(1)),
+    intel(0xbd, "Lunar Lake", Some(2)),
+    intel(0xc5, "Arrow Lake", Some(2)),
+    intel(0xc6, "Arrow Lake", Some(2)),
+    intel(0x8f, "Sapphire Rapids", Some(4)),
+    intel(0xcf, "Emerald Rapids", Some(5)),
+    intel(0xad, "Granite Rapids", Some(6)),
+    intel(0xae, "Granite Rapids", Some(6)),
+    intel(0xaf, "Sierra Forest", Some(6)),
+    intel(0x37, "Silvermont", None),
+    intel(0x4a, "Silvermont", None),
+    intel(0x4d, "Silvermont", None),
+    intel(0x5a, "Silvermont", None),
+    intel(0x5d, "Silvermont", None),
+    intel(0x4c, "Airmont", None),
+    intel(0x5c, "Goldmont", None),
+    intel(0x5f, "Goldmont", None),
+    intel(0x7a, "Goldmont Plus", None),
+    intel(0x86, "Tremont", None),
+    intel(0x96, "Tremont", None),
+    intel(0x9c, "Tremont", None),
+    intel(0xbe, "Gracemont", None),
+    intel(0x57, "Knights Landing", None),
+    intel(0x85, "Knights Mill", None),
+    amd(0x0f, (0x00, 0xff), "K8", None),
+    amd(0x10, (0x00, 0xff), "K10", None),
+    amd(0x15, (0x00, 0x0f), "Bulldozer", None),
+    amd(0x15, (0x10, 0x2f), "Piledriver", None),
+    amd(0x15, (0x30, 0x3f), "Steamroller", None),
+    amd(0x15, (0x60, 0x7f), "Excavator", None),
+    amd(0x16, (0x00, 0x0f), "Jaguar", None),
+    amd(0x16, (0x30, 0x3f), "Puma", None),
+    amd(0x17, (0x08, 0x08), "Zen+", Some(1)),
+    amd(0x17, (0x18, 0x18), "Zen+", Some(1)),
+    amd(0x17, (0x00, 0x2f), "Zen", Some(1)),
+    amd(0x17, (0x30, 0xaf), "Zen 2", Some(2)),
+    amd(0x19, (0x10, 0x1f), "Zen 4", Some(4)),
+    amd(0x19, (0x40, 0x4f), "Zen 3+", Some(3)),
+    amd(0x19, (0x60, 0x7f), "Zen 4", Some(4)),
+    amd(0x19, (0xa0, 0xaf), "Zen 4", Some(4)),
+    amd(0x19, (0x00, 0x5f), "Zen 3", Some(3)),
+    amd(0x1a, (0x00, 0x7f), "Zen 5", Some(5)),
+    Entry {
+        maker: Maker::Hygon,
+        family: 0x18,
+        models: (0x00, 0xff),
+        steppings: ANY,
+        codename: "Dhyana",
+        generation: None,
+    },
+];
+
+impl Cpu<'_> {
+    /// Looks the cpu up by vendor, family, model and stepping. Returns `None`
+    /// for parts the table does not know about.
+    pub fn microarchitecture(&self) -> Option<Microarchitecture> {
+        let maker = match self.vendor() {
+            Vendor::Intel => Maker::Intel,
+            Vendor::Amd => Maker::Amd,
+            Vendor::Hygon => Maker::Hygon,
+            _ => return None,
+        };
+        let stepping = self.stepping.unwrap_or_default();
+
+        TABLE
+            .iter()
+            .find(|entry| {
+                entry.maker == maker
+                    && entry.family == self.cpu_family
+                    && (entry.models.0..=entry.models.1).contains(&self.model)
+                    && (entry.steppings.0..=entry.steppings.1).contains(&stepping)
+            })
+            .map(|entry| Microarchitecture {
+                codename: entry.codename,
+                generation: entry.generation,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        CpuBuilder,
+    };
+
+    fn codename(vendor: &str, family: u32, model: u32, stepping: u32) -> Option<&'static str> {
+        CpuBuilder::new()
+            .vendor_id(vendor)
+            .cpu_family(family)
+            .model(model)
+            .stepping(Some(stepping))
+            .build()
+            .microarchitecture()
+            .map(|m| m.codename)
+    }
+
+    #[test]
+    fn identifies_fixtures() {
+        assert_eq!(
+            cpuinfo(SKYLAKE).unwrap().cpus[0].microarchitecture(),
+            Some(Microarchitecture {
+                codename: "Skylake",
+                generation: Some(6),
+            })
+        );
+        assert_eq!(
+            cpuinfo(EPYC).unwrap().cpus[0].microarchitecture(),
+            Some(Microarchitecture {
+                codename: "Zen 3",
+                generation: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn distinguishes_by_stepping() {
+        assert_eq!(codename("GenuineIntel", 6, 0x55, 4), Some("Skylake"));
+        assert_eq!(codename("GenuineIntel", 6, 0x55, 7), Some("Cascade Lake"));
+        assert_eq!(codename("GenuineIntel", 6, 0x9e, 9), Some("Kaby Lake"));
+        assert_eq!(codename("GenuineIntel", 6, 0x9e, 12), Some("Coffee Lake"));
+    }
+
+    #[test]
+    fn identifies_recent_parts() {
+        assert_eq!(codename("GenuineIntel", 6, 0xb7, 1), Some("Raptor Lake"));
+        assert_eq!(codename("AuthenticAMD", 0x19, 0x61, 2), Some("Zen 4"));
+        assert_eq!(codename("AuthenticAMD", 0x19, 0x44, 1), Some("Zen 3+"));
+        assert_eq!(codename("HygonGenuine", 0x18, 0x01, 1), Some("Dhyana"));
+        assert_eq!(codename("GenuineIntel", 6, 0x01, 1), None);
+        assert_eq!(codename("CentaurHauls", 6, 0x0f, 1), None);
+    }
+}
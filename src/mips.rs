@@ -0,0 +1,345 @@
+use alloc::vec::Vec;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, not_line_ending},
+    combinator::{map, opt},
+    number::complete::double,
+    sequence::tuple,
+    IResult,
+};
+
+use crate::{blocks, boolean, field_value, finish, list, processor, FieldSpec, Result, Schema};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuInfo<'a> {
+    pub system_type: &'a str,
+    pub machine: Option<&'a str>,
+    pub cpus: Vec<Cpu<'a>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Cpu<'a> {
+    pub processor: u32,
+    pub cpu_model: &'a str,
+    pub bogomips: f64,
+    pub wait_instruction: bool,
+    pub microsecond_timers: bool,
+    pub tlb_entries: u32,
+    pub extra_interrupt_vector: bool,
+    pub hardware_watchpoint: &'a str,
+    pub isa: Vec<&'a str>,
+    pub ases_implemented: Vec<&'a str>,
+    pub options_implemented: Vec<&'a str>,
+    pub shadow_register_sets: u32,
+    pub kscratch_registers: u32,
+    pub package: u32,
+    pub core: u32,
+    pub vpe: Option<u32>,
+    pub vced_exceptions: &'a str,
+    pub vcei_exceptions: &'a str,
+}
+
+impl_eq_hash!(Cpu<'_> {
+    floats: [bogomips],
+    fields: [
+        processor, cpu_model, wait_instruction, microsecond_timers, tlb_entries,
+        extra_interrupt_vector, hardware_watchpoint, isa, ases_implemented, options_implemented,
+        shadow_register_sets, kscratch_registers, package, core, vpe, vced_exceptions,
+        vcei_exceptions,
+    ],
+});
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("system type"),
+        FieldSpec::optional("machine"),
+        FieldSpec::required("processor"),
+        FieldSpec::required("cpu model"),
+        FieldSpec::required("BogoMIPS"),
+        FieldSpec::required("wait instruction"),
+        FieldSpec::required("microsecond timers"),
+        FieldSpec::required("tlb_entries"),
+        FieldSpec::required("extra interrupt vector"),
+        FieldSpec::required("hardware watchpoint"),
+        FieldSpec::required("isa"),
+        FieldSpec::required("ASEs implemented"),
+        FieldSpec::optional("Options implemented"),
+        FieldSpec::required("shadow register sets"),
+        FieldSpec::required("kscratch registers"),
+        FieldSpec::required("package"),
+        FieldSpec::required("core"),
+        FieldSpec::optional("VPE"),
+        FieldSpec::required("VCED exceptions"),
+        FieldSpec::required("VCEI exceptions"),
+    ],
+    restart: 2,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    let (system_type, machine, cpus) = finish(
+        input,
+        tuple((system_type, opt(machine), cpus)),
+        &SCHEMA,
+        false,
+    )?;
+
+    Ok(CpuInfo {
+        system_type,
+        machine,
+        cpus,
+    })
+}
+
+fn system_type(input: &str) -> IResult<&str, &str> {
+    field_value(tag("system type"), not_line_ending)(input)
+}
+
+fn machine(input: &str) -> IResult<&str, &str> {
+    field_value(tag("machine"), not_line_ending)(input)
+}
+
+fn cpu_model(input: &str) -> IResult<&str, &str> {
+    field_value(tag("cpu model"), not_line_ending)(input)
+}
+
+fn bogomips(input: &str) -> IResult<&str, f64> {
+    field_value(tag("BogoMIPS"), double)(input)
+}
+
+fn wait_instruction(input: &str) -> IResult<&str, bool> {
+    field_value(tag("wait instruction"), boolean)(input)
+}
+
+fn microsecond_timers(input: &str) -> IResult<&str, bool> {
+    field_value(tag("microsecond timers"), boolean)(input)
+}
+
+fn tlb_entries(input: &str) -> IResult<&str, u32> {
+    field_value(tag("tlb_entries"), complete::u32)(input)
+}
+
+fn extra_interrupt_vector(input: &str) -> IResult<&str, bool> {
+    field_value(tag("extra interrupt vector"), boolean)(input)
+}
+
+fn hardware_watchpoint(input: &str) -> IResult<&str, &str> {
+    field_value(tag("hardware watchpoint"), not_line_ending)(input)
+}
+
+fn isa(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("isa"), list)(input)
+}
+
+fn ases_implemented(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("ASEs implemented"), list)(input)
+}
+
+fn options_implemented(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("Options implemented"), list)(input)
+}
+
+fn shadow_register_sets(input: &str) -> IResult<&str, u32> {
+    field_value(tag("shadow register sets"), complete::u32)(input)
+}
+
+fn kscratch_registers(input: &str) -> IResult<&str, u32> {
+    field_value(tag("kscratch registers"), complete::u32)(input)
+}
+
+fn package(input: &str) -> IResult<&str, u32> {
+    field_value(tag("package"), complete::u32)(input)
+}
+
+fn core(input: &str) -> IResult<&str, u32> {
+    field_value(tag("core"), complete::u32)(input)
+}
+
+fn vpe(input: &str) -> IResult<&str, u32> {
+    field_value(tag("VPE"), complete::u32)(input)
+}
+
+fn vced_exceptions(input: &str) -> IResult<&str, &str> {
+    field_value(tag("VCED exceptions"), not_line_ending)(input)
+}
+
+fn vcei_exceptions(input: &str) -> IResult<&str, &str> {
+    field_value(tag("VCEI exceptions"), not_line_ending)(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    let (input, processor) = processor(input)?;
+    let (input, cpu_model) = cpu_model(input)?;
+    let (input, bogomips) = bogomips(input)?;
+    let (input, wait_instruction) = wait_instruction(input)?;
+    let (input, microsecond_timers) = microsecond_timers(input)?;
+    let (input, tlb_entries) = tlb_entries(input)?;
+    let (input, extra_interrupt_vector) = extra_interrupt_vector(input)?;
+    let (input, hardware_watchpoint) = hardware_watchpoint(input)?;
+    let (input, isa) = isa(input)?;
+    let (input, ases_implemented) = ases_implemented(input)?;
+    let (input, options_implemented) =
+        map(opt(options_implemented), Option::unwrap_or_default)(input)?;
+    let (input, shadow_register_sets) = shadow_register_sets(input)?;
+    let (input, kscratch_registers) = kscratch_registers(input)?;
+    let (input, package) = package(input)?;
+    let (input, core) = core(input)?;
+    let (input, vpe) = opt(vpe)(input)?;
+    let (input, vced_exceptions) = vced_exceptions(input)?;
+    let (input, vcei_exceptions) = vcei_exceptions(input)?;
+
+    let cpu = Cpu {
+        processor,
+        cpu_model,
+        bogomips,
+        wait_instruction,
+        microsecond_timers,
+        tlb_entries,
+        extra_interrupt_vector,
+        hardware_watchpoint,
+        isa,
+        ases_implemented,
+        options_implemented,
+        shadow_register_sets,
+        kscratch_registers,
+        package,
+        core,
+        vpe,
+        vced_exceptions,
+        vcei_exceptions,
+    };
+
+    Ok((input, cpu))
+}
+
+fn cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(cpu)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn parses_system_type() {
+        let result = system_type(
+            "system type		: MediaTek MT7621 ver:1 eco:3
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "MediaTek MT7621 ver:1 eco:3");
+    }
+
+    #[test]
+    fn parses_cpu_model() {
+        let result = cpu_model(
+            "cpu model		: MIPS 1004Kc V2.15
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "MIPS 1004Kc V2.15");
+    }
+
+    #[test]
+    fn parses_bogomips() {
+        let result = bogomips(
+            "BogoMIPS		: 586.13
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 586.13);
+    }
+
+    #[test]
+    fn parses_tlb_entries() {
+        let result = tlb_entries(
+            "tlb_entries		: 32
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 32);
+    }
+
+    #[test]
+    fn parses_isa() {
+        let result = isa("isa			: mips1 mips2 mips32r1 mips32r2
+");
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec!["mips1", "mips2", "mips32r1", "mips32r2"]
+        );
+    }
+
+    #[test]
+    fn parses_ases_implemented() {
+        let result = ases_implemented(
+            "ASEs implemented	: mips16 dsp mt
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, vec!["mips16", "dsp", "mt"]);
+    }
+
+    #[test]
+    fn parses_empty_ases_implemented() {
+        let result = ases_implemented(
+            "ASEs implemented	:
+",
+        );
+        assert!(result.is_ok());
+        assert!(result.unwrap().1.is_empty());
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "system type		: MediaTek MT7621 ver:1 eco:3
+machine			: Xiaomi Mi Router 3G
+processor		: 0
+cpu model		: MIPS 1004Kc V2.15
+BogoMIPS		: 586.13
+wait instruction	: yes
+microsecond timers	: yes
+tlb_entries		: 32
+extra interrupt vector	: yes
+hardware watchpoint	: yes, count: 4, address/irw mask: [0x0ffc, 0x0ffc, 0x0ffb, 0x0ffb]
+isa			: mips1 mips2 mips32r1 mips32r2
+ASEs implemented	: mips16 dsp mt
+shadow register sets	: 1
+kscratch registers	: 0
+package			: 0
+core			: 0
+VPE			: 0
+VCED exceptions		: not available
+VCEI exceptions		: not available
+
+processor		: 1
+cpu model		: MIPS 1004Kc V2.15
+BogoMIPS		: 586.13
+wait instruction	: yes
+microsecond timers	: yes
+tlb_entries		: 32
+extra interrupt vector	: yes
+hardware watchpoint	: yes, count: 4, address/irw mask: [0x0ffc, 0x0ffc, 0x0ffb, 0x0ffb]
+isa			: mips1 mips2 mips32r1 mips32r2
+ASEs implemented	: mips16 dsp mt
+shadow register sets	: 1
+kscratch registers	: 0
+package			: 0
+core			: 0
+VPE			: 1
+VCED exceptions		: not available
+VCEI exceptions		: not available
+
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.machine, Some("Xiaomi Mi Router 3G"));
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.cpus[1].vpe, Some(1));
+        assert!(cpuinfo.cpus[1].options_implemented.is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+use std::{fs::File, io, path::Path, str};
+
+use memmap2::Mmap;
+
+use crate::{cpuinfo, CpuInfo, CpuInfoOwned, Result};
+
+/// A cpuinfo file mapped read-only into memory, so that [`parse`] borrows
+/// from the page cache instead of a copy of the file. Procfs files cannot
+/// be mapped; this is meant for archived snapshots.
+///
+/// [`parse`]: MappedFile::parse
+#[derive(Debug)]
+pub struct MappedFile {
+    map: Mmap,
+}
+
+impl MappedFile {
+    /// Maps the file at `path`.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated, by this or any other
+    /// process, while the mapping is alive. The kernel does not protect the
+    /// mapping from other writers: the borrowed text could change under the
+    /// parser or stop being UTF-8, and reading past a truncated end raises
+    /// `SIGBUS`. See [`Mmap::map`].
+    pub unsafe fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: upheld by the caller.
+        let map = unsafe { Mmap::map(&file)? };
+        Ok(Self { map })
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    /// The contents, checked to be UTF-8 like [`std::fs::read_to_string`]
+    /// does.
+    pub fn as_str(&self) -> Result<&str> {
+        str::from_utf8(self.as_bytes())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e).into())
+    }
+
+    /// Parses the mapped text without copying it; field values borrow from
+    /// the mapping.
+    pub fn parse(&self) -> Result<CpuInfo<'_>> {
+        cpuinfo(self.as_str()?)
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Like [`CpuInfo::from_path`], reading the file through a mapping
+    /// rather than into a buffer. The parsed fields are still copied into
+    /// the owned result; use [`MappedFile`] directly to keep borrowing from
+    /// the mapping instead.
+    ///
+    /// # Safety
+    ///
+    /// As for [`MappedFile::open`], the file must not change until this
+    /// returns.
+    pub unsafe fn from_mmap<P: AsRef<Path>>(path: P) -> Result<CpuInfoOwned> {
+        // SAFETY: upheld by the caller.
+        let mapped = unsafe { MappedFile::open(path)? };
+        Ok(mapped.parse()?.to_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::SKYLAKE, CpuInfoError};
+    use std::fs;
+
+    // Each test maps its own file, which nothing writes to once mapped.
+    fn write(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("cpuinfo-mmap-{}-{}", name, std::process::id()));
+        fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn parses_mapped_file() {
+        let path = write("skylake", SKYLAKE.as_bytes());
+        let mapped = unsafe { MappedFile::open(&path) }.unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let cpuinfo = mapped.parse().unwrap();
+        assert_eq!(cpuinfo.cpus[0].model, 94);
+        let model_name = cpuinfo.cpus[0].model_name.as_ptr();
+        assert!(mapped.as_bytes().as_ptr_range().contains(&model_name));
+    }
+
+    #[test]
+    fn reads_through_mapping() {
+        let path = write("owned", SKYLAKE.as_bytes());
+        let result = unsafe { CpuInfo::from_mmap(&path) };
+        fs::remove_file(&path).unwrap();
+        assert_eq!(result.unwrap().cpus[0].vendor_id, "GenuineIntel");
+    }
+
+    #[test]
+    fn rejects_bad_files() {
+        let path = write("empty", b"");
+        let mapped = unsafe { MappedFile::open(&path) }.unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(mapped.as_bytes(), b"");
+
+        let path = write("binary", b"processor\t: \xff\n");
+        let mapped = unsafe { MappedFile::open(&path) }.unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(
+            matches!(mapped.parse(), Err(CpuInfoError::Io(e)) if e.kind() == io::ErrorKind::InvalidData)
+        );
+
+        assert!(unsafe { MappedFile::open(&path) }.is_err());
+    }
+}
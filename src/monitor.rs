@@ -0,0 +1,253 @@
+#[cfg(feature = "tokio")]
+use std::io;
+use std::{
+    fs,
+    path::PathBuf,
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{cpuinfo_lenient, Frequency, Result, Sysfs, Temperatures};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Source {
+    Cpuinfo(PathBuf),
+    Cpufreq(Sysfs),
+}
+
+/// Frequencies of all cpus at one point in time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sample {
+    pub at: Instant,
+    /// `(processor, frequency)` pairs, in processor order.
+    pub frequencies: Vec<(u32, Frequency)>,
+    /// Set when the monitor was asked to read hwmon sensors.
+    pub temperatures: Option<Temperatures>,
+}
+
+/// Periodically samples cpu frequencies, from `/proc/cpuinfo` by default.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Monitor {
+    source: Source,
+    hwmon: Option<Sysfs>,
+    interval: Duration,
+}
+
+impl Monitor {
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            source: Source::Cpuinfo(PathBuf::from("/proc/cpuinfo")),
+            hwmon: None,
+            interval,
+        }
+    }
+
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.source = Source::Cpuinfo(path.into());
+        self
+    }
+
+    /// Reads `scaling_cur_freq` from cpufreq instead of parsing cpuinfo.
+    pub fn cpufreq(mut self, sysfs: Sysfs) -> Self {
+        self.source = Source::Cpufreq(sysfs);
+        self
+    }
+
+    /// Also reads cpu temperatures from the hwmon sensors in `sysfs`.
+    pub fn temperatures(mut self, sysfs: Sysfs) -> Self {
+        self.hwmon = Some(sysfs);
+        self
+    }
+
+    pub fn sample(&self) -> Result<Sample> {
+        let frequencies = match &self.source {
+            Source::Cpuinfo(path) => {
+                let buffer = fs::read_to_string(path)?;
+                cpuinfo_lenient(&buffer)
+                    .cpuinfo
+                    .cpus
+                    .iter()
+                    .map(|cpu| (cpu.processor, cpu.cpu_mhz))
+                    .collect()
+            }
+            Source::Cpufreq(sysfs) => sysfs
+                .cpu_ids()?
+                .into_iter()
+                .filter_map(|processor| {
+                    let khz: u64 =
+                        sysfs.parse(Sysfs::cpu_path(processor, "cpufreq/scaling_cur_freq"))?;
+                    Some((processor, Frequency::from_khz(khz)))
+                })
+                .collect(),
+        };
+
+        Ok(Sample {
+            at: Instant::now(),
+            frequencies,
+            temperatures: self.hwmon.as_ref().map(Temperatures::read).transpose()?,
+        })
+    }
+
+    /// An endless iterator of samples. The first is taken immediately, the
+    /// following ones after sleeping for the interval.
+    pub fn samples(&self) -> Samples<'_> {
+        Samples {
+            monitor: self,
+            first: true,
+        }
+    }
+
+    /// Calls `callback` with every sample until it returns `false` or a
+    /// sample fails.
+    pub fn run<F: FnMut(&Sample) -> bool>(&self, mut callback: F) -> Result<()> {
+        for sample in self.samples() {
+            if !callback(&sample?) {
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+pub struct Samples<'a> {
+    monitor: &'a Monitor,
+    first: bool,
+}
+
+/// Asynchronous counterparts for tokio, reading on the blocking pool.
+#[cfg(feature = "tokio")]
+impl Monitor {
+    pub async fn sample_async(&self) -> Result<Sample> {
+        let monitor = self.clone();
+        tokio::task::spawn_blocking(move || monitor.sample())
+            .await
+            .map_err(io::Error::other)?
+    }
+
+    /// Like [`Monitor::run`], with the first sample taken immediately.
+    /// Ticks missed because the callback was slow are not made up for.
+    pub async fn run_async<F: FnMut(&Sample) -> bool>(&self, mut callback: F) -> Result<()> {
+        let mut interval = tokio::time::interval(self.interval);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            interval.tick().await;
+            if !callback(&self.sample_async().await?) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+impl Iterator for Samples<'_> {
+    type Item = Result<Sample>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.first {
+            thread::sleep(self.monitor.interval);
+        }
+        self.first = false;
+        Some(self.monitor.sample())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{FakeSysfs, SKYLAKE};
+
+    #[test]
+    fn samples_cpuinfo() {
+        let fake = FakeSysfs::new("monitor-cpuinfo", &[("cpuinfo", SKYLAKE)]);
+        let monitor = Monitor::new(Duration::ZERO).path(fake.root.join("cpuinfo"));
+
+        let samples: Vec<Sample> = monitor.samples().take(2).map(|s| s.unwrap()).collect();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].frequencies, [(0, Frequency::from_mhz(4000.0))]);
+        assert!(samples[0].at <= samples[1].at);
+    }
+
+    #[test]
+    fn samples_cpufreq() {
+        let fake = FakeSysfs::new(
+            "monitor-cpufreq",
+            &[
+                (
+                    "devices/system/cpu/cpu0/cpufreq/scaling_cur_freq",
+                    "800000\n",
+                ),
+                (
+                    "devices/system/cpu/cpu1/cpufreq/scaling_cur_freq",
+                    "4200000\n",
+                ),
+            ],
+        );
+        let monitor = Monitor::new(Duration::ZERO).cpufreq(Sysfs::new(&fake.root));
+
+        let mut count = 0;
+        monitor
+            .run(|sample| {
+                assert_eq!(
+                    sample.frequencies,
+                    [
+                        (0, Frequency::from_mhz(800.0)),
+                        (1, Frequency::from_mhz(4200.0))
+                    ]
+                );
+                count += 1;
+                count < 3
+            })
+            .unwrap();
+        assert_eq!(count, 3);
+    }
+
+    #[test]
+    fn samples_temperatures() {
+        let fake = FakeSysfs::new(
+            "monitor-hwmon",
+            &[
+                ("cpuinfo", SKYLAKE),
+                ("class/hwmon/hwmon0/name", "coretemp\n"),
+                ("class/hwmon/hwmon0/temp1_label", "Package id 0\n"),
+                ("class/hwmon/hwmon0/temp1_input", "45000\n"),
+            ],
+        );
+        let monitor = Monitor::new(Duration::ZERO).path(fake.root.join("cpuinfo"));
+        assert_eq!(monitor.sample().unwrap().temperatures, None);
+
+        let sample = monitor
+            .temperatures(Sysfs::new(&fake.root))
+            .sample()
+            .unwrap();
+        assert_eq!(sample.temperatures.unwrap().package(0), Some(45.0));
+    }
+
+    #[cfg(feature = "tokio")]
+    #[test]
+    fn samples_asynchronously() {
+        let fake = FakeSysfs::new("monitor-async", &[("cpuinfo", SKYLAKE)]);
+        let monitor = Monitor::new(Duration::from_millis(1)).path(fake.root.join("cpuinfo"));
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+
+        let mut samples = Vec::new();
+        runtime
+            .block_on(monitor.run_async(|sample| {
+                samples.push(sample.clone());
+                samples.len() < 2
+            }))
+            .unwrap();
+        assert_eq!(samples.len(), 2);
+        assert_eq!(samples[1].frequencies, [(0, Frequency::from_mhz(4000.0))]);
+
+        let missing = Monitor::new(Duration::ZERO).path("/nonexistent/cpuinfo");
+        assert!(runtime.block_on(missing.sample_async()).is_err());
+    }
+
+    #[test]
+    fn fails_on_missing_source() {
+        let monitor = Monitor::new(Duration::ZERO).path("/nonexistent/cpuinfo");
+        assert!(monitor.run(|_| true).is_err());
+    }
+}
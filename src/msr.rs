@@ -0,0 +1,147 @@
+use std::{
+    fs::File,
+    io,
+    os::unix::fs::FileExt,
+    path::{Path, PathBuf},
+};
+
+use crate::{Cpu, Result};
+
+const MSR_PLATFORM_INFO: u32 = 0xce;
+const IA32_PERF_STATUS: u32 = 0x198;
+const MSR_TURBO_RATIO_LIMIT: u32 = 0x1ad;
+const IA32_ENERGY_PERF_BIAS: u32 = 0x1b0;
+
+/// Reads model specific registers through the `msr` driver, which exposes
+/// them as `/dev/cpu/N/msr`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct MsrReader {
+    root: PathBuf,
+}
+
+impl Default for MsrReader {
+    fn default() -> Self {
+        Self::new("/dev/cpu")
+    }
+}
+
+/// Registers of interest for one cpu. A register the cpu does not
+/// implement is `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Msrs {
+    /// Maximum non-turbo ratio, in multiples of the 100 MHz bus clock.
+    pub base_ratio: Option<u8>,
+    pub current_ratio: Option<u8>,
+    /// Maximum turbo ratio with 1, 2, ... cores active.
+    pub turbo_ratios: Option<Vec<u8>>,
+    /// 0 favours performance, 15 energy saving.
+    pub energy_perf_bias: Option<u8>,
+}
+
+impl MsrReader {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    fn open(&self, processor: u32) -> io::Result<File> {
+        let path = self.root.join(processor.to_string()).join("msr");
+        File::open(&path).map_err(|e| match e.kind() {
+            io::ErrorKind::PermissionDenied => io::Error::new(
+                e.kind(),
+                format!(
+                    "cannot open {}, reading MSRs needs CAP_SYS_RAWIO",
+                    path.display()
+                ),
+            ),
+            io::ErrorKind::NotFound => io::Error::new(
+                e.kind(),
+                format!("{} not found, is the msr module loaded?", path.display()),
+            ),
+            _ => e,
+        })
+    }
+
+    pub fn read(&self, processor: u32, register: u32) -> Result<u64> {
+        let mut value = [0; 8];
+        self.open(processor)?
+            .read_exact_at(&mut value, register.into())?;
+        Ok(u64::from_le_bytes(value))
+    }
+}
+
+impl Cpu<'_> {
+    /// Fails when the msr device cannot be opened. Registers the cpu does
+    /// not implement, which the driver reports as I/O errors, are `None`.
+    pub fn msrs(&self, reader: &MsrReader) -> Result<Msrs> {
+        let file = reader.open(self.processor)?;
+        let read = |register: u32| {
+            let mut value = [0; 8];
+            file.read_exact_at(&mut value, register.into())
+                .ok()
+                .map(|()| u64::from_le_bytes(value))
+        };
+        let byte = |value: u64, n: u32| (value >> (n * 8)) as u8;
+
+        Ok(Msrs {
+            base_ratio: read(MSR_PLATFORM_INFO).map(|v| byte(v, 1)),
+            current_ratio: read(IA32_PERF_STATUS).map(|v| byte(v, 1)),
+            turbo_ratios: read(MSR_TURBO_RATIO_LIMIT).map(|v| {
+                (0..8)
+                    .map(|n| byte(v, n))
+                    .take_while(|&ratio| ratio != 0)
+                    .collect()
+            }),
+            energy_perf_bias: read(IA32_ENERGY_PERF_BIAS).map(|v| (v & 0xf) as u8),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::FakeSysfs, CpuBuilder, CpuInfoError};
+
+    #[test]
+    fn reads_msrs() {
+        let fake = FakeSysfs::new("msr", &[("0/msr", "")]);
+        let file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(fake.root.join("0/msr"))
+            .unwrap();
+        file.write_all_at(&0x2800u64.to_le_bytes(), MSR_PLATFORM_INFO.into())
+            .unwrap();
+        // The file ends within the energy/performance bias register, which
+        // then reads like one the cpu does not implement.
+        file.write_all_at(&0x2a2b2cu64.to_le_bytes(), MSR_TURBO_RATIO_LIMIT.into())
+            .unwrap();
+
+        let reader = MsrReader::new(&fake.root);
+        assert_eq!(reader.read(0, MSR_PLATFORM_INFO).unwrap(), 0x2800);
+
+        let msrs = CpuBuilder::new().build().msrs(&reader).unwrap();
+        assert_eq!(
+            msrs,
+            Msrs {
+                base_ratio: Some(40),
+                current_ratio: Some(0),
+                turbo_ratios: Some(vec![44, 43, 42]),
+                energy_perf_bias: None,
+            }
+        );
+    }
+
+    #[test]
+    fn reports_missing_device() {
+        let fake = FakeSysfs::new("msr-missing", &[]);
+        let cpu = CpuBuilder::new().build();
+        match cpu.msrs(&MsrReader::new(&fake.root)) {
+            Err(CpuInfoError::Io(e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::NotFound);
+                assert!(e.to_string().contains("msr module"));
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,142 @@
+use std::{io, path::PathBuf};
+
+use crate::{sysfs::cpu_list, Result, Sysfs};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumaNode {
+    pub id: u32,
+    pub cpus: Vec<u32>,
+    /// Memory sizes in bytes, from the node's `meminfo`.
+    pub memory_total: Option<u64>,
+    pub memory_free: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumaTopology {
+    pub nodes: Vec<NumaNode>,
+}
+
+/// Finds `field` in a node's meminfo, where lines look like
+/// `Node 0 MemTotal:       32763468 kB`.
+fn meminfo(meminfo: &str, field: &str) -> Option<u64> {
+    meminfo.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if key.split_whitespace().last()? != field {
+            return None;
+        }
+        let mut value = value.split_whitespace();
+        let size: u64 = value.next()?.parse().ok()?;
+        match value.next() {
+            Some("kB") => size.checked_mul(1 << 10),
+            None => Some(size),
+            Some(_) => None,
+        }
+    })
+}
+
+impl NumaTopology {
+    /// Reads nodes from `devices/system/node`. Kernels built without NUMA
+    /// lack the directory, which is reported as a single node holding
+    /// every cpu.
+    pub fn read(sysfs: &Sysfs) -> Result<Self> {
+        let ids = match sysfs.numbered("devices/system/node", "node") {
+            Ok(ids) => ids,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(Self {
+                    nodes: vec![NumaNode {
+                        id: 0,
+                        cpus: sysfs.cpu_ids().unwrap_or_default(),
+                        ..Default::default()
+                    }],
+                });
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut nodes = Vec::new();
+        for id in ids {
+            let dir = PathBuf::from(format!("devices/system/node/node{}", id));
+            let text = sysfs.read(dir.join("cpulist"))?;
+            let cpus = cpu_list(&text).ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("invalid cpu list `{}` for node {}", text, id),
+                )
+            })?;
+            let info = sysfs.read(dir.join("meminfo")).unwrap_or_default();
+            nodes.push(NumaNode {
+                id,
+                cpus,
+                memory_total: meminfo(&info, "MemTotal"),
+                memory_free: meminfo(&info, "MemFree"),
+            });
+        }
+        Ok(Self { nodes })
+    }
+
+    pub fn node(&self, id: u32) -> Option<&NumaNode> {
+        self.nodes.iter().find(|node| node.id == id)
+    }
+
+    /// The node a logical cpu belongs to.
+    pub fn node_of(&self, processor: u32) -> Option<u32> {
+        self.nodes
+            .iter()
+            .find(|node| node.cpus.contains(&processor))
+            .map(|node| node.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::FakeSysfs;
+
+    #[test]
+    fn reads_nodes() {
+        let fake = FakeSysfs::new(
+            "numa",
+            &[
+                ("devices/system/node/node0/cpulist", "0-3,8-11\n"),
+                (
+                    "devices/system/node/node0/meminfo",
+                    "Node 0 MemTotal:       32763468 kB\nNode 0 MemFree:        1024 kB\n",
+                ),
+                ("devices/system/node/node1/cpulist", "4-7,12-15\n"),
+                ("devices/system/node/possible", "0-1\n"),
+            ],
+        );
+        let numa = NumaTopology::read(&Sysfs::new(&fake.root)).unwrap();
+
+        assert_eq!(numa.nodes.len(), 2);
+        assert_eq!(numa.node_of(9), Some(0));
+        assert_eq!(numa.node_of(12), Some(1));
+        assert_eq!(numa.node_of(16), None);
+        assert_eq!(
+            numa.node(0),
+            Some(&NumaNode {
+                id: 0,
+                cpus: vec![0, 1, 2, 3, 8, 9, 10, 11],
+                memory_total: Some(32763468 << 10),
+                memory_free: Some(1024 << 10),
+            })
+        );
+        assert_eq!(numa.node(1).unwrap().memory_total, None);
+    }
+
+    #[test]
+    fn falls_back_to_a_single_node() {
+        let fake = FakeSysfs::new(
+            "numa-none",
+            &[
+                ("devices/system/cpu/cpu0/online", "1\n"),
+                ("devices/system/cpu/cpu1/online", "1\n"),
+            ],
+        );
+        let numa = NumaTopology::read(&Sysfs::new(&fake.root)).unwrap();
+        assert_eq!(numa.nodes.len(), 1);
+        assert_eq!(numa.nodes[0].cpus, [0, 1]);
+    }
+}
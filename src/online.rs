@@ -0,0 +1,110 @@
+use std::io;
+
+use crate::{sysfs::cpu_list, Cpu, CpuInfo, Result, Sysfs};
+
+/// The cpu sets the kernel keeps in `devices/system/cpu`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuPresence {
+    pub online: Vec<u32>,
+    pub offline: Vec<u32>,
+    pub present: Vec<u32>,
+    pub possible: Vec<u32>,
+}
+
+fn read_list(sysfs: &Sysfs, name: &str) -> Result<Vec<u32>> {
+    let text = match sysfs.read(format!("devices/system/cpu/{}", name)) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    cpu_list(&text).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid cpu list `{}` in {}", text, name),
+        )
+        .into()
+    })
+}
+
+impl CpuPresence {
+    /// Reads the `online`, `offline`, `present` and `possible` lists. Missing
+    /// files are treated as empty.
+    pub fn read(sysfs: &Sysfs) -> Result<Self> {
+        Ok(Self {
+            online: read_list(sysfs, "online")?,
+            offline: read_list(sysfs, "offline")?,
+            present: read_list(sysfs, "present")?,
+            possible: read_list(sysfs, "possible")?,
+        })
+    }
+
+    pub fn is_online(&self, processor: u32) -> bool {
+        self.online.contains(&processor)
+    }
+}
+
+impl Cpu<'_> {
+    pub fn is_online(&self, presence: &CpuPresence) -> bool {
+        presence.is_online(self.processor)
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Cpus that are present but missing from cpuinfo because they are
+    /// offline.
+    pub fn offline_cpus(&self, presence: &CpuPresence) -> Vec<u32> {
+        presence
+            .present
+            .iter()
+            .copied()
+            .filter(|&processor| {
+                !presence.is_online(processor)
+                    && !self.cpus.iter().any(|cpu| cpu.processor == processor)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::FakeSysfs, CpuBuilder, CpuInfoBuilder};
+
+    #[test]
+    fn reads_cpu_presence() {
+        let fake = FakeSysfs::new(
+            "online",
+            &[
+                ("devices/system/cpu/online", "0,2-3\n"),
+                ("devices/system/cpu/offline", "1,4-7\n"),
+                ("devices/system/cpu/present", "0-3\n"),
+                ("devices/system/cpu/possible", "0-7\n"),
+            ],
+        );
+        let presence = CpuPresence::read(&Sysfs::new(&fake.root)).unwrap();
+        assert_eq!(presence.online, [0, 2, 3]);
+        assert_eq!(presence.possible.len(), 8);
+
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(CpuBuilder::new().processor(0).build())
+            .cpu(CpuBuilder::new().processor(2).build())
+            .cpu(CpuBuilder::new().processor(3).build())
+            .build();
+        assert!(cpuinfo.cpus.iter().all(|cpu| cpu.is_online(&presence)));
+        assert_eq!(cpuinfo.offline_cpus(&presence), [1]);
+    }
+
+    #[test]
+    fn treats_missing_lists_as_empty() {
+        let fake = FakeSysfs::new("online-missing", &[("devices/system/cpu/online", "0\n")]);
+        let presence = CpuPresence::read(&Sysfs::new(&fake.root)).unwrap();
+        assert_eq!(
+            presence,
+            CpuPresence {
+                online: vec![0],
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    cpuinfo, cpuinfo_guest, cpuinfo_lenient, partial::blocks, CpuInfoError, Parsed, Result,
+};
+use alloc::vec::Vec;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Mode {
+    #[default]
+    Strict,
+    Guest,
+    Lenient,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Architecture {
+    X86,
+    Arm,
+    LoongArch,
+    Mips,
+    PowerPc,
+    RiscV,
+    S390,
+    Sparc,
+}
+
+impl Architecture {
+    /// Guesses the architecture from keys that only one kernel port prints.
+    pub fn detect(input: &str) -> Option<Self> {
+        let keys: Vec<&str> = input
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, _)| key.trim())
+            .collect();
+        let has = |key: &str| keys.contains(&key);
+
+        if has("# processors") {
+            Some(Self::S390)
+        } else if has("ncpus probed") {
+            Some(Self::Sparc)
+        } else if has("CPU implementer") {
+            Some(Self::Arm)
+        } else if has("Model Name") && has("CPU Family") {
+            Some(Self::LoongArch)
+        } else if has("cpu model") {
+            Some(Self::Mips)
+        } else if has("hart") {
+            Some(Self::RiscV)
+        } else if has("timebase") {
+            Some(Self::PowerPc)
+        } else if has("vendor_id") && has("flags") {
+            Some(Self::X86)
+        } else {
+            None
+        }
+    }
+}
+
+/// Tunes [`cpuinfo_with_options`]. Units need no option: sizes are always
+/// parsed into a [`CacheSize`](crate::CacheSize) in bytes and clocks into a
+/// [`Frequency`](crate::Frequency), whichever unit the kernel printed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct ParserOptions {
+    pub mode: Mode,
+    /// Reject input that does not look like x86 cpuinfo, e.g. a file
+    /// collected on another architecture, before parsing it. The options
+    /// only drive the x86 parser; other formats have their own modules.
+    pub require_x86: bool,
+    pub max_cpus: Option<usize>,
+    pub max_input_len: Option<usize>,
+}
+
+pub fn cpuinfo_with_options<'a>(input: &'a str, options: &ParserOptions) -> Result<Parsed<'a>> {
+    if let Some(max) = options.max_input_len {
+        if input.len() > max {
+            return Err(CpuInfoError::InputTooLarge {
+                len: input.len(),
+                limit: max,
+            });
+        }
+    }
+
+    if options.require_x86 {
+        let found = Architecture::detect(input);
+        if found != Some(Architecture::X86) {
+            return Err(CpuInfoError::ArchitectureMismatch {
+                expected: Architecture::X86,
+                found,
+            });
+        }
+    }
+
+    if let Some(max) = options.max_cpus {
+        let count = blocks(input).count();
+        if count > max {
+            return Err(CpuInfoError::TooManyCpus { count, limit: max });
+        }
+    }
+
+    Ok(match options.mode {
+        Mode::Strict => Parsed {
+            cpuinfo: cpuinfo(input)?,
+            skipped: Vec::new(),
+        },
+        Mode::Guest => Parsed {
+            cpuinfo: cpuinfo_guest(input)?,
+            skipped: Vec::new(),
+        },
+        Mode::Lenient => cpuinfo_lenient(input),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{fixtures::SKYLAKE, CacheSize};
+    use alloc::format;
+
+    #[test]
+    fn detects_architecture() {
+        assert_eq!(Architecture::detect(SKYLAKE), Some(Architecture::X86));
+        assert_eq!(
+            Architecture::detect("processor	: 0\nhart		: 1\nisa		: rv64imafdc\n"),
+            Some(Architecture::RiscV)
+        );
+        assert_eq!(Architecture::detect("garbage\n"), None);
+    }
+
+    #[test]
+    fn parses_with_default_options() {
+        let result = cpuinfo_with_options(SKYLAKE, &ParserOptions::default());
+        assert!(result.is_ok());
+
+        let parsed = result.unwrap();
+        assert_eq!(parsed.cpuinfo.cpus.len(), 1);
+        assert_eq!(
+            parsed.cpuinfo.cpus[0].cache_size,
+            Some(CacheSize::from_kib(8192))
+        );
+        assert!(parsed.skipped.is_empty());
+    }
+
+    #[test]
+    fn normalizes_units() {
+        let input = SKYLAKE.replace("8192 KB", "8 MB");
+        let parsed = cpuinfo_with_options(&input, &ParserOptions::default()).unwrap();
+        assert_eq!(
+            parsed.cpuinfo.cpus[0].cache_size,
+            Some(CacheSize::from_kib(8192))
+        );
+    }
+
+    #[test]
+    fn rejects_unexpected_architecture() {
+        let options = ParserOptions {
+            require_x86: true,
+            ..Default::default()
+        };
+        assert!(cpuinfo_with_options(SKYLAKE, &options).is_ok());
+        assert!(matches!(
+            cpuinfo_with_options("processor	: 0\nhart		: 1\nisa		: rv64imafdc\n", &options),
+            Err(CpuInfoError::ArchitectureMismatch {
+                expected: Architecture::X86,
+                found: Some(Architecture::RiscV),
+            })
+        ));
+    }
+
+    #[test]
+    fn enforces_limits() {
+        let options = ParserOptions {
+            max_input_len: Some(16),
+            ..Default::default()
+        };
+        assert!(cpuinfo_with_options(SKYLAKE, &options).is_err());
+
+        let options = ParserOptions {
+            max_cpus: Some(0),
+            ..Default::default()
+        };
+        assert!(cpuinfo_with_options(SKYLAKE, &options).is_err());
+
+        // The limit applies before parsing, so a broken block past it is
+        // never reached.
+        let options = ParserOptions {
+            max_cpus: Some(1),
+            ..Default::default()
+        };
+        let input = format!("{SKYLAKE}\nprocessor	: 1\ngarbage\n");
+        assert!(matches!(
+            cpuinfo_with_options(&input, &options),
+            Err(CpuInfoError::TooManyCpus { count: 2, limit: 1 })
+        ));
+    }
+}
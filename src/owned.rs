@@ -0,0 +1,376 @@
+use alloc::{
+    collections::BTreeSet,
+    string::{String, ToString},
+    sync::Arc,
+    vec::Vec,
+};
+
+use crate::{AddressSizes, CacheSize, Cpu, CpuInfo, Frequency, SizeUnit, TlbSize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuInfoOwned {
+    pub cpus: Vec<CpuOwned>,
+}
+
+/// An owned [`Cpu`]. The model name and the flag, bug and power
+/// management lists are shared between cpus converted together, so the
+/// threads of one socket hold a pointer each rather than a copy, see
+/// [`CpuInfoOwned::intern`].
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuOwned {
+    pub processor: u32,
+    pub vendor_id: String,
+    pub cpu_family: u32,
+    pub model: u32,
+    pub model_name: Arc<str>,
+    pub stepping: Option<u32>,
+    pub microcode: Option<u64>,
+    pub cpu_mhz: Frequency,
+    pub cache_size: Option<CacheSize>,
+    pub cache_size_unit: SizeUnit,
+    pub physical_id: Option<u32>,
+    pub siblings: u32,
+    pub core_id: u32,
+    pub cpu_cores: u32,
+    pub apicid: u32,
+    pub initial_apicid: u32,
+    pub fpu: bool,
+    pub fpu_exception: bool,
+    pub cpuid_level: u32,
+    pub wp: bool,
+    pub flags: Arc<[Arc<str>]>,
+    pub vmx_flags: Arc<[Arc<str>]>,
+    pub svm_flags: Arc<[Arc<str>]>,
+    pub bugs: Arc<[Arc<str>]>,
+    pub bogomips: f64,
+    pub tlb_size: Option<TlbSize>,
+    pub clflush_size: u32,
+    pub cache_alignment: u32,
+    pub address_sizes: AddressSizes,
+    pub power_management: Arc<[Arc<str>]>,
+}
+
+impl_eq_hash!(CpuOwned {
+    floats: [bogomips],
+    fields: [
+        processor,
+        vendor_id,
+        cpu_family,
+        model,
+        model_name,
+        stepping,
+        microcode,
+        cpu_mhz,
+        cache_size,
+        cache_size_unit,
+        physical_id,
+        siblings,
+        core_id,
+        cpu_cores,
+        apicid,
+        initial_apicid,
+        fpu,
+        fpu_exception,
+        cpuid_level,
+        wp,
+        flags,
+        vmx_flags,
+        svm_flags,
+        bugs,
+        tlb_size,
+        clflush_size,
+        cache_alignment,
+        address_sizes,
+        power_management,
+    ],
+});
+
+/// Hands out one shared copy of every distinct string and list, so that
+/// the flags every cpu repeats are stored once per snapshot rather than
+/// once per cpu.
+#[derive(Default)]
+struct Interner {
+    strings: BTreeSet<Arc<str>>,
+    lists: BTreeSet<Arc<[Arc<str>]>>,
+}
+
+impl Interner {
+    fn intern(&mut self, s: &str) -> Arc<str> {
+        if let Some(interned) = self.strings.get(s) {
+            return interned.clone();
+        }
+        let interned: Arc<str> = Arc::from(s);
+        self.strings.insert(interned.clone());
+        interned
+    }
+
+    fn list<S: AsRef<str>>(&mut self, list: &[S]) -> Arc<[Arc<str>]> {
+        let list: Vec<Arc<str>> = list.iter().map(|s| self.intern(s.as_ref())).collect();
+        if let Some(interned) = self.lists.get(list.as_slice()) {
+            return interned.clone();
+        }
+        let interned: Arc<[Arc<str>]> = Arc::from(list);
+        self.lists.insert(interned.clone());
+        interned
+    }
+}
+
+impl CpuOwned {
+    fn interned(cpu: &Cpu<'_>, strings: &mut Interner) -> Self {
+        Self {
+            processor: cpu.processor,
+            vendor_id: cpu.vendor_id.to_string(),
+            cpu_family: cpu.cpu_family,
+            model: cpu.model,
+            model_name: strings.intern(cpu.model_name),
+            stepping: cpu.stepping,
+            microcode: cpu.microcode,
+            cpu_mhz: cpu.cpu_mhz,
+            cache_size: cpu.cache_size,
+            cache_size_unit: cpu.cache_size_unit,
+            physical_id: cpu.physical_id,
+            siblings: cpu.siblings,
+            core_id: cpu.core_id,
+            cpu_cores: cpu.cpu_cores,
+            apicid: cpu.apicid,
+            initial_apicid: cpu.initial_apicid,
+            fpu: cpu.fpu,
+            fpu_exception: cpu.fpu_exception,
+            cpuid_level: cpu.cpuid_level,
+            wp: cpu.wp,
+            flags: strings.list(&cpu.flags),
+            vmx_flags: strings.list(&cpu.vmx_flags),
+            svm_flags: strings.list(&cpu.svm_flags),
+            bugs: strings.list(&cpu.bugs),
+            bogomips: cpu.bogomips,
+            tlb_size: cpu.tlb_size.clone(),
+            clflush_size: cpu.clflush_size,
+            cache_alignment: cpu.cache_alignment,
+            address_sizes: cpu.address_sizes.clone(),
+            power_management: strings.list(&cpu.power_management),
+        }
+    }
+
+    fn intern(&mut self, strings: &mut Interner) {
+        self.model_name = strings.intern(&self.model_name);
+        for list in [
+            &mut self.flags,
+            &mut self.vmx_flags,
+            &mut self.svm_flags,
+            &mut self.bugs,
+            &mut self.power_management,
+        ] {
+            *list = strings.list(list);
+        }
+    }
+}
+
+impl From<&Cpu<'_>> for CpuOwned {
+    fn from(cpu: &Cpu<'_>) -> Self {
+        Self::interned(cpu, &mut Interner::default())
+    }
+}
+
+impl From<Cpu<'_>> for CpuOwned {
+    fn from(cpu: Cpu<'_>) -> Self {
+        Self::from(&cpu)
+    }
+}
+
+impl From<&CpuInfo<'_>> for CpuInfoOwned {
+    fn from(cpuinfo: &CpuInfo<'_>) -> Self {
+        let mut strings = Interner::default();
+        Self {
+            cpus: cpuinfo
+                .cpus
+                .iter()
+                .map(|cpu| CpuOwned::interned(cpu, &mut strings))
+                .collect(),
+        }
+    }
+}
+
+impl From<CpuInfo<'_>> for CpuInfoOwned {
+    fn from(cpuinfo: CpuInfo<'_>) -> Self {
+        Self::from(&cpuinfo)
+    }
+}
+
+impl Cpu<'_> {
+    pub fn to_owned(&self) -> CpuOwned {
+        CpuOwned::from(self)
+    }
+}
+
+impl CpuInfo<'_> {
+    pub fn to_owned(&self) -> CpuInfoOwned {
+        CpuInfoOwned::from(self)
+    }
+}
+
+fn strs(list: &[Arc<str>]) -> Vec<&str> {
+    list.iter().map(|s| &**s).collect()
+}
+
+impl CpuOwned {
+    /// Borrows the strings back into a [`Cpu`], giving access to the
+    /// methods only defined there.
+    pub fn as_cpu(&self) -> Cpu<'_> {
+        Cpu {
+            processor: self.processor,
+            vendor_id: &self.vendor_id,
+            cpu_family: self.cpu_family,
+            model: self.model,
+            model_name: &self.model_name,
+            stepping: self.stepping,
+            microcode: self.microcode,
+            cpu_mhz: self.cpu_mhz,
+            cache_size: self.cache_size,
+            cache_size_unit: self.cache_size_unit,
+            physical_id: self.physical_id,
+            siblings: self.siblings,
+            core_id: self.core_id,
+            cpu_cores: self.cpu_cores,
+            apicid: self.apicid,
+            initial_apicid: self.initial_apicid,
+            fpu: self.fpu,
+            fpu_exception: self.fpu_exception,
+            cpuid_level: self.cpuid_level,
+            wp: self.wp,
+            flags: strs(&self.flags),
+            vmx_flags: strs(&self.vmx_flags),
+            svm_flags: strs(&self.svm_flags),
+            bugs: strs(&self.bugs),
+            bogomips: self.bogomips,
+            tlb_size: self.tlb_size.clone(),
+            clflush_size: self.clflush_size,
+            cache_alignment: self.cache_alignment,
+            address_sizes: self.address_sizes.clone(),
+            power_management: strs(&self.power_management),
+        }
+    }
+}
+
+impl CpuInfoOwned {
+    pub fn as_cpuinfo(&self) -> CpuInfo<'_> {
+        CpuInfo {
+            cpus: self.cpus.iter().map(CpuOwned::as_cpu).collect(),
+        }
+    }
+
+    /// Shares identical strings and lists between cpus. Conversions from
+    /// [`CpuInfo`] already do this, but deserialized or hand-built
+    /// snapshots hold a copy per cpu.
+    pub fn intern(&mut self) {
+        let mut strings = Interner::default();
+        for cpu in &mut self.cpus {
+            cpu.intern(&mut strings);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, cpuinfo_lenient, fixtures::EPYC};
+    use alloc::{format, vec};
+
+    #[test]
+    fn outlives_input() {
+        let owned = {
+            let input = String::from(
+                "processor	: 3
+vendor_id	: AuthenticAMD
+model name	: AMD EPYC 7B13
+flags		: fpu vme de pse
+",
+            );
+            cpuinfo_lenient(&input).cpuinfo.to_owned()
+        };
+
+        assert_eq!(owned.cpus.len(), 1);
+        assert_eq!(owned.cpus[0].processor, 3);
+        assert_eq!(owned.cpus[0].vendor_id, "AuthenticAMD");
+        assert_eq!(&*owned.cpus[0].model_name, "AMD EPYC 7B13");
+        let borrowed = owned.as_cpuinfo();
+        assert_eq!(borrowed.cpus[0].flags, vec!["fpu", "vme", "de", "pse"]);
+        assert_eq!(borrowed.cpus[0].model_name, "AMD EPYC 7B13");
+        assert!(borrowed.cpus[0].has_flag("pse"));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn round_trips_through_json() {
+        let input = "processor	: 0
+vendor_id	: GenuineIntel
+flags		: fpu vme
+";
+        let cpuinfo = cpuinfo_lenient(input).cpuinfo;
+
+        let json = serde_json::to_string(&cpuinfo).unwrap();
+        let borrowed: crate::CpuInfo = serde_json::from_str(&json).unwrap();
+        assert_eq!(borrowed.cpus[0].flags, vec!["fpu", "vme"]);
+
+        let owned: crate::CpuInfoOwned = serde_json::from_str(&json).unwrap();
+        assert_eq!(owned.cpus[0].vendor_id, "GenuineIntel");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn compares_and_hashes() {
+        use std::collections::HashSet;
+
+        let input = format!("{}\n{}", EPYC, EPYC.replace("2445.406", "2445.407"));
+        let parsed = cpuinfo(&input).unwrap();
+        assert_eq!(parsed.cpus[0], cpuinfo(EPYC).unwrap().cpus[0]);
+        assert_ne!(parsed.cpus[0], parsed.cpus[1]);
+
+        let owned = parsed.to_owned();
+        assert_eq!(owned, parsed.clone().to_owned());
+        let cpus: HashSet<_> = owned.cpus.iter().chain(&owned.cpus).collect();
+        assert_eq!(cpus.len(), 2);
+        let borrowed: HashSet<_> = [owned.as_cpuinfo(), parsed].into_iter().collect();
+        assert_eq!(borrowed.len(), 1);
+    }
+
+    #[test]
+    fn shares_strings_between_cpus() {
+        let input = format!(
+            "{}\n{}",
+            EPYC,
+            EPYC.replace("processor\t: 3", "processor\t: 4")
+        );
+        let mut owned = cpuinfo(&input).unwrap().to_owned();
+        let shared = |owned: &CpuInfoOwned| {
+            owned.cpus[0]
+                .flags
+                .iter()
+                .zip(owned.cpus[1].flags.iter())
+                .all(|(a, b)| Arc::ptr_eq(a, b))
+        };
+        assert!(shared(&owned));
+        assert!(Arc::ptr_eq(&owned.cpus[0].flags, &owned.cpus[1].flags));
+        assert!(Arc::ptr_eq(&owned.cpus[0].bugs, &owned.cpus[1].bugs));
+        assert!(Arc::ptr_eq(
+            &owned.cpus[0].model_name,
+            &owned.cpus[1].model_name
+        ));
+
+        owned.cpus[1].flags = owned.cpus[1]
+            .flags
+            .iter()
+            .map(|f| Arc::from(&**f))
+            .collect();
+        owned.cpus[1].model_name = Arc::from(&*owned.cpus[1].model_name);
+        assert!(!shared(&owned));
+        owned.intern();
+        assert!(Arc::ptr_eq(&owned.cpus[0].flags, &owned.cpus[1].flags));
+        assert!(Arc::ptr_eq(
+            &owned.cpus[0].model_name,
+            &owned.cpus[1].model_name
+        ));
+        assert!(shared(&owned));
+    }
+}
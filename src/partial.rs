@@ -0,0 +1,140 @@
+use crate::{cpu, error::Location, finish, Cpu, CpuInfoError, SCHEMA};
+use alloc::vec::Vec;
+
+#[derive(Debug)]
+pub struct ParseIssue {
+    /// Index of the offending block, counting blank-line separated blocks.
+    pub block: usize,
+    pub error: CpuInfoError,
+}
+
+/// Parses each processor block on its own, keeping the ones that parse and
+/// reporting the others instead of failing the whole input.
+pub fn cpuinfo_partial(input: &str) -> (Vec<Cpu<'_>>, Vec<ParseIssue>) {
+    let mut cpus = Vec::new();
+    let mut issues = Vec::new();
+
+    for (block, (first_line, text)) in blocks(input).enumerate() {
+        match finish(text, cpu, &SCHEMA, false) {
+            Ok(cpu) => cpus.push(cpu),
+            Err(error) => issues.push(ParseIssue {
+                block,
+                error: shift(error, first_line - 1),
+            }),
+        }
+    }
+
+    (cpus, issues)
+}
+
+/// Splits the input at blank lines, yielding each block along with the
+/// 1-based line number it starts at.
+pub(crate) fn blocks(input: &str) -> Blocks<'_> {
+    Blocks {
+        input,
+        offset: 0,
+        line: 1,
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Blocks<'a> {
+    input: &'a str,
+    offset: usize,
+    /// The number of the line at `offset`.
+    line: usize,
+}
+
+impl<'a> Iterator for Blocks<'a> {
+    type Item = (usize, &'a str);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut start = None;
+        while self.offset < self.input.len() {
+            let rest = &self.input[self.offset..];
+            let len = memchr::memchr(b'\n', rest.as_bytes()).map_or(rest.len(), |i| i + 1);
+            let (begin, line) = (self.offset, self.line);
+            self.offset += len;
+            self.line += 1;
+
+            if rest[..len].trim().is_empty() {
+                if let Some((first_line, from)) = start {
+                    return Some((first_line, &self.input[from..begin]));
+                }
+            } else if start.is_none() {
+                start = Some((line, begin));
+            }
+        }
+        start.map(|(first_line, from)| (first_line, &self.input[from..]))
+    }
+}
+
+pub(crate) fn shift(error: CpuInfoError, lines: usize) -> CpuInfoError {
+    let moved = |location: Location| Location {
+        line: location.line + lines,
+        ..location
+    };
+
+    match error {
+        CpuInfoError::UnexpectedField {
+            expected,
+            found,
+            location,
+        } => CpuInfoError::UnexpectedField {
+            expected,
+            found,
+            location: moved(location),
+        },
+        CpuInfoError::MissingField { field, location } => CpuInfoError::MissingField {
+            field,
+            location: moved(location),
+        },
+        CpuInfoError::InvalidValue {
+            field,
+            value,
+            location,
+        } => CpuInfoError::InvalidValue {
+            field,
+            value,
+            location: moved(location),
+        },
+        error => error,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::SKYLAKE;
+    use alloc::format;
+
+    #[test]
+    fn keeps_valid_blocks() {
+        let input = format!(
+            "{}\n{}\n{}\n",
+            SKYLAKE,
+            SKYLAKE
+                .replace("processor	: 0", "processor	: 1")
+                .replace("4000.000", "fast"),
+            SKYLAKE.replace("processor	: 0", "processor	: 2"),
+        );
+        let (cpus, issues) = cpuinfo_partial(&input);
+
+        assert_eq!(cpus.len(), 2);
+        assert_eq!(cpus[0].processor, 0);
+        assert_eq!(cpus[1].processor, 2);
+
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].block, 1);
+        assert!(matches!(
+            &issues[0].error,
+            CpuInfoError::InvalidValue {
+                location: Location {
+                    line: 35,
+                    processor: Some(1),
+                },
+                ..
+            }
+        ));
+    }
+}
@@ -0,0 +1,225 @@
+use alloc::vec::Vec;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alphanumeric1, line_ending, not_line_ending},
+    combinator::opt,
+    sequence::{pair, preceded, terminated},
+    IResult,
+};
+
+use crate::{blocks, field_value, finish, mhz, processor, FieldSpec, Frequency, Result, Schema};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuInfo<'a> {
+    pub cpus: Vec<Cpu<'a>>,
+    pub machine: Machine<'a>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cpu<'a> {
+    pub processor: u32,
+    pub cpu: &'a str,
+    pub clock: Frequency,
+    pub revision: &'a str,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Machine<'a> {
+    pub timebase: u64,
+    pub platform: &'a str,
+    pub model: &'a str,
+    pub machine: &'a str,
+    pub firmware: Option<&'a str>,
+    pub mmu: Option<&'a str>,
+}
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("processor"),
+        FieldSpec::required("cpu"),
+        FieldSpec::required("clock"),
+        FieldSpec::required("revision"),
+        FieldSpec::required("timebase"),
+        FieldSpec::required("platform"),
+        FieldSpec::required("model"),
+        FieldSpec::required("machine"),
+        FieldSpec::optional("firmware"),
+        FieldSpec::optional("MMU"),
+    ],
+    restart: 0,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    let (cpus, machine) = finish(
+        input,
+        pair(cpus, preceded(line_ending, machine)),
+        &SCHEMA,
+        false,
+    )?;
+    Ok(CpuInfo { cpus, machine })
+}
+
+fn cpu_name(input: &str) -> IResult<&str, &str> {
+    field_value(tag("cpu"), not_line_ending)(input)
+}
+
+fn clock(input: &str) -> IResult<&str, Frequency> {
+    field_value(tag("clock"), terminated(mhz, tag("MHz")))(input)
+}
+
+fn revision(input: &str) -> IResult<&str, &str> {
+    field_value(tag("revision"), not_line_ending)(input)
+}
+
+fn timebase(input: &str) -> IResult<&str, u64> {
+    field_value(tag("timebase"), complete::u64)(input)
+}
+
+fn platform(input: &str) -> IResult<&str, &str> {
+    field_value(tag("platform"), not_line_ending)(input)
+}
+
+fn model(input: &str) -> IResult<&str, &str> {
+    field_value(tag("model"), not_line_ending)(input)
+}
+
+fn machine_name(input: &str) -> IResult<&str, &str> {
+    field_value(tag("machine"), not_line_ending)(input)
+}
+
+fn firmware(input: &str) -> IResult<&str, &str> {
+    field_value(tag("firmware"), not_line_ending)(input)
+}
+
+fn mmu(input: &str) -> IResult<&str, &str> {
+    field_value(tag("MMU"), alphanumeric1)(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    let (input, processor) = processor(input)?;
+    let (input, cpu) = cpu_name(input)?;
+    let (input, clock) = clock(input)?;
+    let (input, revision) = revision(input)?;
+
+    let cpu = Cpu {
+        processor,
+        cpu,
+        clock,
+        revision,
+    };
+
+    Ok((input, cpu))
+}
+
+fn cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(cpu)(input)
+}
+
+fn machine(input: &str) -> IResult<&str, Machine<'_>> {
+    let (input, timebase) = timebase(input)?;
+    let (input, platform) = platform(input)?;
+    let (input, model) = model(input)?;
+    let (input, machine) = machine_name(input)?;
+    let (input, firmware) = opt(firmware)(input)?;
+    let (input, mmu) = opt(mmu)(input)?;
+
+    let machine = Machine {
+        timebase,
+        platform,
+        model,
+        machine,
+        firmware,
+        mmu,
+    };
+
+    Ok((input, machine))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cpu_name() {
+        let result = cpu_name(
+            "cpu		: POWER9 (architected), altivec supported
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "POWER9 (architected), altivec supported");
+    }
+
+    #[test]
+    fn parses_clock() {
+        let result = clock(
+            "clock		: 2166.000000MHz
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, Frequency::from_mhz(2166.0));
+    }
+
+    #[test]
+    fn parses_revision() {
+        let result = revision(
+            "revision	: 2.2 (pvr 004e 1202)
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "2.2 (pvr 004e 1202)");
+    }
+
+    #[test]
+    fn parses_machine() {
+        let result = machine(
+            "timebase	: 512000000
+platform	: PowerNV
+model		: 8335-GTH
+machine		: PowerNV 8335-GTH
+firmware	: OPAL
+MMU		: Radix
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            Machine {
+                timebase: 512000000,
+                platform: "PowerNV",
+                model: "8335-GTH",
+                machine: "PowerNV 8335-GTH",
+                firmware: Some("OPAL"),
+                mmu: Some("Radix"),
+            }
+        )
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "processor	: 0
+cpu		: POWER9 (architected), altivec supported
+clock		: 2750.000000MHz
+revision	: 2.2 (pvr 004e 0202)
+
+processor	: 1
+cpu		: POWER9 (architected), altivec supported
+clock		: 2750.000000MHz
+revision	: 2.2 (pvr 004e 0202)
+
+timebase	: 512000000
+platform	: pSeries
+model		: IBM,9009-42A
+machine		: CHRP IBM,9009-42A
+MMU		: Radix
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.cpus[1].clock, Frequency::from_mhz(2750.0));
+        assert_eq!(cpuinfo.machine.platform, "pSeries");
+        assert!(cpuinfo.machine.firmware.is_none());
+    }
+}
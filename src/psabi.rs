@@ -0,0 +1,115 @@
+use core::fmt;
+
+use crate::{Cpu, CpuInfo};
+
+/// x86-64 microarchitecture levels, as defined by the x86-64 psABI.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PsAbiLevel {
+    V1,
+    V2,
+    V3,
+    V4,
+}
+
+const V1: &[&str] = &[
+    "lm", "cmov", "cx8", "fpu", "fxsr", "mmx", "syscall", "sse", "sse2",
+];
+const V2: &[&str] = &[
+    "cx16", "lahf_lm", "popcnt", "pni", "sse4_1", "sse4_2", "ssse3",
+];
+// `abm` is how the kernel reports LZCNT, `xsave` stands in for OSXSAVE
+// which is not shown.
+const V3: &[&str] = &[
+    "avx", "avx2", "bmi1", "bmi2", "f16c", "fma", "abm", "movbe", "xsave",
+];
+const V4: &[&str] = &["avx512f", "avx512bw", "avx512cd", "avx512dq", "avx512vl"];
+
+impl PsAbiLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V1 => "x86-64",
+            Self::V2 => "x86-64-v2",
+            Self::V3 => "x86-64-v3",
+            Self::V4 => "x86-64-v4",
+        }
+    }
+}
+
+impl fmt::Display for PsAbiLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Cpu<'_> {
+    /// The highest x86-64 level this cpu supports, or `None` if it is not
+    /// 64-bit capable.
+    pub fn psabi_level(&self) -> Option<PsAbiLevel> {
+        let has_all = |flags: &[&str]| flags.iter().all(|flag| self.has_flag(flag));
+
+        [
+            (V1, PsAbiLevel::V1),
+            (V2, PsAbiLevel::V2),
+            (V3, PsAbiLevel::V3),
+            (V4, PsAbiLevel::V4),
+        ]
+        .into_iter()
+        .take_while(|(flags, _)| has_all(flags))
+        .map(|(_, level)| level)
+        .last()
+    }
+}
+
+impl CpuInfo<'_> {
+    /// The highest level every cpu supports.
+    pub fn psabi_level(&self) -> Option<PsAbiLevel> {
+        self.cpus.iter().map(Cpu::psabi_level).min().flatten()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, fixtures::EPYC, CpuBuilder, CpuInfoBuilder};
+    use alloc::string::ToString;
+
+    fn cpu_with(levels: &[&[&'static str]]) -> Cpu<'static> {
+        CpuBuilder::new()
+            .flags(
+                levels
+                    .iter()
+                    .flat_map(|flags| flags.iter().copied())
+                    .collect(),
+            )
+            .build()
+    }
+
+    #[test]
+    fn detects_levels() {
+        assert_eq!(cpu_with(&[]).psabi_level(), None);
+        assert_eq!(cpu_with(&[V1]).psabi_level(), Some(PsAbiLevel::V1));
+        assert_eq!(cpu_with(&[V1, V2]).psabi_level(), Some(PsAbiLevel::V2));
+        assert_eq!(cpu_with(&[V1, V2, V3]).psabi_level(), Some(PsAbiLevel::V3));
+        assert_eq!(
+            cpu_with(&[V1, V2, V3, V4]).psabi_level(),
+            Some(PsAbiLevel::V4)
+        );
+        assert_eq!(cpu_with(&[V1, V3, V4]).psabi_level(), Some(PsAbiLevel::V1));
+    }
+
+    #[test]
+    fn detects_level_of_a_real_cpu() {
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        assert_eq!(cpuinfo.cpus[0].psabi_level(), Some(PsAbiLevel::V3));
+        assert_eq!(PsAbiLevel::V3.to_string(), "x86-64-v3");
+    }
+
+    #[test]
+    fn uses_lowest_level_across_cpus() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu_with(&[V1, V2, V3]))
+            .cpu(cpu_with(&[V1, V2]))
+            .build();
+        assert_eq!(cpuinfo.psabi_level(), Some(PsAbiLevel::V2));
+    }
+}
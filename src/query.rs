@@ -0,0 +1,91 @@
+use crate::{Cpu, CpuInfo, Frequency};
+
+/// Selection methods return a new `CpuInfo` holding the matching cpus, so
+/// they can be chained.
+impl<'a> CpuInfo<'a> {
+    pub fn filter<F: FnMut(&Cpu<'a>) -> bool>(&self, mut predicate: F) -> CpuInfo<'a> {
+        CpuInfo {
+            cpus: self
+                .cpus
+                .iter()
+                .filter(|cpu| predicate(cpu))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn with_flag(&self, flag: &str) -> CpuInfo<'a> {
+        self.filter(|cpu| cpu.has_flag(flag))
+    }
+
+    /// Cpus without a `physical id`, as in some guests, count as socket 0.
+    pub fn on_socket(&self, socket: u32) -> CpuInfo<'a> {
+        self.filter(|cpu| cpu.physical_id.unwrap_or_default() == socket)
+    }
+
+    pub fn faster_than(&self, frequency: Frequency) -> CpuInfo<'a> {
+        self.filter(|cpu| cpu.cpu_mhz > frequency)
+    }
+
+    /// [`faster_than`](Self::faster_than) with the clock given in MHz.
+    pub fn faster_than_mhz(&self, mhz: f64) -> CpuInfo<'a> {
+        self.faster_than(Frequency::from_mhz(mhz))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CpuBuilder, CpuInfoBuilder, Frequency};
+    use alloc::vec::Vec;
+
+    #[test]
+    fn selects_cpus() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(
+                CpuBuilder::new()
+                    .processor(0)
+                    .physical_id(Some(0))
+                    .cpu_mhz(Frequency::from_mhz(3500.0))
+                    .flag("vmx")
+                    .build(),
+            )
+            .cpu(
+                CpuBuilder::new()
+                    .processor(1)
+                    .physical_id(Some(1))
+                    .cpu_mhz(Frequency::from_mhz(2800.0))
+                    .flag("vmx")
+                    .build(),
+            )
+            .cpu(
+                CpuBuilder::new()
+                    .processor(2)
+                    .physical_id(Some(1))
+                    .cpu_mhz(Frequency::from_mhz(3900.0))
+                    .build(),
+            )
+            .build();
+
+        let processors = |cpuinfo: crate::CpuInfo| -> Vec<u32> {
+            cpuinfo.cpus.iter().map(|cpu| cpu.processor).collect()
+        };
+        assert_eq!(processors(cpuinfo.with_flag("VMX")), [0, 1]);
+        assert_eq!(processors(cpuinfo.on_socket(1)), [1, 2]);
+        assert_eq!(
+            processors(cpuinfo.faster_than(Frequency::from_mhz(3000.0))),
+            [0, 2]
+        );
+        assert_eq!(
+            processors(
+                cpuinfo
+                    .on_socket(1)
+                    .with_flag("vmx")
+                    .faster_than(Frequency::from_mhz(2000.0))
+            ),
+            [1]
+        );
+        assert_eq!(processors(cpuinfo.faster_than_mhz(3000.0)), [0, 2]);
+        assert_eq!(processors(cpuinfo.filter(|cpu| cpu.processor == 2)), [2]);
+        assert!(cpuinfo.on_socket(2).cpus.is_empty());
+    }
+}
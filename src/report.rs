@@ -0,0 +1,360 @@
+use alloc::{
+    collections::BTreeMap,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
+use core::fmt::Write;
+
+#[cfg(feature = "std")]
+use crate::Vulnerabilities;
+use crate::{CpuBug, CpuInfo, FlagCategory};
+
+/// Output format of a [`Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ReportFormat {
+    Markdown,
+    Html,
+}
+
+enum Block {
+    Table(&'static [&'static str], Vec<Vec<String>>),
+    Text(String),
+}
+
+/// A hardware report suitable for attaching to support tickets: a summary
+/// table, the topology, flags grouped by category and known
+/// vulnerabilities.
+#[derive(Debug, Clone)]
+pub struct Report<'a> {
+    cpuinfo: &'a CpuInfo<'a>,
+    title: String,
+    /// Bugs listed by any cpu and, when known, the kernel's status.
+    bugs: Vec<(CpuBug, Option<String>)>,
+}
+
+impl<'a> CpuInfo<'a> {
+    pub fn report(&'a self) -> Report<'a> {
+        let mut bugs: Vec<(CpuBug, Option<String>)> = Vec::new();
+        for bug in self.cpus.iter().flat_map(|cpu| cpu.typed_bugs()) {
+            if !bugs.iter().any(|(b, _)| *b == bug) {
+                bugs.push((bug, None));
+            }
+        }
+        Report {
+            cpuinfo: self,
+            title: "Hardware report".to_string(),
+            bugs,
+        }
+    }
+}
+
+impl Report<'_> {
+    pub fn title(mut self, title: &str) -> Self {
+        self.title = title.to_string();
+        self
+    }
+
+    /// Reports mitigation status as read from sysfs along with each bug.
+    #[cfg(feature = "std")]
+    pub fn vulnerabilities(mut self, vulnerabilities: &Vulnerabilities) -> Self {
+        for (bug, status) in &mut self.bugs {
+            *status = vulnerabilities
+                .get(bug)
+                .and_then(|entry| entry.status.clone());
+        }
+        self
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Markdown => self.to_markdown(),
+            ReportFormat::Html => self.to_html(),
+        }
+    }
+
+    pub fn to_markdown(&self) -> String {
+        let mut out = format!("# {}\n", self.title);
+        for (heading, blocks) in self.sections() {
+            let _ = write!(out, "\n## {}\n", heading);
+            for block in blocks {
+                out.push('\n');
+                match block {
+                    Block::Text(text) => {
+                        let _ = writeln!(out, "{}", text);
+                    }
+                    Block::Table(header, rows) => {
+                        let _ = writeln!(out, "| {} |", header.join(" | "));
+                        let _ = writeln!(out, "|{}", " --- |".repeat(header.len()));
+                        for row in rows {
+                            let cells: Vec<String> =
+                                row.iter().map(|cell| cell.replace('|', "\\|")).collect();
+                            let _ = writeln!(out, "| {} |", cells.join(" | "));
+                        }
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// A standalone HTML document.
+    pub fn to_html(&self) -> String {
+        let title = escape(&self.title);
+        let mut out = format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+             <style>table {{ border-collapse: collapse; }} \
+             th, td {{ border: 1px solid #999; padding: 2px 6px; text-align: left; }}</style>\n\
+             </head>\n<body>\n<h1>{title}</h1>\n"
+        );
+        for (heading, blocks) in self.sections() {
+            let _ = writeln!(out, "<h2>{}</h2>", escape(heading));
+            for block in blocks {
+                match block {
+                    Block::Text(text) => {
+                        let _ = writeln!(out, "<p>{}</p>", escape(&text));
+                    }
+                    Block::Table(header, rows) => {
+                        out.push_str("<table>\n<tr>");
+                        for cell in header {
+                            let _ = write!(out, "<th>{}</th>", escape(cell));
+                        }
+                        out.push_str("</tr>\n");
+                        for row in rows {
+                            out.push_str("<tr>");
+                            for cell in row {
+                                let _ = write!(out, "<td>{}</td>", escape(&cell));
+                            }
+                            out.push_str("</tr>\n");
+                        }
+                        out.push_str("</table>\n");
+                    }
+                }
+            }
+        }
+        out.push_str("</body>\n</html>\n");
+        out
+    }
+
+    fn sections(&self) -> Vec<(&'static str, Vec<Block>)> {
+        let Some(summary) = self.cpuinfo.lscpu_summary() else {
+            return vec![("Summary", vec![Block::Text("No cpus found.".to_string())])];
+        };
+        let cpu = &self.cpuinfo.cpus[0];
+
+        let mut rows = vec![
+            row("Architecture", summary.architecture),
+            row("CPU(s)", summary.cpus),
+            row("Vendor ID", &summary.vendor_id),
+            row("Model name", &summary.model_name),
+            row("CPU family", summary.cpu_family),
+            row("Model", summary.model),
+        ];
+        if let Some(stepping) = summary.stepping {
+            rows.push(row("Stepping", stepping));
+        }
+        if let Some(microarch) = cpu.microarchitecture() {
+            rows.push(row("Microarchitecture", microarch.codename));
+        }
+        if let Some(microcode) = cpu.microcode {
+            rows.push(row("Microcode", format!("{:#x}", microcode)));
+        }
+        rows.push(row("BogoMIPS", format!("{:.2}", summary.bogomips)));
+        if let Some(virtualization) = summary.virtualization {
+            rows.push(row("Virtualization", virtualization));
+        }
+        if let Some(level) = self.cpuinfo.psabi_level() {
+            rows.push(row("psABI level", level));
+        }
+        rows.push(row(
+            "Address sizes",
+            format!(
+                "{} bits physical, {} bits virtual",
+                summary.address_sizes.0, summary.address_sizes.1
+            ),
+        ));
+
+        let topology = self.cpuinfo.topology();
+        let mut sockets: Vec<u32> = topology.cpus.iter().map(|p| p.socket).collect();
+        sockets.sort_unstable();
+        sockets.dedup();
+        let topology_rows = sockets
+            .iter()
+            .map(|&socket| {
+                let placements: Vec<_> = topology
+                    .cpus
+                    .iter()
+                    .filter(|p| p.socket == socket)
+                    .collect();
+                let mut cores: Vec<u32> = placements.iter().map(|p| p.core).collect();
+                cores.sort_unstable();
+                cores.dedup();
+                let processors: Vec<String> =
+                    placements.iter().map(|p| p.processor.to_string()).collect();
+                vec![
+                    socket.to_string(),
+                    cores.len().to_string(),
+                    processors.len().to_string(),
+                    processors.join(", "),
+                ]
+            })
+            .collect();
+
+        let vulnerabilities = if self.bugs.is_empty() {
+            Block::Text("The kernel lists no bugs for these cpus.".to_string())
+        } else {
+            Block::Table(
+                &["Bug", "CVEs", "Status"],
+                self.bugs
+                    .iter()
+                    .map(|(bug, status)| {
+                        vec![
+                            bug.to_string(),
+                            bug.cves().join(", "),
+                            status.clone().unwrap_or_else(|| "unknown".to_string()),
+                        ]
+                    })
+                    .collect(),
+            )
+        };
+
+        vec![
+            ("Summary", vec![Block::Table(&["Field", "Value"], rows)]),
+            (
+                "Topology",
+                vec![
+                    Block::Text(format!(
+                        "{} socket(s), {} core(s) per socket, {} thread(s) per core.",
+                        topology.sockets,
+                        topology.cores_per_socket,
+                        topology.threads_per_core()
+                    )),
+                    Block::Table(&["Socket", "Cores", "Threads", "Processors"], topology_rows),
+                ],
+            ),
+            ("Flags", self.flag_blocks()),
+            ("Vulnerabilities", vec![vulnerabilities]),
+        ]
+    }
+
+    /// Flags every cpu has, grouped by category in the order the first cpu
+    /// lists them.
+    fn flag_blocks(&self) -> Vec<Block> {
+        let flags: Vec<&str> = self.cpuinfo.cpus[0]
+            .flags
+            .iter()
+            .copied()
+            .filter(|flag| self.cpuinfo.all_have_flag(flag))
+            .collect();
+        let mut groups: BTreeMap<FlagCategory, Vec<&str>> = BTreeMap::new();
+        for flag in flags {
+            groups.entry(FlagCategory::of(flag)).or_default().push(flag);
+        }
+
+        let mut blocks = Vec::new();
+        let common = self.cpuinfo.common_flags();
+        if self.cpuinfo.cpus.iter().any(|cpu| cpu.flag_set() != common) {
+            blocks.push(Block::Text(
+                "Cpus report different flags; only those common to all are listed.".to_string(),
+            ));
+        }
+        blocks.push(Block::Table(
+            &["Category", "Flags"],
+            groups
+                .into_iter()
+                .map(|(category, flags)| vec![category.to_string(), flags.join(" ")])
+                .collect(),
+        ));
+        blocks
+    }
+}
+
+fn row<T: ToString>(field: &str, value: T) -> Vec<String> {
+    vec![field.to_string(), value.to_string()]
+}
+
+pub(crate) fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+
+    #[test]
+    fn renders_markdown() {
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let markdown = cpuinfo.report().to_markdown();
+
+        assert!(markdown.starts_with("# Hardware report\n\n## Summary\n\n| Field | Value |\n"));
+        assert!(markdown.contains("| Vendor ID | AuthenticAMD |\n"));
+        assert!(markdown.contains("| 0 | 1 | 1 | 3 |\n"));
+        assert!(markdown.contains("| Cryptography | pclmulqdq aes rdrand rdseed sha_ni |\n"));
+        assert!(markdown.contains("| Virtualization | hypervisor svm "));
+        assert!(markdown.contains("| sysret_ss_attrs |  | unknown |\n"));
+        assert_eq!(cpuinfo.report().render(ReportFormat::Markdown), markdown);
+    }
+
+    #[test]
+    fn renders_escaped_html() {
+        let input = SKYLAKE.replace("Intel(R)", "<Intel & co>");
+        let cpuinfo = cpuinfo(&input).unwrap();
+        let html = cpuinfo.report().title("Ticket #1").to_html();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("<h1>Ticket #1</h1>"));
+        assert!(html.contains("<td>&lt;Intel &amp; co&gt; Core"));
+        assert!(html.contains("<td>cpu_meltdown</td><td>CVE-2017-5754</td>"));
+        assert!(html.ends_with("</html>\n"));
+    }
+
+    #[test]
+    fn notes_differing_flags() {
+        let mut cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        let notice = "Cpus report different flags";
+        assert!(!cpuinfo.report().to_markdown().contains(notice));
+
+        let mut second = cpuinfo.cpus[0].clone();
+        let last = second.flags.len() - 1;
+        second.flags[last] = "avx512f";
+        cpuinfo.cpus.push(second);
+        let markdown = cpuinfo.report().to_markdown();
+        assert!(markdown.contains(notice));
+        assert!(!markdown.contains("avx512f"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn includes_mitigation_status() {
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        let fake = crate::fixtures::FakeSysfs::new(
+            "report",
+            &[(
+                "devices/system/cpu/vulnerabilities/meltdown",
+                "Mitigation: PTI\n",
+            )],
+        );
+        let sysfs = crate::Sysfs::new(&fake.root);
+        let report = cpuinfo
+            .report()
+            .vulnerabilities(&cpuinfo.vulnerabilities(&sysfs));
+        assert!(report
+            .to_markdown()
+            .contains("| cpu_meltdown | CVE-2017-5754 | Mitigation: PTI |\n"));
+    }
+}
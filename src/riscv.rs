@@ -0,0 +1,250 @@
+use alloc::vec::Vec;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alpha1, alphanumeric1, not_line_ending},
+    combinator::opt,
+    multi::many0,
+    sequence::preceded,
+    IResult,
+};
+
+use crate::{blocks, field_value, finish, hexadecimal, processor, FieldSpec, Result, Schema};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuInfo<'a> {
+    pub cpus: Vec<Cpu<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cpu<'a> {
+    pub processor: u32,
+    pub hart: u32,
+    pub isa: Isa<'a>,
+    pub mmu: Option<&'a str>,
+    pub uarch: Option<&'a str>,
+    pub mvendorid: Option<u64>,
+    pub marchid: Option<u64>,
+    pub mimpid: Option<u64>,
+    /// The extensions this hart implements, printed since Linux 6.7. `isa`
+    /// only lists the ones common to every hart.
+    pub hart_isa: Option<Isa<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Isa<'a> {
+    pub xlen: u32,
+    pub extensions: Vec<&'a str>,
+}
+
+impl Isa<'_> {
+    /// Checks for an extension by name, e.g. `"c"` or `"zicsr"`, treating
+    /// `g` as shorthand for `imafd_zicsr_zifencei`.
+    pub fn has_extension(&self, name: &str) -> bool {
+        let has = |ext: &str| self.extensions.iter().any(|e| e.eq_ignore_ascii_case(ext));
+
+        has(name)
+            || (has("g")
+                && ["i", "m", "a", "f", "d", "zicsr", "zifencei"]
+                    .iter()
+                    .any(|ext| ext.eq_ignore_ascii_case(name)))
+    }
+}
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("processor"),
+        FieldSpec::required("hart"),
+        FieldSpec::required("isa"),
+        FieldSpec::optional("mmu"),
+        FieldSpec::optional("uarch"),
+        FieldSpec::optional("mvendorid"),
+        FieldSpec::optional("marchid"),
+        FieldSpec::optional("mimpid"),
+        FieldSpec::optional("hart isa"),
+    ],
+    restart: 0,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    let cpus = finish(input, cpus, &SCHEMA, false)?;
+    Ok(CpuInfo { cpus })
+}
+
+fn isa_string(input: &str) -> IResult<&str, Isa<'_>> {
+    let (input, xlen) = preceded(tag("rv"), complete::u32)(input)?;
+    let (input, single) = alpha1(input)?;
+    let (input, multi) = many0(preceded(tag("_"), alphanumeric1))(input)?;
+
+    let mut extensions: Vec<&str> = (0..single.len()).map(|i| &single[i..i + 1]).collect();
+    extensions.extend(multi);
+
+    Ok((input, Isa { xlen, extensions }))
+}
+
+fn hart(input: &str) -> IResult<&str, u32> {
+    field_value(tag("hart"), complete::u32)(input)
+}
+
+fn isa(input: &str) -> IResult<&str, Isa<'_>> {
+    field_value(tag("isa"), isa_string)(input)
+}
+
+fn mmu(input: &str) -> IResult<&str, &str> {
+    field_value(tag("mmu"), alphanumeric1)(input)
+}
+
+fn uarch(input: &str) -> IResult<&str, &str> {
+    field_value(tag("uarch"), not_line_ending)(input)
+}
+
+fn mvendorid(input: &str) -> IResult<&str, u64> {
+    field_value(tag("mvendorid"), hexadecimal)(input)
+}
+
+fn marchid(input: &str) -> IResult<&str, u64> {
+    field_value(tag("marchid"), hexadecimal)(input)
+}
+
+fn mimpid(input: &str) -> IResult<&str, u64> {
+    field_value(tag("mimpid"), hexadecimal)(input)
+}
+
+fn hart_isa(input: &str) -> IResult<&str, Isa<'_>> {
+    field_value(tag("hart isa"), isa_string)(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    let (input, processor) = processor(input)?;
+    let (input, hart) = hart(input)?;
+    let (input, isa) = isa(input)?;
+    let (input, mmu) = opt(mmu)(input)?;
+    let (input, uarch) = opt(uarch)(input)?;
+    let (input, mvendorid) = opt(mvendorid)(input)?;
+    let (input, marchid) = opt(marchid)(input)?;
+    let (input, mimpid) = opt(mimpid)(input)?;
+    let (input, hart_isa) = opt(hart_isa)(input)?;
+
+    let cpu = Cpu {
+        processor,
+        hart,
+        isa,
+        mmu,
+        uarch,
+        mvendorid,
+        marchid,
+        mimpid,
+        hart_isa,
+    };
+
+    Ok((input, cpu))
+}
+
+fn cpus(input: &str) -> IResult<&str, Vec<Cpu<'_>>> {
+    blocks(cpu)(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn parses_hart() {
+        let result = hart(
+            "hart		: 1
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 1);
+    }
+
+    #[test]
+    fn parses_isa() {
+        let result = isa("isa		: rv64imafdc_zicsr_zifencei_zba_zbb
+");
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            Isa {
+                xlen: 64,
+                extensions: vec!["i", "m", "a", "f", "d", "c", "zicsr", "zifencei", "zba", "zbb"],
+            }
+        )
+    }
+
+    #[test]
+    fn expands_g_extension() {
+        let (_, isa) = isa_string("rv64gc").unwrap();
+        assert!(isa.has_extension("c"));
+        assert!(isa.has_extension("D"));
+        assert!(isa.has_extension("zifencei"));
+        assert!(!isa.has_extension("v"));
+    }
+
+    #[test]
+    fn parses_mmu() {
+        let result = mmu("mmu		: sv39
+");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "sv39");
+    }
+
+    #[test]
+    fn parses_uarch() {
+        let result = uarch(
+            "uarch		: sifive,u74-mc
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "sifive,u74-mc");
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "processor	: 0
+hart		: 1
+isa		: rv64imafdc
+mmu		: sv39
+uarch		: sifive,u74-mc
+
+processor	: 1
+hart		: 2
+isa		: rv64imafdc
+mmu		: sv39
+uarch		: sifive,u74-mc
+
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.cpus[1].hart, 2);
+        assert!(cpuinfo.cpus[1].isa.has_extension("f"));
+    }
+
+    #[test]
+    fn parses_machine_ids() {
+        let result = cpuinfo(
+            "processor	: 0
+hart		: 2
+isa		: rv64imafdch_zicntr_zicsr_zifencei_zihpm
+mmu		: sv39
+uarch		: sifive,u74-mc
+mvendorid	: 0x489
+marchid		: 0x8000000000000007
+mimpid		: 0x4210427
+hart isa	: rv64imafdch_zicntr_zicsr_zifencei_zihpm
+
+",
+        );
+        assert!(result.is_ok());
+
+        let cpu = &result.unwrap().cpus[0];
+        assert_eq!(cpu.mvendorid, Some(0x489));
+        assert_eq!(cpu.marchid, Some(0x8000_0000_0000_0007));
+        assert_eq!(cpu.mimpid, Some(0x421_0427));
+        assert!(cpu.hart_isa.as_ref().unwrap().has_extension("zihpm"));
+    }
+}
@@ -0,0 +1,193 @@
+use alloc::{string::String, vec::Vec};
+
+use crate::{Cpu, CpuInfo};
+
+/// Kernel flag names and the stable rustc x86 target features they enable.
+const FEATURES: &[(&str, &str)] = &[
+    ("fxsr", "fxsr"),
+    ("sse", "sse"),
+    ("sse2", "sse2"),
+    ("pni", "sse3"),
+    ("ssse3", "ssse3"),
+    ("sse4_1", "sse4.1"),
+    ("sse4_2", "sse4.2"),
+    ("sse4a", "sse4a"),
+    ("cx16", "cmpxchg16b"),
+    ("popcnt", "popcnt"),
+    ("abm", "lzcnt"),
+    ("movbe", "movbe"),
+    ("aes", "aes"),
+    ("pclmulqdq", "pclmulqdq"),
+    ("rdrand", "rdrand"),
+    ("rdseed", "rdseed"),
+    ("adx", "adx"),
+    ("sha_ni", "sha"),
+    ("xsave", "xsave"),
+    ("xsaveopt", "xsaveopt"),
+    ("xsavec", "xsavec"),
+    ("xsaves", "xsaves"),
+    ("avx", "avx"),
+    ("avx2", "avx2"),
+    ("fma", "fma"),
+    ("f16c", "f16c"),
+    ("bmi1", "bmi1"),
+    ("bmi2", "bmi2"),
+    ("tbm", "tbm"),
+    ("gfni", "gfni"),
+    ("vaes", "vaes"),
+    ("vpclmulqdq", "vpclmulqdq"),
+    ("avx_vnni", "avxvnni"),
+    ("avx512f", "avx512f"),
+    ("avx512cd", "avx512cd"),
+    ("avx512dq", "avx512dq"),
+    ("avx512bw", "avx512bw"),
+    ("avx512vl", "avx512vl"),
+    ("avx512ifma", "avx512ifma"),
+    ("avx512vbmi", "avx512vbmi"),
+    ("avx512_vbmi2", "avx512vbmi2"),
+    ("avx512_vnni", "avx512vnni"),
+    ("avx512_bitalg", "avx512bitalg"),
+    ("avx512_vpopcntdq", "avx512vpopcntdq"),
+    ("avx512_bf16", "avx512bf16"),
+    ("avx512_fp16", "avx512fp16"),
+];
+
+/// LLVM cpu names by codename. Server parts sharing a codename with
+/// client ones are told apart by AVX-512.
+fn llvm_cpu(codename: &str, avx512: bool) -> Option<&'static str> {
+    Some(match (codename, avx512) {
+        ("Nehalem", _) => "nehalem",
+        ("Westmere", _) => "westmere",
+        ("Sandy Bridge", _) => "sandybridge",
+        ("Ivy Bridge", _) => "ivybridge",
+        ("Haswell", _) => "haswell",
+        ("Broadwell", _) => "broadwell",
+        ("Skylake", true) => "skylake-avx512",
+        ("Skylake" | "Kaby Lake" | "Coffee Lake" | "Whiskey Lake" | "Comet Lake", _) => "skylake",
+        ("Cascade Lake", _) => "cascadelake",
+        ("Cooper Lake", _) => "cooperlake",
+        ("Cannon Lake", _) => "cannonlake",
+        ("Ice Lake", true) => "icelake-server",
+        ("Ice Lake", false) => "icelake-client",
+        ("Tiger Lake", _) => "tigerlake",
+        ("Rocket Lake", _) => "rocketlake",
+        ("Alder Lake", _) => "alderlake",
+        ("Raptor Lake", _) => "raptorlake",
+        ("Meteor Lake", _) => "meteorlake",
+        ("Arrow Lake", _) => "arrowlake",
+        ("Lunar Lake", _) => "lunarlake",
+        ("Sapphire Rapids", _) => "sapphirerapids",
+        ("Emerald Rapids", _) => "emeraldrapids",
+        ("Granite Rapids", _) => "graniterapids",
+        ("Sierra Forest", _) => "sierraforest",
+        ("Silvermont" | "Airmont", _) => "silvermont",
+        ("Goldmont", _) => "goldmont",
+        ("Goldmont Plus", _) => "goldmont-plus",
+        ("Tremont", _) => "tremont",
+        ("Gracemont", _) => "gracemont",
+        ("Knights Landing", _) => "knl",
+        ("Knights Mill", _) => "knm",
+        ("Bulldozer", _) => "bdver1",
+        ("Piledriver", _) => "bdver2",
+        ("Steamroller", _) => "bdver3",
+        ("Excavator", _) => "bdver4",
+        ("Jaguar" | "Puma", _) => "btver2",
+        ("Zen" | "Zen+" | "Dhyana", _) => "znver1",
+        ("Zen 2", _) => "znver2",
+        ("Zen 3" | "Zen 3+", _) => "znver3",
+        ("Zen 4", _) => "znver4",
+        ("Zen 5", _) => "znver5",
+        _ => return None,
+    })
+}
+
+fn target_features<F: Fn(&str) -> bool>(has_flag: F) -> String {
+    let features: Vec<String> = FEATURES
+        .iter()
+        .filter(|(flag, _)| has_flag(flag))
+        .map(|(_, feature)| alloc::format!("+{}", feature))
+        .collect();
+    features.join(",")
+}
+
+impl Cpu<'_> {
+    /// The value for `-C target-feature`, e.g. `+sse2,+avx2,+fma`, enabling
+    /// every stable rustc feature this cpu has.
+    pub fn rustc_target_features(&self) -> String {
+        target_features(|flag| self.has_flag(flag))
+    }
+
+    /// The best value for `-C target-cpu`: the LLVM name of the
+    /// microarchitecture where known, otherwise the psABI level.
+    pub fn rustc_target_cpu(&self) -> Option<&'static str> {
+        self.microarchitecture()
+            .and_then(|microarch| llvm_cpu(microarch.codename, self.has_flag("avx512f")))
+            .or_else(|| self.psabi_level().map(|level| level.as_str()))
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Like [`Cpu::rustc_target_features`], with only the features every
+    /// cpu has.
+    pub fn rustc_target_features(&self) -> String {
+        target_features(|flag| self.all_have_flag(flag))
+    }
+
+    /// The first cpu's [`Cpu::rustc_target_cpu`] when all cpus are the same
+    /// part, otherwise the psABI level they share.
+    pub fn rustc_target_cpu(&self) -> Option<&'static str> {
+        let first = self.cpus.first()?;
+        let same_part = self.cpus.iter().all(|cpu| {
+            (cpu.vendor_id, cpu.cpu_family, cpu.model)
+                == (first.vendor_id, first.cpu_family, first.model)
+        });
+        if same_part {
+            first.rustc_target_cpu()
+        } else {
+            self.psabi_level().map(|level| level.as_str())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        Generator, Profile,
+    };
+    use alloc::format;
+
+    #[test]
+    fn maps_flags_to_rustc_features() {
+        let epyc = cpuinfo(EPYC).unwrap();
+        let features = epyc[0].rustc_target_features();
+        assert!(features.starts_with("+fxsr,+sse,+sse2,+sse3,+ssse3,+sse4.1,+sse4.2,+sse4a,"));
+        assert!(features.contains(",+sha,") && features.ends_with(",+vaes,+vpclmulqdq"));
+        assert!(!features.contains("avx512"));
+        assert_eq!(epyc[0].rustc_target_cpu(), Some("znver3"));
+
+        let skylake = cpuinfo(SKYLAKE).unwrap();
+        assert_eq!(skylake[0].rustc_target_features(), "");
+        assert_eq!(skylake[0].rustc_target_cpu(), Some("skylake"));
+
+        let server = Generator::new(Profile::SkylakeServer).build();
+        assert_eq!(server.rustc_target_cpu(), Some("skylake-avx512"));
+        assert!(server.rustc_target_features().contains(",+avx512f,"));
+    }
+
+    #[test]
+    fn falls_back_to_common_features() {
+        let input = format!(
+            "{}\n{}",
+            SKYLAKE,
+            EPYC.replace("processor\t: 3", "processor\t: 1")
+        );
+        let mixed = cpuinfo(&input).unwrap();
+        assert_eq!(mixed.rustc_target_features(), "");
+        assert_eq!(mixed.rustc_target_cpu(), None);
+
+        let zen4 = Generator::new(Profile::Zen4).build();
+        assert_eq!(zen4.rustc_target_cpu(), Some("znver4"));
+    }
+}
@@ -0,0 +1,519 @@
+use alloc::vec::Vec;
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{self, alpha1, hex_digit1, line_ending, not_line_ending, space0, space1},
+    combinator::{map_res, opt, value},
+    multi::{many1, separated_list0},
+    number::complete::double,
+    sequence::{preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::{
+    blocks, error::Presence, field_value, finish, line_end, list, mhz, separator, CacheSize,
+    FieldSpec, Frequency, Result, Schema,
+};
+
+#[derive(Debug, Clone)]
+pub struct CpuInfo<'a> {
+    pub vendor_id: &'a str,
+    pub processors: u32,
+    pub bogomips_per_cpu: f64,
+    pub max_thread_id: Option<u32>,
+    pub features: Vec<&'a str>,
+    pub facilities: Vec<u32>,
+    pub caches: Vec<Cache<'a>>,
+    pub cpus: Vec<Cpu<'a>>,
+    /// The `cpu number` sections following the summary, one per online
+    /// cpu. Kernels before 4.15 do not print them.
+    pub sections: Vec<CpuSection<'a>>,
+}
+
+impl_eq_hash!(CpuInfo<'_> {
+    floats: [bogomips_per_cpu],
+    fields: [vendor_id, processors, max_thread_id, features, facilities, caches, cpus, sections]
+});
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cache<'a> {
+    pub index: u32,
+    pub level: u32,
+    pub cache_type: &'a str,
+    pub scope: &'a str,
+    pub size: CacheSize,
+    pub line_size: u32,
+    pub associativity: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Cpu<'a> {
+    pub processor: u32,
+    pub version: u32,
+    pub identification: u32,
+    /// Machine type as printed by the kernel, e.g. `3906` for a z14.
+    pub machine: &'a str,
+}
+
+/// A per-cpu section. The topology fields are only printed by kernels built
+/// with `CONFIG_SCHED_TOPOLOGY`, the ids since Linux 5.7 and the clock only
+/// on machines that report it, z14 and later.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuSection<'a> {
+    pub cpu_number: u32,
+    pub physical_id: Option<u32>,
+    pub core_id: Option<u32>,
+    pub book_id: Option<u32>,
+    pub drawer_id: Option<u32>,
+    pub dedicated: Option<bool>,
+    pub address: Option<u32>,
+    pub siblings: Option<u32>,
+    pub cpu_cores: Option<u32>,
+    pub version: Option<u32>,
+    pub identification: Option<u32>,
+    pub machine: Option<&'a str>,
+    pub mhz_dynamic: Option<Frequency>,
+    pub mhz_static: Option<Frequency>,
+}
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("vendor_id"),
+        FieldSpec::required("# processors"),
+        FieldSpec::required("bogomips per cpu"),
+        FieldSpec::optional("max thread id"),
+        FieldSpec::required("features"),
+        FieldSpec::required("facilities"),
+        FieldSpec::repeated("cache", Presence::Required),
+        FieldSpec::repeated("processor", Presence::Required),
+        FieldSpec::required("cpu number"),
+        FieldSpec::optional("physical id"),
+        FieldSpec::optional("core id"),
+        FieldSpec::optional("book id"),
+        FieldSpec::optional("drawer id"),
+        FieldSpec::optional("dedicated"),
+        FieldSpec::optional("address"),
+        FieldSpec::optional("siblings"),
+        FieldSpec::optional("cpu cores"),
+        FieldSpec::optional("version"),
+        FieldSpec::optional("identification"),
+        FieldSpec::optional("machine"),
+        FieldSpec::optional("cpu MHz dynamic"),
+        FieldSpec::optional("cpu MHz static"),
+    ],
+    restart: 8,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    finish(input, info, &SCHEMA, false)
+}
+
+fn info(input: &str) -> IResult<&str, CpuInfo<'_>> {
+    let (input, vendor_id) = vendor_id(input)?;
+    let (input, processors) = processors(input)?;
+    let (input, bogomips_per_cpu) = bogomips_per_cpu(input)?;
+    let (input, max_thread_id) = opt(max_thread_id)(input)?;
+    let (input, features) = features(input)?;
+    let (input, facilities) = facilities(input)?;
+    let (input, caches) = many1(cache)(input)?;
+    let (input, cpus) = many1(cpu)(input)?;
+    let (input, sections) = opt(preceded(line_ending, blocks(section)))(input)?;
+
+    let cpuinfo = CpuInfo {
+        vendor_id,
+        processors,
+        bogomips_per_cpu,
+        max_thread_id,
+        features,
+        facilities,
+        caches,
+        cpus,
+        sections: sections.unwrap_or_default(),
+    };
+
+    Ok((input, cpuinfo))
+}
+
+fn hex_value(input: &str) -> IResult<&str, u32> {
+    map_res(hex_digit1, |out: &str| u32::from_str_radix(out, 16))(input)
+}
+
+fn vendor_id(input: &str) -> IResult<&str, &str> {
+    field_value(tag("vendor_id"), not_line_ending)(input)
+}
+
+fn processors(input: &str) -> IResult<&str, u32> {
+    field_value(tag("# processors"), complete::u32)(input)
+}
+
+fn bogomips_per_cpu(input: &str) -> IResult<&str, f64> {
+    field_value(tag("bogomips per cpu"), double)(input)
+}
+
+fn max_thread_id(input: &str) -> IResult<&str, u32> {
+    field_value(tag("max thread id"), complete::u32)(input)
+}
+
+fn features(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("features"), terminated(list, space0))(input)
+}
+
+fn facilities(input: &str) -> IResult<&str, Vec<u32>> {
+    field_value(tag("facilities"), separated_list0(tag(" "), complete::u32))(input)
+}
+
+fn cache(input: &str) -> IResult<&str, Cache<'_>> {
+    let (input, index) = preceded(tag("cache"), complete::u32)(input)?;
+    let (input, _) = separator(input)?;
+    let (input, level) = preceded(tag("level="), complete::u32)(input)?;
+    let (input, cache_type) = preceded(tag(" type="), alpha1)(input)?;
+    let (input, scope) = preceded(tag(" scope="), alpha1)(input)?;
+    let (input, size) = preceded(tag(" size="), terminated(complete::u64, tag("K")))(input)?;
+    let (input, line_size) = preceded(tag(" line_size="), complete::u32)(input)?;
+    let (input, associativity) = preceded(tag(" associativity="), complete::u32)(input)?;
+    let (input, _) = line_end(input)?;
+
+    let cache = Cache {
+        index,
+        level,
+        cache_type,
+        scope,
+        size: CacheSize::from_kib(size),
+        line_size,
+        associativity,
+    };
+
+    Ok((input, cache))
+}
+
+fn cpu(input: &str) -> IResult<&str, Cpu<'_>> {
+    let (input, processor) = preceded(tag("processor "), complete::u32)(input)?;
+    let (input, _) = separator(input)?;
+    let (input, version) = preceded(tag("version = "), hex_value)(input)?;
+    let (input, identification) = preceded(
+        tuple((tag(","), space1, tag("identification = "))),
+        hex_value,
+    )(input)?;
+    let (input, machine) =
+        preceded(tuple((tag(","), space1, tag("machine = "))), hex_digit1)(input)?;
+    let (input, _) = line_end(input)?;
+
+    let cpu = Cpu {
+        processor,
+        version,
+        identification,
+        machine,
+    };
+
+    Ok((input, cpu))
+}
+
+fn cpu_number(input: &str) -> IResult<&str, u32> {
+    field_value(tag("cpu number"), complete::u32)(input)
+}
+
+fn physical_id(input: &str) -> IResult<&str, u32> {
+    field_value(tag("physical id"), complete::u32)(input)
+}
+
+fn core_id(input: &str) -> IResult<&str, u32> {
+    field_value(tag("core id"), complete::u32)(input)
+}
+
+fn book_id(input: &str) -> IResult<&str, u32> {
+    field_value(tag("book id"), complete::u32)(input)
+}
+
+fn drawer_id(input: &str) -> IResult<&str, u32> {
+    field_value(tag("drawer id"), complete::u32)(input)
+}
+
+fn dedicated(input: &str) -> IResult<&str, bool> {
+    field_value(
+        tag("dedicated"),
+        alt((value(false, tag("0")), value(true, tag("1")))),
+    )(input)
+}
+
+fn address(input: &str) -> IResult<&str, u32> {
+    field_value(tag("address"), complete::u32)(input)
+}
+
+fn siblings(input: &str) -> IResult<&str, u32> {
+    field_value(tag("siblings"), complete::u32)(input)
+}
+
+fn cpu_cores(input: &str) -> IResult<&str, u32> {
+    field_value(tag("cpu cores"), complete::u32)(input)
+}
+
+fn version(input: &str) -> IResult<&str, u32> {
+    field_value(tag("version"), hex_value)(input)
+}
+
+fn identification(input: &str) -> IResult<&str, u32> {
+    field_value(tag("identification"), hex_value)(input)
+}
+
+fn machine(input: &str) -> IResult<&str, &str> {
+    field_value(tag("machine"), hex_digit1)(input)
+}
+
+fn mhz_dynamic(input: &str) -> IResult<&str, Frequency> {
+    field_value(tag("cpu MHz dynamic"), mhz)(input)
+}
+
+fn mhz_static(input: &str) -> IResult<&str, Frequency> {
+    field_value(tag("cpu MHz static"), mhz)(input)
+}
+
+fn section(input: &str) -> IResult<&str, CpuSection<'_>> {
+    let (input, cpu_number) = cpu_number(input)?;
+    let (input, physical_id) = opt(physical_id)(input)?;
+    let (input, core_id) = opt(core_id)(input)?;
+    let (input, book_id) = opt(book_id)(input)?;
+    let (input, drawer_id) = opt(drawer_id)(input)?;
+    let (input, dedicated) = opt(dedicated)(input)?;
+    let (input, address) = opt(address)(input)?;
+    let (input, siblings) = opt(siblings)(input)?;
+    let (input, cpu_cores) = opt(cpu_cores)(input)?;
+    let (input, version) = opt(version)(input)?;
+    let (input, identification) = opt(identification)(input)?;
+    let (input, machine) = opt(machine)(input)?;
+    let (input, mhz_dynamic) = opt(mhz_dynamic)(input)?;
+    let (input, mhz_static) = opt(mhz_static)(input)?;
+
+    let section = CpuSection {
+        cpu_number,
+        physical_id,
+        core_id,
+        book_id,
+        drawer_id,
+        dedicated,
+        address,
+        siblings,
+        cpu_cores,
+        version,
+        identification,
+        machine,
+        mhz_dynamic,
+        mhz_static,
+    };
+
+    Ok((input, section))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn parses_vendor_id() {
+        let result = vendor_id(
+            "vendor_id       : IBM/S390
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "IBM/S390");
+    }
+
+    #[test]
+    fn parses_processors() {
+        let result = processors(
+            "# processors    : 4
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 4);
+    }
+
+    #[test]
+    fn parses_bogomips_per_cpu() {
+        let result = bogomips_per_cpu(
+            "bogomips per cpu: 3241.00
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 3241.0);
+    }
+
+    #[test]
+    fn parses_facilities() {
+        let result = facilities(
+            "facilities      : 0 1 2 3 4 6 7 8 9 10
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, vec![0, 1, 2, 3, 4, 6, 7, 8, 9, 10]);
+    }
+
+    #[test]
+    fn parses_cache() {
+        let result = cache(
+            "cache2          : level=2 type=Data scope=Private size=2048K line_size=256 associativity=8
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            Cache {
+                index: 2,
+                level: 2,
+                cache_type: "Data",
+                scope: "Private",
+                size: CacheSize::from_kib(2048),
+                line_size: 256,
+                associativity: 8,
+            }
+        )
+    }
+
+    #[test]
+    fn parses_cpu() {
+        let result = cpu(
+            "processor 1: version = FF,  identification = 033EF7,  machine = 3906
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            Cpu {
+                processor: 1,
+                version: 0xff,
+                identification: 0x033ef7,
+                machine: "3906",
+            }
+        )
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "vendor_id       : IBM/S390
+# processors    : 2
+bogomips per cpu: 3241.00
+max thread id   : 0
+features	: esan3 zarch stfle msa ldisp eimm dfp edat etf3eh highgprs te vx sie
+facilities      : 0 1 2 3 4 6 7 8 9 10 12 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 30 31 32 33
+cache0          : level=1 type=Data scope=Private size=128K line_size=256 associativity=8
+cache1          : level=1 type=Instruction scope=Private size=96K line_size=256 associativity=6
+cache2          : level=2 type=Data scope=Private size=2048K line_size=256 associativity=8
+cache3          : level=2 type=Instruction scope=Private size=2048K line_size=256 associativity=8
+cache4          : level=3 type=Unified scope=Shared size=131072K line_size=256 associativity=32
+cache5          : level=4 type=Unified scope=Shared size=688128K line_size=256 associativity=42
+processor 0: version = FF,  identification = 233EF7,  machine = 3906
+processor 1: version = FF,  identification = 033EF7,  machine = 3906
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.processors, 2);
+        assert_eq!(cpuinfo.max_thread_id, Some(0));
+        assert!(cpuinfo.features.contains(&"vx"));
+        assert_eq!(cpuinfo.caches.len(), 6);
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.cpus[0].identification, 0x233ef7);
+        assert!(cpuinfo.sections.is_empty());
+    }
+
+    #[test]
+    fn parses_cpu_sections() {
+        let result = cpuinfo(
+            "vendor_id       : IBM/S390
+# processors    : 2
+bogomips per cpu: 3241.00
+max thread id   : 0
+features	: esan3 zarch stfle msa ldisp eimm dfp edat etf3eh highgprs te vx sie
+facilities      : 0 1 2 3 4 6 7 8 9 10 12 14 15 16 17 18 19 20 21 22 23 24 25 26 27 28 30 31 32 33
+cache0          : level=1 type=Data scope=Private size=128K line_size=256 associativity=8
+cache1          : level=1 type=Instruction scope=Private size=128K line_size=256 associativity=8
+cache2          : level=2 type=Data scope=Private size=4096K line_size=256 associativity=8
+cache3          : level=2 type=Instruction scope=Private size=2048K line_size=256 associativity=8
+cache4          : level=3 type=Unified scope=Shared size=131072K line_size=256 associativity=32
+cache5          : level=4 type=Unified scope=Shared size=688128K line_size=256 associativity=42
+processor 0: version = FF,  identification = 233EF7,  machine = 3906
+processor 1: version = FF,  identification = 233EF7,  machine = 3906
+
+cpu number      : 0
+physical id     : 1
+core id         : 0
+book id         : 1
+drawer id       : 4
+dedicated       : 0
+address         : 0
+siblings        : 2
+cpu cores       : 2
+version         : FF
+identification  : 233EF7
+machine         : 3906
+cpu MHz dynamic : 5208
+cpu MHz static  : 5208
+
+cpu number      : 1
+physical id     : 1
+core id         : 1
+book id         : 1
+drawer id       : 4
+dedicated       : 1
+address         : 1
+siblings        : 2
+cpu cores       : 2
+version         : FF
+identification  : 233EF7
+machine         : 3906
+cpu MHz dynamic : 5208
+cpu MHz static  : 5208
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.cpus.len(), 2);
+        assert_eq!(cpuinfo.sections.len(), 2);
+        assert_eq!(
+            cpuinfo.sections[1],
+            CpuSection {
+                cpu_number: 1,
+                physical_id: Some(1),
+                core_id: Some(1),
+                book_id: Some(1),
+                drawer_id: Some(4),
+                dedicated: Some(true),
+                address: Some(1),
+                siblings: Some(2),
+                cpu_cores: Some(2),
+                version: Some(0xff),
+                identification: Some(0x233ef7),
+                machine: Some("3906"),
+                mhz_dynamic: Some(Frequency::from_mhz(5208.0)),
+                mhz_static: Some(Frequency::from_mhz(5208.0)),
+            }
+        );
+    }
+
+    #[test]
+    fn parses_clock_only_sections() {
+        let result = cpuinfo(
+            "vendor_id       : IBM/S390
+# processors    : 1
+bogomips per cpu: 3241.00
+features	: esan3 zarch stfle msa
+facilities      : 0 1 2 3
+cache0          : level=1 type=Data scope=Private size=128K line_size=256 associativity=8
+processor 0: version = FF,  identification = 233EF7,  machine = 3906
+
+cpu number      : 0
+cpu MHz dynamic : 5208
+cpu MHz static  : 5208
+",
+        );
+        assert!(result.is_ok());
+
+        let section = &result.unwrap().sections[0];
+        assert_eq!(section.physical_id, None);
+        assert_eq!(section.machine, None);
+        assert_eq!(section.mhz_static, Some(Frequency::from_mhz(5208.0)));
+    }
+}
@@ -0,0 +1,174 @@
+use core::{
+    fmt,
+    iter::Sum,
+    ops::{Add, AddAssign},
+};
+
+/// A cache size in bytes. cpuinfo and sysfs print sizes in KB, `lscpu` in
+/// KiB/MiB; keeping the unit in the type avoids mixing them up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(transparent)
+)]
+pub struct CacheSize(u64);
+
+const UNITS: &[(&str, u32)] = &[("GiB", 30), ("MiB", 20), ("KiB", 10)];
+
+impl CacheSize {
+    pub const fn from_bytes(bytes: u64) -> Self {
+        Self(bytes)
+    }
+
+    /// The kernel's `KB` is 1024 bytes, so this is also what it calls KB.
+    pub const fn from_kib(kib: u64) -> Self {
+        Self(kib << 10)
+    }
+
+    pub const fn from_mib(mib: u64) -> Self {
+        Self(mib << 20)
+    }
+
+    pub const fn from_gib(gib: u64) -> Self {
+        Self(gib << 30)
+    }
+
+    pub const fn as_bytes(&self) -> u64 {
+        self.0
+    }
+
+    /// Whole KiB, rounded down.
+    pub const fn as_kib(&self) -> u64 {
+        self.0 >> 10
+    }
+
+    /// Whole MiB, rounded down.
+    pub const fn as_mib(&self) -> u64 {
+        self.0 >> 20
+    }
+
+    pub fn checked_mul(self, n: u64) -> Option<Self> {
+        self.0.checked_mul(n).map(Self)
+    }
+}
+
+impl fmt::Display for CacheSize {
+    /// Uses the largest binary unit the size reaches, e.g. `8 MiB`, with one
+    /// decimal when it is not a whole number of them, e.g. `7.5 MiB`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(&(unit, shift)) = UNITS.iter().find(|(_, shift)| self.0 >= 1 << shift) else {
+            return write!(f, "{} B", self.0);
+        };
+        if self.0.is_multiple_of(1 << shift) {
+            write!(f, "{} {}", self.0 >> shift, unit)
+        } else {
+            let value = self.0 as f64 / (1u64 << shift) as f64;
+            write!(f, "{:.*} {}", f.precision().unwrap_or(1), value, unit)
+        }
+    }
+}
+
+/// The unit a cpuinfo `cache size` was printed in. The kernel prints `KB`,
+/// meaning KiB, as do the others; `B` is only written for sizes that are
+/// not a whole number of KiB.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SizeUnit {
+    Bytes,
+    #[default]
+    Kilobytes,
+    Megabytes,
+    Gigabytes,
+}
+
+impl SizeUnit {
+    pub const fn symbol(&self) -> &'static str {
+        match self {
+            Self::Bytes => "B",
+            Self::Kilobytes => "KB",
+            Self::Megabytes => "MB",
+            Self::Gigabytes => "GB",
+        }
+    }
+
+    pub const fn size(&self) -> CacheSize {
+        match self {
+            Self::Bytes => CacheSize::from_bytes(1),
+            Self::Kilobytes => CacheSize::from_kib(1),
+            Self::Megabytes => CacheSize::from_mib(1),
+            Self::Gigabytes => CacheSize::from_gib(1),
+        }
+    }
+
+    /// `size` as a whole number of this unit, if it is one.
+    pub const fn count(&self, size: CacheSize) -> Option<u64> {
+        let unit = self.size().as_bytes();
+        if size.as_bytes().is_multiple_of(unit) {
+            Some(size.as_bytes() / unit)
+        } else {
+            None
+        }
+    }
+}
+
+impl Add for CacheSize {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for CacheSize {
+    fn add_assign(&mut self, other: Self) {
+        self.0 += other.0;
+    }
+}
+
+impl Sum for CacheSize {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn converts_units() {
+        let l3 = CacheSize::from_kib(36_608);
+        assert_eq!(l3.as_bytes(), 36_608 * 1024);
+        assert_eq!(l3.as_mib(), 35);
+        assert_eq!(CacheSize::from_mib(8), CacheSize::from_kib(8192));
+        // A 4 TiB cache would not have fit the u32 this replaces.
+        assert_eq!(CacheSize::from_gib(4096).as_kib(), 1 << 32);
+        assert_eq!(CacheSize::from_bytes(u64::MAX).checked_mul(2), None);
+        assert_eq!(
+            [CacheSize::from_kib(512), CacheSize::from_kib(512)]
+                .into_iter()
+                .sum::<CacheSize>(),
+            CacheSize::from_mib(1)
+        );
+    }
+
+    #[test]
+    fn counts_in_units() {
+        let l3 = CacheSize::from_mib(8);
+        assert_eq!(SizeUnit::Kilobytes.count(l3), Some(8192));
+        assert_eq!(SizeUnit::Megabytes.count(l3), Some(8));
+        assert_eq!(SizeUnit::Gigabytes.count(l3), None);
+        assert_eq!(SizeUnit::Kilobytes.count(CacheSize::from_bytes(1536)), None);
+    }
+
+    #[test]
+    fn displays_binary_units() {
+        assert_eq!(CacheSize::from_mib(8).to_string(), "8 MiB");
+        assert_eq!(CacheSize::from_kib(7680).to_string(), "7.5 MiB");
+        assert_eq!(CacheSize::from_kib(48).to_string(), "48 KiB");
+        assert_eq!(CacheSize::from_gib(1).to_string(), "1 GiB");
+        assert_eq!(CacheSize::from_bytes(64).to_string(), "64 B");
+    }
+}
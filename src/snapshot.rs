@@ -0,0 +1,53 @@
+use crate::{CpuInfo, CpuInfoOwned, Result};
+
+impl CpuInfo<'_> {
+    /// Serializes to TOML, with one `[[cpus]]` table per cpu and the same
+    /// field names as the JSON output.
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+}
+
+impl CpuInfoOwned {
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string(self)?)
+    }
+
+    /// Reads a snapshot written by `to_toml`, e.g. an expected profile to
+    /// compare against with [`CpuInfo::diff`].
+    pub fn from_toml(input: &str) -> Result<Self> {
+        let mut snapshot: Self = toml::from_str(input)?;
+        snapshot.intern();
+        Ok(snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+
+    #[test]
+    fn round_trips_through_toml() {
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let toml = cpuinfo.to_toml().unwrap();
+        assert!(toml.starts_with("[[cpus]]\nprocessor = 3\nvendor_id = \"AuthenticAMD\"\n"));
+
+        let snapshot = CpuInfoOwned::from_toml(&toml).unwrap();
+        assert!(cpuinfo.diff(&snapshot.as_cpuinfo()).is_empty());
+        assert_eq!(snapshot.to_toml().unwrap(), toml);
+    }
+
+    #[test]
+    fn compares_against_a_profile() {
+        let expected =
+            CpuInfoOwned::from_toml(&cpuinfo(SKYLAKE).unwrap().to_toml().unwrap()).unwrap();
+        let input = SKYLAKE.replace("0xf0", "0xf4");
+        let actual = cpuinfo(&input).unwrap();
+        assert_eq!(expected.as_cpuinfo().diff(&actual).changed.len(), 1);
+        assert!(CpuInfoOwned::from_toml("cpus = 1").is_err());
+    }
+}
@@ -0,0 +1,195 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::vec::Vec;
+
+use crate::{Cpu, CpuInfo, FlagSet};
+
+/// What one socket holds, taken from its first cpu where the cpus of a
+/// socket should agree. Comparing the summaries of two sockets shows a
+/// board built from mismatched parts.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SocketSummary<'a> {
+    pub socket: u32,
+    pub cpus: usize,
+    pub cores: usize,
+    pub vendor_id: &'a str,
+    pub cpu_family: u32,
+    pub model: u32,
+    pub stepping: Option<u32>,
+    pub model_name: &'a str,
+    pub microcode: Option<u64>,
+    /// The flags every cpu of the socket has.
+    pub common_flags: FlagSet,
+}
+
+impl SocketSummary<'_> {
+    /// Whether both sockets hold the same part at the same stepping and
+    /// microcode revision with the same flags, whatever their core count.
+    pub fn same_part(&self, other: &SocketSummary) -> bool {
+        self.vendor_id == other.vendor_id
+            && self.cpu_family == other.cpu_family
+            && self.model == other.model
+            && self.stepping == other.stepping
+            && self.model_name == other.model_name
+            && self.microcode == other.microcode
+            && self.common_flags == other.common_flags
+    }
+}
+
+impl<'a> CpuInfo<'a> {
+    /// Cpus keyed by `physical id`, each list in the order the cpus are
+    /// listed. Cpus without one count as socket 0.
+    pub fn by_socket(&self) -> BTreeMap<u32, Vec<&Cpu<'a>>> {
+        let mut sockets: BTreeMap<u32, Vec<&Cpu<'a>>> = BTreeMap::new();
+        for cpu in &self.cpus {
+            sockets
+                .entry(cpu.physical_id.unwrap_or_default())
+                .or_default()
+                .push(cpu);
+        }
+        sockets
+    }
+
+    /// Cpus keyed by `(physical id, core id)`, i.e. the logical cpus
+    /// sharing each physical core, in the order they are listed.
+    pub fn by_core(&self) -> BTreeMap<(u32, u32), Vec<&Cpu<'a>>> {
+        let mut cores: BTreeMap<(u32, u32), Vec<&Cpu<'a>>> = BTreeMap::new();
+        for cpu in &self.cpus {
+            cores
+                .entry((cpu.physical_id.unwrap_or_default(), cpu.core_id))
+                .or_default()
+                .push(cpu);
+        }
+        cores
+    }
+
+    /// The lowest numbered logical cpu of each physical core, ordered by
+    /// socket and core; pinning one benchmark thread to each keeps SMT
+    /// siblings from sharing a core.
+    pub fn one_per_core(&self) -> Vec<&Cpu<'a>> {
+        self.by_core()
+            .into_values()
+            .filter_map(|cpus| cpus.into_iter().min_by_key(|cpu| cpu.processor))
+            .collect()
+    }
+
+    /// One summary per socket, ordered by `physical id`.
+    pub fn socket_summaries(&self) -> Vec<SocketSummary<'a>> {
+        self.by_socket()
+            .into_iter()
+            .map(|(socket, cpus)| {
+                let first = cpus[0];
+                let cores: BTreeSet<u32> = cpus.iter().map(|cpu| cpu.core_id).collect();
+                SocketSummary {
+                    socket,
+                    cpus: cpus.len(),
+                    cores: cores.len(),
+                    vendor_id: first.vendor_id,
+                    cpu_family: first.cpu_family,
+                    model: first.model,
+                    stepping: first.stepping,
+                    model_name: first.model_name,
+                    microcode: first.microcode,
+                    common_flags: cpus
+                        .iter()
+                        .map(|cpu| cpu.flag_set())
+                        .reduce(|common, flags| common.intersection(&flags))
+                        .unwrap_or_default(),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{CpuBuilder, CpuFlag, CpuInfoBuilder};
+    use alloc::vec;
+    use alloc::vec::Vec;
+
+    fn cpu(processor: u32, socket: u32, stepping: u32) -> crate::Cpu<'static> {
+        CpuBuilder::new()
+            .processor(processor)
+            .physical_id(Some(socket))
+            .core_id(processor / 2)
+            .model_name("Xeon")
+            .stepping(Some(stepping))
+            .flags(vec!["fpu", "avx2"])
+            .build()
+    }
+
+    #[test]
+    fn groups_cpus_by_socket() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu(0, 0, 4))
+            .cpu(cpu(4, 1, 7))
+            .cpu(cpu(1, 0, 4))
+            .cpu(CpuBuilder::new().processor(5).physical_id(Some(1)).build())
+            .build();
+
+        let sockets = cpuinfo.by_socket();
+        let processors: Vec<(u32, Vec<u32>)> = sockets
+            .iter()
+            .map(|(&socket, cpus)| (socket, cpus.iter().map(|cpu| cpu.processor).collect()))
+            .collect();
+        assert_eq!(processors, [(0, vec![0, 1]), (1, vec![4, 5])]);
+
+        let summaries = cpuinfo.socket_summaries();
+        assert_eq!(summaries.len(), 2);
+        assert_eq!((summaries[0].cpus, summaries[0].cores), (2, 1));
+        assert_eq!((summaries[1].cpus, summaries[1].cores), (2, 2));
+        assert_eq!(summaries[0].stepping, Some(4));
+        assert_eq!(summaries[1].stepping, Some(7));
+        assert!(summaries[0].common_flags.contains(&CpuFlag::Avx2));
+        // Socket 1's second cpu lists no flags.
+        assert!(summaries[1].common_flags.is_empty());
+        assert!(!summaries[0].same_part(&summaries[1]));
+    }
+
+    #[test]
+    fn matches_identical_sockets() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu(0, 0, 4))
+            .cpu(cpu(1, 0, 4))
+            .cpu(cpu(2, 1, 4))
+            .build();
+        let summaries = cpuinfo.socket_summaries();
+        assert!(summaries[0].same_part(&summaries[1]));
+        assert!(CpuInfoBuilder::new().build().by_socket().is_empty());
+    }
+
+    #[test]
+    fn picks_one_cpu_per_core() {
+        // Cores are numbered processor / 2; the cpus are listed out of
+        // order and core 3 has a single thread.
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu(4, 1, 4))
+            .cpu(cpu(0, 0, 4))
+            .cpu(cpu(2, 0, 4))
+            .cpu(cpu(6, 1, 4))
+            .cpu(cpu(1, 0, 4))
+            .cpu(cpu(3, 0, 4))
+            .cpu(cpu(5, 1, 4))
+            .build();
+
+        let cores: Vec<((u32, u32), Vec<u32>)> = cpuinfo
+            .by_core()
+            .into_iter()
+            .map(|(core, cpus)| (core, cpus.iter().map(|cpu| cpu.processor).collect()))
+            .collect();
+        assert_eq!(
+            cores,
+            [
+                ((0, 0), vec![0, 1]),
+                ((0, 1), vec![2, 3]),
+                ((1, 2), vec![4, 5]),
+                ((1, 3), vec![6]),
+            ]
+        );
+        let pinned: Vec<u32> = cpuinfo
+            .one_per_core()
+            .iter()
+            .map(|cpu| cpu.processor)
+            .collect();
+        assert_eq!(pinned, [0, 2, 4, 6]);
+    }
+}
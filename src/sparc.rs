@@ -0,0 +1,311 @@
+use alloc::vec::Vec;
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, alpha1, alphanumeric1, hex_digit1, not_line_ending},
+    combinator::{map, map_res, opt},
+    multi::{many0, separated_list0},
+    sequence::{delimited, preceded, terminated, tuple},
+    IResult,
+};
+
+use crate::{error::Presence, field_value, finish, line_end, separator, FieldSpec, Result, Schema};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuInfo<'a> {
+    pub cpu: &'a str,
+    pub fpu: &'a str,
+    pub pmu: &'a str,
+    pub prom: &'a str,
+    pub cpu_type: &'a str,
+    pub ncpus_probed: u32,
+    pub ncpus_active: u32,
+    pub dcache_parity_tl1: Option<u32>,
+    pub icache_parity_tl1: Option<u32>,
+    pub cpucaps: Vec<&'a str>,
+    pub clock_ticks: Vec<ClockTick>,
+    pub mmu_type: Option<&'a str>,
+    pub mmu_page_sizes: Vec<&'a str>,
+    pub cpu_states: Vec<CpuState<'a>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ClockTick {
+    pub cpu: u32,
+    pub ticks: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CpuState<'a> {
+    pub cpu: u32,
+    pub state: &'a str,
+}
+
+const SCHEMA: Schema = Schema {
+    fields: &[
+        FieldSpec::required("cpu"),
+        FieldSpec::required("fpu"),
+        FieldSpec::required("pmu"),
+        FieldSpec::required("prom"),
+        FieldSpec::required("type"),
+        FieldSpec::required("ncpus probed"),
+        FieldSpec::required("ncpus active"),
+        FieldSpec::optional("D$ parity tl1"),
+        FieldSpec::optional("I$ parity tl1"),
+        FieldSpec::optional("cpucaps"),
+        FieldSpec::repeated("Cpu", Presence::Optional),
+        FieldSpec::optional("MMU Type"),
+        FieldSpec::optional("MMU PGSZs"),
+        FieldSpec::optional("State"),
+        FieldSpec::repeated("CPU", Presence::Optional),
+    ],
+    restart: 0,
+};
+
+pub fn cpuinfo(input: &str) -> Result<CpuInfo<'_>> {
+    finish(input, info, &SCHEMA, false)
+}
+
+fn info(input: &str) -> IResult<&str, CpuInfo<'_>> {
+    let (input, cpu) = cpu(input)?;
+    let (input, fpu) = fpu(input)?;
+    let (input, pmu) = pmu(input)?;
+    let (input, prom) = prom(input)?;
+    let (input, cpu_type) = cpu_type(input)?;
+    let (input, ncpus_probed) = ncpus_probed(input)?;
+    let (input, ncpus_active) = ncpus_active(input)?;
+    let (input, dcache_parity_tl1) = opt(dcache_parity_tl1)(input)?;
+    let (input, icache_parity_tl1) = opt(icache_parity_tl1)(input)?;
+    let (input, cpucaps) = map(opt(cpucaps), Option::unwrap_or_default)(input)?;
+    let (input, clock_ticks) = many0(clock_tick)(input)?;
+    let (input, mmu_type) = opt(mmu_type)(input)?;
+    let (input, mmu_page_sizes) = map(opt(mmu_page_sizes), Option::unwrap_or_default)(input)?;
+    let (input, cpu_states) = map(opt(cpu_states), Option::unwrap_or_default)(input)?;
+
+    let cpuinfo = CpuInfo {
+        cpu,
+        fpu,
+        pmu,
+        prom,
+        cpu_type,
+        ncpus_probed,
+        ncpus_active,
+        dcache_parity_tl1,
+        icache_parity_tl1,
+        cpucaps,
+        clock_ticks,
+        mmu_type,
+        mmu_page_sizes,
+        cpu_states,
+    };
+
+    Ok((input, cpuinfo))
+}
+
+fn comma_list(input: &str) -> IResult<&str, Vec<&str>> {
+    separated_list0(tag(","), alphanumeric1)(input)
+}
+
+fn cpu(input: &str) -> IResult<&str, &str> {
+    field_value(tag("cpu"), not_line_ending)(input)
+}
+
+fn fpu(input: &str) -> IResult<&str, &str> {
+    field_value(tag("fpu"), not_line_ending)(input)
+}
+
+fn pmu(input: &str) -> IResult<&str, &str> {
+    field_value(tag("pmu"), not_line_ending)(input)
+}
+
+fn prom(input: &str) -> IResult<&str, &str> {
+    field_value(tag("prom"), not_line_ending)(input)
+}
+
+fn cpu_type(input: &str) -> IResult<&str, &str> {
+    field_value(tag("type"), not_line_ending)(input)
+}
+
+fn ncpus_probed(input: &str) -> IResult<&str, u32> {
+    field_value(tag("ncpus probed"), complete::u32)(input)
+}
+
+fn ncpus_active(input: &str) -> IResult<&str, u32> {
+    field_value(tag("ncpus active"), complete::u32)(input)
+}
+
+fn dcache_parity_tl1(input: &str) -> IResult<&str, u32> {
+    field_value(tag("D$ parity tl1"), complete::u32)(input)
+}
+
+fn icache_parity_tl1(input: &str) -> IResult<&str, u32> {
+    field_value(tag("I$ parity tl1"), complete::u32)(input)
+}
+
+fn cpucaps(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("cpucaps"), comma_list)(input)
+}
+
+fn clock_tick(input: &str) -> IResult<&str, ClockTick> {
+    map(
+        terminated(
+            tuple((
+                delimited(tag("Cpu"), complete::u32, tag("ClkTck")),
+                preceded(
+                    separator,
+                    map_res(hex_digit1, |out: &str| u64::from_str_radix(out, 16)),
+                ),
+            )),
+            line_end,
+        ),
+        |(cpu, ticks)| ClockTick { cpu, ticks },
+    )(input)
+}
+
+fn mmu_type(input: &str) -> IResult<&str, &str> {
+    field_value(tag("MMU Type"), not_line_ending)(input)
+}
+
+fn mmu_page_sizes(input: &str) -> IResult<&str, Vec<&str>> {
+    field_value(tag("MMU PGSZs"), comma_list)(input)
+}
+
+fn cpu_state(input: &str) -> IResult<&str, CpuState<'_>> {
+    map(
+        terminated(
+            tuple((
+                preceded(tag("CPU"), complete::u32),
+                preceded(separator, alpha1),
+            )),
+            line_end,
+        ),
+        |(cpu, state)| CpuState { cpu, state },
+    )(input)
+}
+
+fn cpu_states(input: &str) -> IResult<&str, Vec<CpuState<'_>>> {
+    preceded(terminated(tag("State:"), line_end), many0(cpu_state))(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    #[test]
+    fn parses_cpu() {
+        let result = cpu("cpu		: UltraSparc T2 (Niagara2)
+");
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, "UltraSparc T2 (Niagara2)");
+    }
+
+    #[test]
+    fn parses_ncpus_probed() {
+        let result = ncpus_probed(
+            "ncpus probed	: 64
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().1, 64);
+    }
+
+    #[test]
+    fn parses_cpucaps() {
+        let result = cpucaps(
+            "cpucaps		: flush,stbar,swap,muldiv,v9,blkinit,n2,mul32,div32,v8plus,popc,vis,vis2,ASIBlkInit
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec![
+                "flush",
+                "stbar",
+                "swap",
+                "muldiv",
+                "v9",
+                "blkinit",
+                "n2",
+                "mul32",
+                "div32",
+                "v8plus",
+                "popc",
+                "vis",
+                "vis2",
+                "ASIBlkInit",
+            ]
+        )
+    }
+
+    #[test]
+    fn parses_clock_tick() {
+        let result = clock_tick(
+            "Cpu12ClkTck	: 000000005d21dba0
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            ClockTick {
+                cpu: 12,
+                ticks: 0x5d21dba0,
+            }
+        )
+    }
+
+    #[test]
+    fn parses_cpu_states() {
+        let result = cpu_states(
+            "State:
+CPU0:		online
+CPU1:		online
+",
+        );
+        assert!(result.is_ok());
+        assert_eq!(
+            result.unwrap().1,
+            vec![
+                CpuState {
+                    cpu: 0,
+                    state: "online",
+                },
+                CpuState {
+                    cpu: 1,
+                    state: "online",
+                },
+            ]
+        )
+    }
+
+    #[test]
+    fn parses_cpuinfo() {
+        let result = cpuinfo(
+            "cpu		: UltraSparc T2 (Niagara2)
+fpu		: UltraSparc T2 integrated FPU
+pmu		: niagara2
+prom		: OBP 4.30.4.d 2011/07/06 14:29
+type		: sun4v
+ncpus probed	: 2
+ncpus active	: 2
+D$ parity tl1	: 0
+I$ parity tl1	: 0
+cpucaps		: flush,stbar,swap,muldiv,v9,blkinit,n2,mul32,div32,v8plus,popc,vis,vis2,ASIBlkInit
+Cpu0ClkTck	: 000000005d21dba0
+Cpu1ClkTck	: 000000005d21dba0
+MMU Type	: Hypervisor (sun4v)
+MMU PGSZs	: 8K,64K,4MB,256MB
+State:
+CPU0:		online
+CPU1:		online
+",
+        );
+        assert!(result.is_ok());
+
+        let cpuinfo = result.unwrap();
+        assert_eq!(cpuinfo.cpu_type, "sun4v");
+        assert_eq!(cpuinfo.ncpus_active, 2);
+        assert_eq!(cpuinfo.clock_ticks.len(), 2);
+        assert_eq!(cpuinfo.mmu_page_sizes, vec!["8K", "64K", "4MB", "256MB"]);
+        assert_eq!(cpuinfo.cpu_states.len(), 2);
+    }
+}
@@ -0,0 +1,312 @@
+use crate::Cpu;
+
+/// Published specifications of a processor model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CpuSpec {
+    pub vendor_id: &'static str,
+    pub family: u32,
+    /// Product line as it appears in `model name`, e.g. `Xeon(R) Gold`.
+    pub line: &'static str,
+    /// Model number, e.g. `6338` or `i7-6700K`.
+    pub number: &'static str,
+    pub cores: u32,
+    pub threads: u32,
+    pub base_mhz: u32,
+    pub boost_mhz: u32,
+    pub tdp_watts: u32,
+    pub launch_year: u16,
+    pub socket: &'static str,
+}
+
+const fn intel(
+    line: &'static str,
+    number: &'static str,
+    (cores, threads): (u32, u32),
+    (base_mhz, boost_mhz): (u32, u32),
+    tdp_watts: u32,
+    launch_year: u16,
+    socket: &'static str,
+) -> CpuSpec {
+    CpuSpec {
+        vendor_id: "GenuineIntel",
+        family: 6,
+        line,
+        number,
+        cores,
+        threads,
+        base_mhz,
+        boost_mhz,
+        tdp_watts,
+        launch_year,
+        socket,
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+const fn amd(
+    family: u32,
+    line: &'static str,
+    number: &'static str,
+    (cores, threads): (u32, u32),
+    (base_mhz, boost_mhz): (u32, u32),
+    tdp_watts: u32,
+    launch_year: u16,
+    socket: &'static str,
+) -> CpuSpec {
+    CpuSpec {
+        vendor_id: "AuthenticAMD",
+        family,
+        line,
+        number,
+        cores,
+        threads,
+        base_mhz,
+        boost_mhz,
+        tdp_watts,
+        launch_year,
+        socket,
+    }
+}
+
+// Hybrid Intel parts list the performance cores' base clock.
+const SPECS: &[CpuSpec] = &[
+    intel(
+        "Core(TM)",
+        "i7-6700K",
+        (4, 8),
+        (4000, 4200),
+        91,
+        2015,
+        "LGA1151",
+    ),
+    intel(
+        "Core(TM)",
+        "i9-9900K",
+        (8, 16),
+        (3600, 5000),
+        95,
+        2018,
+        "LGA1151",
+    ),
+    intel(
+        "Core(TM)",
+        "i9-12900K",
+        (16, 24),
+        (3200, 5200),
+        125,
+        2021,
+        "LGA1700",
+    ),
+    intel(
+        "Core(TM)",
+        "i9-13900K",
+        (24, 32),
+        (3000, 5800),
+        125,
+        2022,
+        "LGA1700",
+    ),
+    intel(
+        "Xeon(R) Gold",
+        "6148",
+        (20, 40),
+        (2400, 3700),
+        150,
+        2017,
+        "LGA3647",
+    ),
+    intel(
+        "Xeon(R) Platinum",
+        "8180",
+        (28, 56),
+        (2500, 3800),
+        205,
+        2017,
+        "LGA3647",
+    ),
+    intel(
+        "Xeon(R) Gold",
+        "6248",
+        (20, 40),
+        (2500, 3900),
+        150,
+        2019,
+        "LGA3647",
+    ),
+    intel(
+        "Xeon(R) Gold",
+        "6338",
+        (32, 64),
+        (2000, 3200),
+        205,
+        2021,
+        "LGA4189",
+    ),
+    intel(
+        "Xeon(R) Platinum",
+        "8380",
+        (40, 80),
+        (2300, 3400),
+        270,
+        2021,
+        "LGA4189",
+    ),
+    intel(
+        "Xeon(R) Platinum",
+        "8480+",
+        (56, 112),
+        (2000, 3800),
+        350,
+        2023,
+        "LGA4677",
+    ),
+    amd(
+        0x17,
+        "EPYC",
+        "7601",
+        (32, 64),
+        (2200, 3200),
+        180,
+        2017,
+        "SP3",
+    ),
+    amd(
+        0x17,
+        "EPYC",
+        "7742",
+        (64, 128),
+        (2250, 3400),
+        225,
+        2019,
+        "SP3",
+    ),
+    amd(
+        0x19,
+        "EPYC",
+        "7543",
+        (32, 64),
+        (2800, 3700),
+        225,
+        2021,
+        "SP3",
+    ),
+    amd(
+        0x19,
+        "EPYC",
+        "7763",
+        (64, 128),
+        (2450, 3500),
+        280,
+        2021,
+        "SP3",
+    ),
+    amd(
+        0x19,
+        "EPYC",
+        "9654",
+        (96, 192),
+        (2400, 3700),
+        360,
+        2022,
+        "SP5",
+    ),
+    amd(
+        0x17,
+        "Ryzen 9",
+        "3900X",
+        (12, 24),
+        (3800, 4600),
+        105,
+        2019,
+        "AM4",
+    ),
+    amd(
+        0x17,
+        "Ryzen Threadripper",
+        "3990X",
+        (64, 128),
+        (2900, 4300),
+        280,
+        2020,
+        "sTRX4",
+    ),
+    amd(
+        0x19,
+        "Ryzen 7",
+        "5800X",
+        (8, 16),
+        (3800, 4700),
+        105,
+        2020,
+        "AM4",
+    ),
+    amd(
+        0x19,
+        "Ryzen 9",
+        "5950X",
+        (16, 32),
+        (3400, 4900),
+        105,
+        2020,
+        "AM4",
+    ),
+    amd(
+        0x19,
+        "Ryzen 9",
+        "7950X",
+        (16, 32),
+        (4500, 5700),
+        170,
+        2022,
+        "AM5",
+    ),
+];
+
+impl CpuSpec {
+    /// Looks a part up by the `vendor_id`, `cpu family` and `model name` the
+    /// kernel reports. The model number must appear as a word of the name.
+    pub fn lookup(vendor_id: &str, family: u32, model_name: &str) -> Option<&'static Self> {
+        SPECS.iter().find(|spec| {
+            spec.vendor_id == vendor_id
+                && spec.family == family
+                && model_name.contains(spec.line)
+                && model_name
+                    .split_whitespace()
+                    .any(|word| word == spec.number)
+        })
+    }
+}
+
+impl Cpu<'_> {
+    pub fn spec(&self) -> Option<&'static CpuSpec> {
+        CpuSpec::lookup(self.vendor_id, self.cpu_family, self.model_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        Generator, Profile,
+    };
+
+    #[test]
+    fn looks_up_specs() {
+        let skylake = cpuinfo(SKYLAKE).unwrap();
+        let spec = skylake[0].spec().unwrap();
+        assert_eq!((spec.cores, spec.threads), (4, 8));
+        assert_eq!(spec.socket, "LGA1151");
+
+        let epyc = cpuinfo(EPYC).unwrap();
+        assert_eq!(epyc[0].spec().unwrap().boost_mhz, 3500);
+
+        let icelake = Generator::new(Profile::IceLakeServer).build();
+        assert_eq!(icelake[0].spec().unwrap().launch_year, 2021);
+        assert!(Generator::new(Profile::Zen4).build()[0].spec().is_some());
+
+        assert!(CpuSpec::lookup("AuthenticAMD", 0x17, "AMD EPYC 7763 64-Core Processor").is_none());
+        assert!(CpuSpec::lookup("GenuineIntel", 6, "Intel(R) Xeon(R) Gold 63380").is_none());
+    }
+}
@@ -0,0 +1,225 @@
+use alloc::{string::ToString, vec::Vec};
+use nom::{
+    bytes::complete::tag,
+    character::complete::{self, space0, space1},
+    combinator::{all_consuming, opt},
+    multi::separated_list1,
+    sequence::{delimited, pair, preceded},
+    IResult,
+};
+
+use crate::{CpuInfo, CpuInfoError, Location, Result};
+
+/// Time spent in each state, in `USER_HZ` ticks, from a `/proc/stat` cpu
+/// line. Columns missing on older kernels are zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuTimes {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    pub guest: u64,
+    pub guest_nice: u64,
+}
+
+impl CpuTimes {
+    /// All ticks. `guest` and `guest_nice` are already part of `user` and
+    /// `nice`, so they are not counted again.
+    pub fn total(&self) -> u64 {
+        self.user
+            + self.nice
+            + self.system
+            + self.idle
+            + self.iowait
+            + self.irq
+            + self.softirq
+            + self.steal
+    }
+
+    pub fn idle_total(&self) -> u64 {
+        self.idle + self.iowait
+    }
+
+    /// Fraction of time between `self` and the later sample `later` that
+    /// was not idle, from 0.0 to 1.0. `None` when no time has passed.
+    pub fn utilization(&self, later: &CpuTimes) -> Option<f64> {
+        let total = later.total().checked_sub(self.total())?;
+        let idle = later.idle_total().saturating_sub(self.idle_total());
+        (total > 0).then(|| total.saturating_sub(idle) as f64 / total as f64)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stat {
+    /// The aggregate `cpu` line.
+    pub total: CpuTimes,
+    /// `(processor, times)` from the `cpuN` lines.
+    pub cpus: Vec<(u32, CpuTimes)>,
+}
+
+impl Stat {
+    pub fn get(&self, processor: u32) -> Option<&CpuTimes> {
+        self.cpus
+            .iter()
+            .find(|(p, _)| *p == processor)
+            .map(|(_, times)| times)
+    }
+
+    /// Per-cpu utilization between `self` and the later sample `later`.
+    pub fn utilization(&self, later: &Stat) -> Vec<(u32, Option<f64>)> {
+        self.cpus
+            .iter()
+            .filter_map(|(processor, before)| {
+                let after = later.get(*processor)?;
+                Some((*processor, before.utilization(after)))
+            })
+            .collect()
+    }
+}
+
+fn cpu_line(input: &str) -> IResult<&str, (Option<u32>, Vec<u64>)> {
+    all_consuming(pair(
+        preceded(tag("cpu"), opt(complete::u32)),
+        delimited(space1, separated_list1(space1, complete::u64), space0),
+    ))(input)
+}
+
+fn times(values: &[u64]) -> Option<CpuTimes> {
+    let value = |i: usize| values.get(i).copied().unwrap_or_default();
+    (values.len() >= 4).then(|| CpuTimes {
+        user: value(0),
+        nice: value(1),
+        system: value(2),
+        idle: value(3),
+        iowait: value(4),
+        irq: value(5),
+        softirq: value(6),
+        steal: value(7),
+        guest: value(8),
+        guest_nice: value(9),
+    })
+}
+
+/// Parses the cpu lines of `/proc/stat`, ignoring the other counters.
+pub fn stat(input: &str) -> Result<Stat> {
+    let mut stat = Stat::default();
+
+    for (i, line) in input.lines().enumerate() {
+        if !line.starts_with("cpu") {
+            continue;
+        }
+
+        let parsed = cpu_line(line)
+            .ok()
+            .and_then(|(_, (processor, values))| times(&values).map(|times| (processor, times)));
+        match parsed {
+            Some((None, times)) => stat.total = times,
+            Some((Some(processor), times)) => stat.cpus.push((processor, times)),
+            None => {
+                let (field, value) = line.split_once(' ').unwrap_or((line, ""));
+                return Err(CpuInfoError::InvalidValue {
+                    field: field.to_string(),
+                    value: value.trim().to_string(),
+                    location: Location {
+                        line: i + 1,
+                        processor: field.strip_prefix("cpu").and_then(|n| n.parse().ok()),
+                    },
+                });
+            }
+        }
+    }
+
+    Ok(stat)
+}
+
+impl CpuInfo<'_> {
+    /// Utilization of every cpu between two `/proc/stat` samples, in the
+    /// same order as `cpus`. Cpus missing from either sample get `None`.
+    pub fn utilization(&self, before: &Stat, after: &Stat) -> Vec<Option<f64>> {
+        self.cpus
+            .iter()
+            .map(|cpu| {
+                before
+                    .get(cpu.processor)?
+                    .utilization(after.get(cpu.processor)?)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, fixtures::SKYLAKE};
+
+    const BEFORE: &str = "cpu  4705 356 584 3699176 23060 0 277 0 0 0
+cpu0 1393280 32966 572056 13343292 6130 0 17875 0 23933 0
+cpu1 1335 6 298 1800 0 0 0 0 0 0
+intr 1462898 0 0 0
+ctxt 115315
+btime 769041601
+processes 86031
+";
+
+    #[test]
+    fn parses_stat() {
+        let stat = stat(BEFORE).unwrap();
+        assert_eq!(stat.total.user, 4705);
+        assert_eq!(stat.cpus.len(), 2);
+        assert_eq!(
+            stat.get(1),
+            Some(&CpuTimes {
+                user: 1335,
+                nice: 6,
+                system: 298,
+                idle: 1800,
+                ..Default::default()
+            })
+        );
+    }
+
+    #[test]
+    fn parses_old_stat() {
+        let stat = stat("cpu  1 2 3 4\ncpu0 1 2 3 4\n").unwrap();
+        assert_eq!(stat.get(0).unwrap().idle, 4);
+        assert_eq!(stat.get(0).unwrap().steal, 0);
+    }
+
+    #[test]
+    fn rejects_invalid_lines() {
+        assert!(matches!(
+            stat("cpu  1 2 3 4\ncpu0 1 2 x 4\n"),
+            Err(CpuInfoError::InvalidValue {
+                field,
+                location: Location {
+                    line: 2,
+                    processor: Some(0),
+                },
+                ..
+            }) if field == "cpu0"
+        ));
+    }
+
+    #[test]
+    fn computes_utilization() {
+        let before = stat(BEFORE).unwrap();
+        let after = stat(
+            &BEFORE
+                .replace("cpu0 1393280", "cpu0 1393380")
+                .replace("1800", "2100"),
+        )
+        .unwrap();
+
+        assert_eq!(before.utilization(&after), [(0, Some(1.0)), (1, Some(0.0))]);
+
+        let cpuinfo = cpuinfo(SKYLAKE).unwrap();
+        assert_eq!(cpuinfo.utilization(&before, &after), [Some(1.0)]);
+        assert_eq!(cpuinfo.utilization(&before, &before), [None]);
+    }
+}
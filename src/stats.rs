@@ -0,0 +1,146 @@
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+
+use crate::{CacheSize, CpuInfo, Frequency};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SocketStats {
+    pub socket: u32,
+    pub cpus: usize,
+    pub cores: usize,
+    pub min_frequency: Frequency,
+    pub max_frequency: Frequency,
+    pub mean_frequency: Frequency,
+    pub cache_size: CacheSize,
+}
+
+impl CpuInfo<'_> {
+    pub fn min_frequency(&self) -> Option<Frequency> {
+        self.cpus.iter().map(|cpu| cpu.cpu_mhz).min()
+    }
+
+    pub fn max_frequency(&self) -> Option<Frequency> {
+        self.cpus.iter().map(|cpu| cpu.cpu_mhz).max()
+    }
+
+    pub fn mean_frequency(&self) -> Option<Frequency> {
+        let sum: u64 = self.cpus.iter().map(|cpu| cpu.cpu_mhz.as_hz()).sum();
+        (!self.cpus.is_empty()).then(|| Frequency::from_hz(sum / self.cpus.len() as u64))
+    }
+
+    pub fn min_mhz(&self) -> Option<f64> {
+        self.min_frequency().map(|frequency| frequency.as_mhz())
+    }
+
+    pub fn max_mhz(&self) -> Option<f64> {
+        self.max_frequency().map(|frequency| frequency.as_mhz())
+    }
+
+    pub fn mean_mhz(&self) -> Option<f64> {
+        self.mean_frequency().map(|frequency| frequency.as_mhz())
+    }
+
+    /// `cache size` describes the package's last level cache and is repeated
+    /// for every logical cpu, so it is counted once per socket.
+    pub fn total_cache_size(&self) -> CacheSize {
+        self.sockets()
+            .into_iter()
+            .filter_map(|socket| {
+                self.cpus
+                    .iter()
+                    .find(|cpu| cpu.physical_id.unwrap_or_default() == socket)
+                    .and_then(|cpu| cpu.cache_size)
+            })
+            .sum()
+    }
+
+    pub fn total_cache_bytes(&self) -> u64 {
+        self.total_cache_size().as_bytes()
+    }
+
+    pub fn socket_stats(&self) -> Vec<SocketStats> {
+        self.sockets()
+            .into_iter()
+            .map(|socket| {
+                let cpus = self.on_socket(socket);
+                let cores: BTreeSet<u32> = cpus.cpus.iter().map(|cpu| cpu.core_id).collect();
+                SocketStats {
+                    socket,
+                    cpus: cpus.cpus.len(),
+                    cores: cores.len(),
+                    min_frequency: cpus.min_frequency().unwrap_or_default(),
+                    max_frequency: cpus.max_frequency().unwrap_or_default(),
+                    mean_frequency: cpus.mean_frequency().unwrap_or_default(),
+                    cache_size: cpus.total_cache_size(),
+                }
+            })
+            .collect()
+    }
+
+    fn sockets(&self) -> BTreeSet<u32> {
+        self.cpus
+            .iter()
+            .map(|cpu| cpu.physical_id.unwrap_or_default())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CpuBuilder, CpuInfoBuilder};
+
+    fn cpu(processor: u32, socket: u32, mhz: f64) -> crate::Cpu<'static> {
+        CpuBuilder::new()
+            .processor(processor)
+            .physical_id(Some(socket))
+            .core_id(processor % 2)
+            .cpu_mhz(Frequency::from_mhz(mhz))
+            .cache_size(Some(CacheSize::from_mib(32)))
+            .build()
+    }
+
+    #[test]
+    fn aggregates_frequencies() {
+        let cpuinfo = CpuInfoBuilder::new()
+            .cpu(cpu(0, 0, 1000.0))
+            .cpu(cpu(1, 0, 3000.0))
+            .cpu(cpu(2, 1, 2000.0))
+            .cpu(cpu(3, 1, 2000.0))
+            .build();
+
+        assert_eq!(cpuinfo.min_frequency(), Some(Frequency::from_mhz(1000.0)));
+        assert_eq!(cpuinfo.max_frequency(), Some(Frequency::from_mhz(3000.0)));
+        assert_eq!(cpuinfo.mean_frequency(), Some(Frequency::from_mhz(2000.0)));
+        assert_eq!(cpuinfo.min_mhz(), Some(1000.0));
+        assert_eq!(cpuinfo.max_mhz(), Some(3000.0));
+        assert_eq!(cpuinfo.mean_mhz(), Some(2000.0));
+        assert_eq!(cpuinfo.total_cache_size(), CacheSize::from_mib(64));
+        assert_eq!(cpuinfo.total_cache_bytes(), 64 << 20);
+
+        let stats = cpuinfo.socket_stats();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(
+            stats[0],
+            SocketStats {
+                socket: 0,
+                cpus: 2,
+                cores: 2,
+                min_frequency: Frequency::from_mhz(1000.0),
+                max_frequency: Frequency::from_mhz(3000.0),
+                mean_frequency: Frequency::from_mhz(2000.0),
+                cache_size: CacheSize::from_mib(32),
+            }
+        );
+    }
+
+    #[test]
+    fn aggregates_nothing() {
+        let cpuinfo = CpuInfoBuilder::new().build();
+        assert_eq!(cpuinfo.min_frequency(), None);
+        assert_eq!(cpuinfo.mean_frequency(), None);
+        assert_eq!(cpuinfo.max_mhz(), None);
+        assert_eq!(cpuinfo.total_cache_size(), CacheSize::default());
+        assert!(cpuinfo.socket_stats().is_empty());
+    }
+}
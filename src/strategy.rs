@@ -0,0 +1,175 @@
+use std::sync::Arc;
+
+use proptest::{collection::vec, option, prelude::*};
+
+use crate::{AddressSizes, CacheSize, CpuInfoOwned, CpuOwned, Frequency, SizeUnit, TlbSize};
+
+fn names(max: usize) -> impl Strategy<Value = Arc<[Arc<str>]>> {
+    vec("[a-z][a-z0-9_]{0,11}".prop_map(Arc::from), 0..max).prop_map(Arc::from)
+}
+
+/// A float as it reads back from text with `decimals` places, so generated
+/// cpus survive rendering unchanged.
+fn decimal(max: u32, decimals: u32) -> impl Strategy<Value = f64> {
+    let scale = 10u32.pow(decimals);
+    (0..max * scale).prop_map(move |n| {
+        format!(
+            "{}.{:0width$}",
+            n / scale,
+            n % scale,
+            width = decimals as usize
+        )
+        .parse()
+        .unwrap()
+    })
+}
+
+impl Arbitrary for CpuOwned {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// Cpus the parsers accept in guest mode, with realistic vendors and
+    /// values in the ranges the kernel prints.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        let identity = (
+            0..4096u32,
+            prop_oneof![
+                "GenuineIntel",
+                "AuthenticAMD",
+                "HygonGenuine",
+                "CentaurHauls"
+            ],
+            0..0x20u32,
+            0..0x100u32,
+            "[A-Z][A-Za-z0-9()@.-]{0,11}( [A-Za-z0-9()@.-]{1,11}){0,4}",
+            option::of(0..16u32),
+            option::of(any::<u32>().prop_map(u64::from)),
+        );
+        let clocks = (
+            // `cpu MHz` is printed with three decimals, i.e. in kHz.
+            (0..6_000_000u64).prop_map(Frequency::from_khz),
+            option::of((1..65536u64).prop_map(CacheSize::from_kib)),
+            decimal(20000, 2),
+        );
+        let placement = (
+            option::of(0..8u32),
+            1..512u32,
+            0..256u32,
+            1..256u32,
+            any::<u8>().prop_map(u32::from),
+            any::<u8>().prop_map(u32::from),
+            0..0x30u32,
+        );
+        let features = (
+            any::<(bool, bool, bool)>(),
+            names(40),
+            names(8),
+            names(8),
+            names(8),
+            names(6),
+        );
+        let memory = (
+            option::of((
+                1..4096u32,
+                prop_oneof![Just(4u64 << 10), Just(2 << 20), Just(1 << 30)],
+            )),
+            prop_oneof![Just(32u32), Just(64)],
+            prop_oneof![Just(32u32), Just(64), Just(128)],
+            (32..58u32, 32..58u32),
+        );
+
+        (identity, clocks, placement, features, memory)
+            .prop_map(
+                |(
+                    (processor, vendor_id, cpu_family, model, model_name, stepping, microcode),
+                    (cpu_mhz, cache_size, bogomips),
+                    (
+                        physical_id,
+                        siblings,
+                        core_id,
+                        cpu_cores,
+                        apicid,
+                        initial_apicid,
+                        cpuid_level,
+                    ),
+                    ((fpu, fpu_exception, wp), flags, vmx_flags, svm_flags, bugs, power_management),
+                    (tlb_size, clflush_size, cache_alignment, (physical_size, virtual_size)),
+                )| CpuOwned {
+                    processor,
+                    vendor_id: vendor_id.to_string(),
+                    cpu_family,
+                    model,
+                    model_name: Arc::from(model_name),
+                    stepping,
+                    microcode,
+                    cpu_mhz,
+                    cache_size,
+                    cache_size_unit: SizeUnit::Kilobytes,
+                    physical_id,
+                    siblings,
+                    core_id,
+                    cpu_cores,
+                    apicid,
+                    initial_apicid,
+                    fpu,
+                    fpu_exception,
+                    cpuid_level,
+                    wp,
+                    flags,
+                    vmx_flags,
+                    svm_flags,
+                    bugs,
+                    bogomips,
+                    tlb_size: tlb_size.map(|(entries, page_size)| TlbSize { entries, page_size }),
+                    clflush_size,
+                    cache_alignment,
+                    address_sizes: AddressSizes {
+                        physical_size,
+                        virtual_size,
+                    },
+                    power_management,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl Arbitrary for CpuInfoOwned {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    /// One to eight cpus, numbered from 0 as the kernel does.
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        vec(any::<CpuOwned>(), 1..8)
+            .prop_map(|mut cpus| {
+                for (processor, cpu) in cpus.iter_mut().enumerate() {
+                    cpu.processor = processor as u32;
+                }
+                CpuInfoOwned { cpus }
+            })
+            .boxed()
+    }
+}
+
+/// Generated cpuinfo together with the `/proc/cpuinfo` text it renders to,
+/// for checking parsers against known answers.
+pub fn cpuinfo_text() -> impl Strategy<Value = (CpuInfoOwned, String)> {
+    any::<CpuInfoOwned>().prop_map(|info| {
+        let text = info.as_cpuinfo().to_proc_string();
+        (info, text)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cpuinfo_guest;
+
+    proptest! {
+        #[test]
+        fn round_trips_generated_cpuinfo((info, text) in cpuinfo_text()) {
+            let parsed = cpuinfo_guest(&text).unwrap().to_owned();
+            prop_assert_eq!(parsed, info);
+        }
+    }
+}
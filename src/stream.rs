@@ -0,0 +1,158 @@
+use std::io::BufRead;
+
+use crate::{cpuinfo, cpuinfo_guest, cpuinfo_lenient, CpuInfoError, CpuOwned, Mode, Result};
+
+/// Parses cpuinfo from a reader one processor block at a time, so that
+/// input with thousands of cpus, e.g. arriving over a socket, never has to
+/// be held in memory at once. Each cpu is yielded as soon as the blank line
+/// ending its block, or the end of input, has been read.
+///
+/// The first error ends the stream. Error locations count lines from the
+/// start of the input, as with [`cpuinfo`](crate::cpuinfo).
+pub struct CpuStream<R> {
+    reader: R,
+    mode: Mode,
+    block: String,
+    /// Lines consumed so far, including those of the pending block.
+    line: usize,
+    done: bool,
+}
+
+impl<R: BufRead> CpuStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            mode: Mode::default(),
+            block: String::new(),
+            line: 0,
+            done: false,
+        }
+    }
+
+    pub fn mode(mut self, mode: Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Reads up to the end of the next block, leaving it in `self.block`
+    /// and returning the line it starts at, or `None` at the end of input.
+    fn read_block(&mut self) -> std::io::Result<Option<usize>> {
+        self.block.clear();
+        let mut start = None;
+        loop {
+            let len = self.block.len();
+            if self.reader.read_line(&mut self.block)? == 0 {
+                return Ok(start);
+            }
+            self.line += 1;
+
+            if self.block[len..].trim().is_empty() {
+                if start.is_some() {
+                    return Ok(start);
+                }
+                self.block.truncate(len);
+            } else if start.is_none() {
+                start = Some(self.line);
+            }
+        }
+    }
+
+    fn parse_block(&self, start: usize) -> Result<Option<CpuOwned>> {
+        let cpuinfo = match self.mode {
+            Mode::Strict => cpuinfo(&self.block),
+            Mode::Guest => cpuinfo_guest(&self.block),
+            Mode::Lenient => Ok(cpuinfo_lenient(&self.block).cpuinfo),
+        }
+        .map_err(|e| offset(e, start - 1))?;
+        Ok(cpuinfo.cpus.first().map(|cpu| cpu.to_owned()))
+    }
+}
+
+impl<R: BufRead> Iterator for CpuStream<R> {
+    type Item = Result<CpuOwned>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            let result = match self.read_block() {
+                Ok(Some(start)) => self.parse_block(start).transpose(),
+                Ok(None) => None,
+                Err(e) => Some(Err(e.into())),
+            };
+            match result {
+                Some(Ok(cpu)) => return Some(Ok(cpu)),
+                Some(Err(e)) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+                // Lenient parsing may skip a whole block.
+                None if !self.block.is_empty() => continue,
+                None => self.done = true,
+            }
+        }
+        None
+    }
+}
+
+/// Moves the location of a block's error to where the block starts.
+fn offset(mut error: CpuInfoError, lines: usize) -> CpuInfoError {
+    match &mut error {
+        CpuInfoError::UnexpectedField { location, .. }
+        | CpuInfoError::MissingField { location, .. }
+        | CpuInfoError::InvalidValue { location, .. } => location.line += lines,
+        _ => {}
+    }
+    error
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{EPYC, SKYLAKE};
+
+    #[test]
+    fn streams_cpus() {
+        let input = format!("\n{}\n\n{}", SKYLAKE, EPYC);
+        let cpus: Vec<CpuOwned> = CpuStream::new(input.as_bytes())
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(cpus.len(), 2);
+        assert_eq!(cpus[0].model, 94);
+        assert_eq!(cpus[1].processor, 3);
+        assert_eq!(cpus[1].flags.len(), 101);
+    }
+
+    #[test]
+    fn reports_errors_relative_to_the_input() {
+        let input = format!(
+            "{}\n{}",
+            SKYLAKE,
+            EPYC.replace("model\t\t: 1", "model\t\t: x")
+        );
+        let mut stream = CpuStream::new(input.as_bytes());
+        assert!(stream.next().unwrap().is_ok());
+
+        let error = stream.next().unwrap().unwrap_err();
+        assert!(matches!(
+            error,
+            CpuInfoError::InvalidValue { location, .. } if location.line == 31
+        ));
+        assert_eq!(
+            error.to_string(),
+            crate::cpuinfo(&input).unwrap_err().to_string()
+        );
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn streams_guests() {
+        let guest = SKYLAKE.replace("microcode\t: 0xf0\n", "");
+        assert!(CpuStream::new(guest.as_bytes()).next().unwrap().is_err());
+
+        let cpu = CpuStream::new(guest.as_bytes())
+            .mode(Mode::Guest)
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(cpu.microcode, None);
+    }
+}
@@ -0,0 +1,120 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use nom::{
+    bytes::complete::tag,
+    character::complete,
+    combinator::{all_consuming, opt},
+    multi::separated_list0,
+    sequence::{pair, preceded},
+    IResult,
+};
+
+/// Access to the kernel's sysfs, rooted at `/sys` by default. Pointing it at
+/// another directory allows reading a copy taken from another machine.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sysfs {
+    root: PathBuf,
+}
+
+impl Default for Sysfs {
+    fn default() -> Self {
+        Self::new("/sys")
+    }
+}
+
+impl Sysfs {
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Reads a sysfs attribute, with the trailing newline removed.
+    pub(crate) fn read<P: AsRef<Path>>(&self, path: P) -> io::Result<String> {
+        let contents = fs::read_to_string(self.root.join(path))?;
+        Ok(contents.trim_end().to_string())
+    }
+
+    pub(crate) fn parse<T: FromStr, P: AsRef<Path>>(&self, path: P) -> Option<T> {
+        self.read(path).ok()?.parse().ok()
+    }
+
+    /// Numbers of the `<prefix>N` entries in `dir`, sorted.
+    pub(crate) fn numbered<P: AsRef<Path>>(&self, dir: P, prefix: &str) -> io::Result<Vec<u32>> {
+        let mut ids: Vec<u32> = fs::read_dir(self.root.join(dir))?
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                name.to_str()?.strip_prefix(prefix)?.parse().ok()
+            })
+            .collect();
+        ids.sort_unstable();
+        Ok(ids)
+    }
+
+    /// Numbers of the `cpuN` directories below `devices/system/cpu`.
+    pub(crate) fn cpu_ids(&self) -> io::Result<Vec<u32>> {
+        self.numbered("devices/system/cpu", "cpu")
+    }
+
+    /// Path of an attribute below `devices/system/cpu/cpuN`.
+    pub(crate) fn cpu_path(processor: u32, attribute: &str) -> PathBuf {
+        Path::new("devices/system/cpu")
+            .join(format!("cpu{}", processor))
+            .join(attribute)
+    }
+}
+
+fn cpu_range(input: &str) -> IResult<&str, (u32, Option<u32>)> {
+    pair(complete::u32, opt(preceded(tag("-"), complete::u32)))(input)
+}
+
+/// Parses the kernel's cpu list format, e.g. `0-3,8,10-11`.
+pub(crate) fn cpu_list(input: &str) -> Option<Vec<u32>> {
+    let (_, ranges) = all_consuming(separated_list0(tag(","), cpu_range))(input.trim()).ok()?;
+    Some(
+        ranges
+            .into_iter()
+            .flat_map(|(start, end)| start..=end.unwrap_or(start))
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::FakeSysfs;
+
+    #[test]
+    fn reads_attributes() {
+        let fake = FakeSysfs::new(
+            "sysfs",
+            &[(
+                "devices/system/cpu/cpu1/cpufreq/cpuinfo_max_freq",
+                "4200000\n",
+            )],
+        );
+        let sysfs = Sysfs::new(&fake.root);
+
+        let path = Sysfs::cpu_path(1, "cpufreq/cpuinfo_max_freq");
+        assert_eq!(sysfs.read(&path).unwrap(), "4200000");
+        assert_eq!(sysfs.parse::<u64, _>(&path), Some(4200000));
+        assert_eq!(sysfs.parse::<u64, _>(Sysfs::cpu_path(0, "online")), None);
+        assert_eq!(sysfs.cpu_ids().unwrap(), [1]);
+    }
+
+    #[test]
+    fn parses_cpu_lists() {
+        assert_eq!(cpu_list("0-3,8,10-11\n"), Some(vec![0, 1, 2, 3, 8, 10, 11]));
+        assert_eq!(cpu_list("5"), Some(vec![5]));
+        assert_eq!(cpu_list("\n"), Some(vec![]));
+        assert_eq!(cpu_list("0-"), None);
+    }
+}
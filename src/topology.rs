@@ -0,0 +1,316 @@
+use alloc::collections::{BTreeMap, BTreeSet};
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::CpuInfo;
+#[cfg(feature = "std")]
+use crate::{CacheHierarchy, CacheType};
+
+/// Where a logical cpu sits in the package/core/thread hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Placement {
+    pub processor: u32,
+    pub socket: u32,
+    pub core: u32,
+    /// Index of the thread among the logical cpus sharing its core, in the
+    /// order they are listed.
+    pub thread: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Topology {
+    pub sockets: usize,
+    pub cores_per_socket: usize,
+    /// Most threads listed for one core. See also
+    /// [`threads_per_core`](Self::threads_per_core).
+    pub listed_threads_per_core: usize,
+    /// `siblings` divided by `cpu cores`, the kernel's count of online
+    /// threads per core, or 0 where those fields are missing or zero.
+    pub reported_threads_per_core: usize,
+    /// Whether the cpus advertise the `ht` flag. Intel parts set it even
+    /// with SMT disabled or absent, so it only hints at capability.
+    pub ht: bool,
+    pub cpus: Vec<Placement>,
+}
+
+impl Topology {
+    pub fn placement(&self, processor: u32) -> Option<&Placement> {
+        self.cpus.iter().find(|cpu| cpu.processor == processor)
+    }
+
+    pub fn logical_cpus(&self) -> usize {
+        self.cpus.len()
+    }
+
+    /// Threads per core, the larger of those listed and those reported by
+    /// `siblings` and `cpu cores`, so input listing only some of the cpus
+    /// still counts their siblings.
+    pub fn threads_per_core(&self) -> usize {
+        self.listed_threads_per_core
+            .max(self.reported_threads_per_core)
+    }
+
+    /// Whether cores run more than one thread. The `ht` flag is not
+    /// enough: it stays set when SMT is turned off.
+    pub fn smt_enabled(&self) -> bool {
+        self.threads_per_core() > 1
+    }
+
+    /// The logical cpus sharing a core with `processor`, itself included,
+    /// in ascending order. Empty for an unknown processor.
+    pub fn siblings(&self, processor: u32) -> Vec<u32> {
+        let Some(placement) = self.placement(processor) else {
+            return Vec::new();
+        };
+        let mut siblings: Vec<u32> = self
+            .cpus
+            .iter()
+            .filter(|cpu| (cpu.socket, cpu.core) == (placement.socket, placement.core))
+            .map(|cpu| cpu.processor)
+            .collect();
+        siblings.sort_unstable();
+        siblings
+    }
+
+    /// Every logical cpu mapped to its sibling set, as returned by
+    /// [`siblings`](Self::siblings).
+    pub fn sibling_sets(&self) -> BTreeMap<u32, Vec<u32>> {
+        let mut cores: BTreeMap<(u32, u32), Vec<u32>> = BTreeMap::new();
+        for cpu in &self.cpus {
+            cores
+                .entry((cpu.socket, cpu.core))
+                .or_default()
+                .push(cpu.processor);
+        }
+        let mut sets = BTreeMap::new();
+        for mut siblings in cores.into_values() {
+            siblings.sort_unstable();
+            for &processor in &siblings {
+                sets.insert(processor, siblings.clone());
+            }
+        }
+        sets
+    }
+
+    /// A Graphviz graph of sockets, cores and threads, for rendering with
+    /// e.g. `dot -Tsvg`.
+    pub fn to_dot(&self) -> String {
+        self.dot(|_| {})
+    }
+
+    /// Like [`to_dot`](Self::to_dot), with a node for every distinct cache
+    /// linked to the threads sharing it. `caches` is as returned by
+    /// [`CpuInfo::cache_hierarchies`].
+    #[cfg(feature = "std")]
+    pub fn to_dot_with_caches(&self, caches: &[CacheHierarchy]) -> String {
+        self.dot(|out| {
+            let mut seen = Vec::new();
+            for cache in caches.iter().flat_map(|hierarchy| &hierarchy.caches) {
+                let Some(&owner) = cache.shared_cpus.first() else {
+                    continue;
+                };
+                let kind = match cache.cache_type {
+                    CacheType::Data => "d",
+                    CacheType::Instruction => "i",
+                    CacheType::Unified => "",
+                };
+                let id = (cache.level, kind, owner);
+                if seen.contains(&id) {
+                    continue;
+                }
+                seen.push(id);
+
+                let node = format!("l{}{}_{}", cache.level, kind, owner);
+                let _ = writeln!(
+                    out,
+                    "    {} [label=\"L{}{} {}\", shape=note];",
+                    node, cache.level, kind, cache.size
+                );
+                for cpu in &cache.shared_cpus {
+                    let _ = writeln!(out, "    {} -> cpu{} [style=dashed, dir=none];", node, cpu);
+                }
+            }
+        })
+    }
+
+    fn dot<F: FnOnce(&mut String)>(&self, extra: F) -> String {
+        let mut out = String::from("digraph topology {\n    node [shape=box];\n");
+        let mut sockets = BTreeSet::new();
+        let mut cores = BTreeSet::new();
+        for cpu in &self.cpus {
+            if sockets.insert(cpu.socket) {
+                let _ = writeln!(out, "    socket{0} [label=\"Socket {0}\"];", cpu.socket);
+            }
+            if cores.insert((cpu.socket, cpu.core)) {
+                let _ = writeln!(
+                    out,
+                    "    core{0}_{1} [label=\"Core {1}\"];\n    socket{0} -> core{0}_{1};",
+                    cpu.socket, cpu.core
+                );
+            }
+            let _ = writeln!(
+                out,
+                "    cpu{0} [label=\"CPU {0}\", shape=ellipse];\n    core{1}_{2} -> cpu{0};",
+                cpu.processor, cpu.socket, cpu.core
+            );
+        }
+        extra(&mut out);
+        out.push_str("}\n");
+        out
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Derives the topology from `physical id` and `core id`. Counts are the
+    /// largest seen, so hybrid parts report the widest socket and core.
+    /// Cpus without a `physical id`, as in some guests, are put in socket 0.
+    pub fn topology(&self) -> Topology {
+        let mut threads: BTreeMap<(u32, u32), u32> = BTreeMap::new();
+        let mut cores: BTreeMap<u32, BTreeSet<u32>> = BTreeMap::new();
+
+        let cpus: Vec<Placement> = self
+            .cpus
+            .iter()
+            .map(|cpu| {
+                let socket = cpu.physical_id.unwrap_or_default();
+                let thread = threads.entry((socket, cpu.core_id)).or_default();
+                let placement = Placement {
+                    processor: cpu.processor,
+                    socket,
+                    core: cpu.core_id,
+                    thread: *thread,
+                };
+                *thread += 1;
+                cores.entry(socket).or_default().insert(cpu.core_id);
+                placement
+            })
+            .collect();
+
+        Topology {
+            sockets: cores.len(),
+            cores_per_socket: cores.values().map(BTreeSet::len).max().unwrap_or_default(),
+            listed_threads_per_core: threads.values().max().copied().unwrap_or_default() as usize,
+            reported_threads_per_core: self
+                .cpus
+                .iter()
+                .filter(|cpu| cpu.cpu_cores > 0)
+                .map(|cpu| (cpu.siblings / cpu.cpu_cores) as usize)
+                .max()
+                .unwrap_or_default(),
+            ht: self.all_have_flag("ht"),
+            cpus,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cpuinfo, fixtures::SKYLAKE, CpuBuilder, CpuInfoBuilder};
+
+    #[test]
+    fn derives_single_cpu_topology() {
+        let topology = cpuinfo(SKYLAKE).unwrap().topology();
+        assert_eq!(topology.sockets, 1);
+        assert_eq!(topology.cores_per_socket, 1);
+        assert_eq!(topology.listed_threads_per_core, 1);
+        assert_eq!(topology.reported_threads_per_core, 2);
+        assert_eq!(topology.threads_per_core(), 2);
+        assert!(topology.smt_enabled());
+        assert!(!topology.ht);
+        assert_eq!(topology.siblings(0), [0]);
+        assert_eq!(
+            topology.placement(0),
+            Some(&Placement {
+                processor: 0,
+                socket: 0,
+                core: 0,
+                thread: 0,
+            })
+        );
+    }
+
+    #[test]
+    fn derives_multi_socket_topology() {
+        // Linux lists the first thread of every core before the siblings.
+        let mut builder = CpuInfoBuilder::new();
+        let mut processor = 0;
+        for _thread in 0..2 {
+            for socket in 0..2 {
+                for core in 0..4 {
+                    builder = builder.cpu(
+                        CpuBuilder::new()
+                            .processor(processor)
+                            .physical_id(Some(socket))
+                            .core_id(core)
+                            .build(),
+                    );
+                    processor += 1;
+                }
+            }
+        }
+
+        let topology = builder.build().topology();
+        assert_eq!(topology.sockets, 2);
+        assert_eq!(topology.cores_per_socket, 4);
+        assert_eq!(topology.listed_threads_per_core, 2);
+        assert_eq!(topology.logical_cpus(), 16);
+        assert_eq!(
+            topology.placement(13),
+            Some(&Placement {
+                processor: 13,
+                socket: 1,
+                core: 1,
+                thread: 1,
+            })
+        );
+        assert_eq!(topology.placement(16), None);
+        assert_eq!(topology.threads_per_core(), 2);
+        assert_eq!(topology.siblings(13), [5, 13]);
+        assert!(topology.siblings(16).is_empty());
+        let sets = topology.sibling_sets();
+        assert_eq!(sets.len(), 16);
+        assert_eq!(sets[&5], [5, 13]);
+
+        let dot = topology.to_dot();
+        assert!(dot.starts_with("digraph topology {\n"));
+        assert_eq!(dot.matches("[label=\"Socket").count(), 2);
+        assert_eq!(dot.matches("[label=\"Core").count(), 8);
+        assert!(dot.contains("    core1_1 -> cpu13;\n"));
+        assert!(dot.ends_with("}\n"));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn exports_cache_sharing() {
+        let cache = |level, cache_type, size, shared_cpus: &[u32]| crate::Cache {
+            level,
+            cache_type,
+            size,
+            shared_cpus: shared_cpus.to_vec(),
+        };
+        let mut builder = CpuInfoBuilder::new();
+        let mut caches = Vec::new();
+        for processor in 0..2 {
+            builder = builder.cpu(CpuBuilder::new().processor(processor).core_id(0).build());
+            caches.push(CacheHierarchy {
+                caches: vec![
+                    cache(1, CacheType::Data, crate::CacheSize::from_kib(32), &[0, 1]),
+                    cache(
+                        2,
+                        CacheType::Unified,
+                        crate::CacheSize::from_mib(1),
+                        &[0, 1],
+                    ),
+                ],
+            });
+        }
+
+        let dot = builder.build().topology().to_dot_with_caches(&caches);
+        assert!(dot.contains("    l1d_0 [label=\"L1d 32 KiB\", shape=note];\n"));
+        assert!(dot.contains("    l2_0 -> cpu1 [style=dashed, dir=none];\n"));
+        assert_eq!(dot.matches("shape=note").count(), 2);
+        assert_eq!(dot.matches("[label=\"Core").count(), 1);
+    }
+}
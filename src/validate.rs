@@ -0,0 +1,239 @@
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+use crate::CpuInfo;
+
+/// An inconsistency found by [`CpuInfo::validate`], typical of corrupt or
+/// hand-edited input.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TopologyWarning {
+    /// `siblings` is not a whole number of threads for each of `cpu cores`.
+    SiblingsMismatch {
+        processor: u32,
+        siblings: u32,
+        cpu_cores: u32,
+    },
+    /// More logical cpus are listed in the socket than `siblings` allows.
+    TooManyThreads {
+        socket: u32,
+        listed: usize,
+        siblings: u32,
+    },
+    /// More distinct core ids are listed in the socket than `cpu cores`.
+    TooManyCores {
+        socket: u32,
+        listed: usize,
+        cpu_cores: u32,
+    },
+    /// Core ids leave gaps on many parts, so only ids of at least four
+    /// times `cpu cores` are reported.
+    CoreIdOutOfRange {
+        processor: u32,
+        core_id: u32,
+        cpu_cores: u32,
+    },
+    DuplicateApicId {
+        apicid: u32,
+        processors: Vec<u32>,
+    },
+    /// Cpus of one socket report different parts.
+    MixedModels {
+        socket: u32,
+        processors: Vec<u32>,
+    },
+}
+
+impl fmt::Display for TopologyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::SiblingsMismatch {
+                processor,
+                siblings,
+                cpu_cores,
+            } => write!(
+                f,
+                "cpu {}: {} siblings is not a multiple of {} cores",
+                processor, siblings, cpu_cores
+            ),
+            Self::TooManyThreads {
+                socket,
+                listed,
+                siblings,
+            } => write!(
+                f,
+                "socket {}: {} cpus listed but only {} siblings",
+                socket, listed, siblings
+            ),
+            Self::TooManyCores {
+                socket,
+                listed,
+                cpu_cores,
+            } => write!(
+                f,
+                "socket {}: {} cores listed but only {} cpu cores",
+                socket, listed, cpu_cores
+            ),
+            Self::CoreIdOutOfRange {
+                processor,
+                core_id,
+                cpu_cores,
+            } => write!(
+                f,
+                "cpu {}: core id {} is out of range for {} cores",
+                processor, core_id, cpu_cores
+            ),
+            Self::DuplicateApicId { apicid, processors } => {
+                write!(f, "apicid {} is shared by cpus {:?}", apicid, processors)
+            }
+            Self::MixedModels { socket, processors } => {
+                write!(
+                    f,
+                    "socket {}: cpus {:?} report another model",
+                    socket, processors
+                )
+            }
+        }
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Checks that the topology fields agree with each other. Cpus without a
+    /// `physical id` are taken to be in socket 0, as by
+    /// [`topology`](Self::topology).
+    pub fn validate(&self) -> Vec<TopologyWarning> {
+        let mut warnings = Vec::new();
+        let mut sockets: BTreeMap<u32, Vec<_>> = BTreeMap::new();
+        let mut apicids: BTreeMap<u32, Vec<u32>> = BTreeMap::new();
+
+        for cpu in &self.cpus {
+            if cpu.cpu_cores == 0 || cpu.siblings % cpu.cpu_cores != 0 {
+                warnings.push(TopologyWarning::SiblingsMismatch {
+                    processor: cpu.processor,
+                    siblings: cpu.siblings,
+                    cpu_cores: cpu.cpu_cores,
+                });
+            }
+            if cpu.core_id >= cpu.cpu_cores.saturating_mul(4) {
+                warnings.push(TopologyWarning::CoreIdOutOfRange {
+                    processor: cpu.processor,
+                    core_id: cpu.core_id,
+                    cpu_cores: cpu.cpu_cores,
+                });
+            }
+            sockets
+                .entry(cpu.physical_id.unwrap_or_default())
+                .or_default()
+                .push(cpu);
+            apicids.entry(cpu.apicid).or_default().push(cpu.processor);
+        }
+
+        for (socket, cpus) in sockets {
+            let first = cpus[0];
+            let mut cores: Vec<u32> = cpus.iter().map(|cpu| cpu.core_id).collect();
+            cores.sort_unstable();
+            cores.dedup();
+
+            if cpus.len() > first.siblings as usize {
+                warnings.push(TopologyWarning::TooManyThreads {
+                    socket,
+                    listed: cpus.len(),
+                    siblings: first.siblings,
+                });
+            }
+            if cores.len() > first.cpu_cores as usize {
+                warnings.push(TopologyWarning::TooManyCores {
+                    socket,
+                    listed: cores.len(),
+                    cpu_cores: first.cpu_cores,
+                });
+            }
+
+            let processors: Vec<u32> = cpus
+                .iter()
+                .filter(|cpu| {
+                    (cpu.vendor_id, cpu.cpu_family, cpu.model, cpu.model_name)
+                        != (
+                            first.vendor_id,
+                            first.cpu_family,
+                            first.model,
+                            first.model_name,
+                        )
+                })
+                .map(|cpu| cpu.processor)
+                .collect();
+            if !processors.is_empty() {
+                warnings.push(TopologyWarning::MixedModels { socket, processors });
+            }
+        }
+
+        for (apicid, processors) in apicids {
+            if processors.len() > 1 {
+                warnings.push(TopologyWarning::DuplicateApicId { apicid, processors });
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{format, string::ToString, vec};
+
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+        Generator, Profile,
+    };
+
+    #[test]
+    fn accepts_consistent_topologies() {
+        assert!(cpuinfo(SKYLAKE).unwrap().validate().is_empty());
+        assert!(cpuinfo(EPYC).unwrap().validate().is_empty());
+        let generated = Generator::new(Profile::IceLakeServer)
+            .sockets(2)
+            .cores_per_socket(8)
+            .build();
+        assert!(generated.validate().is_empty());
+    }
+
+    #[test]
+    fn warns_about_inconsistencies() {
+        let broken = SKYLAKE
+            .replace("siblings\t: 8", "siblings\t: 1")
+            .replace("core id\t\t: 0", "core id\t\t: 16");
+        let other = EPYC.replace("apicid\t\t: 3", "apicid\t\t: 0");
+        let input = format!("{}\n{}", broken, other);
+        let warnings = cpuinfo(&input).unwrap().validate();
+
+        assert_eq!(
+            warnings,
+            [
+                TopologyWarning::SiblingsMismatch {
+                    processor: 0,
+                    siblings: 1,
+                    cpu_cores: 4,
+                },
+                TopologyWarning::CoreIdOutOfRange {
+                    processor: 0,
+                    core_id: 16,
+                    cpu_cores: 4,
+                },
+                TopologyWarning::TooManyThreads {
+                    socket: 0,
+                    listed: 2,
+                    siblings: 1,
+                },
+                TopologyWarning::MixedModels {
+                    socket: 0,
+                    processors: vec![3],
+                },
+                TopologyWarning::DuplicateApicId {
+                    apicid: 0,
+                    processors: vec![0, 3],
+                },
+            ]
+        );
+        assert_eq!(warnings[4].to_string(), "apicid 0 is shared by cpus [0, 3]");
+    }
+}
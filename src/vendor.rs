@@ -0,0 +1,89 @@
+use crate::{arm, Cpu};
+use alloc::string::{String, ToString};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Vendor {
+    Intel,
+    Amd,
+    /// An ARM licensee, identified by its `CPU implementer` code.
+    Arm(u32),
+    Centaur,
+    Hygon,
+    Unknown(String),
+}
+
+impl Vendor {
+    pub fn from_vendor_id(vendor_id: &str) -> Self {
+        match vendor_id {
+            "GenuineIntel" => Self::Intel,
+            "AuthenticAMD" | "AMDisbetter!" => Self::Amd,
+            "CentaurHauls" => Self::Centaur,
+            "HygonGenuine" => Self::Hygon,
+            other => Self::Unknown(other.to_string()),
+        }
+    }
+
+    pub fn from_arm_implementer(implementer: u32) -> Self {
+        Self::Arm(implementer)
+    }
+
+    pub fn is_intel(&self) -> bool {
+        *self == Self::Intel
+    }
+
+    pub fn is_amd(&self) -> bool {
+        *self == Self::Amd
+    }
+
+    pub fn is_arm(&self) -> bool {
+        matches!(self, Self::Arm(_))
+    }
+
+    pub fn is_centaur(&self) -> bool {
+        *self == Self::Centaur
+    }
+
+    pub fn is_hygon(&self) -> bool {
+        *self == Self::Hygon
+    }
+}
+
+impl Cpu<'_> {
+    pub fn vendor(&self) -> Vendor {
+        Vendor::from_vendor_id(self.vendor_id)
+    }
+}
+
+impl arm::Cpu<'_> {
+    pub fn vendor(&self) -> Vendor {
+        Vendor::from_arm_implementer(self.cpu_implementer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{EPYC, SKYLAKE},
+    };
+
+    #[test]
+    fn detects_vendor() {
+        assert!(cpuinfo(SKYLAKE).unwrap().cpus[0].vendor().is_intel());
+        assert!(cpuinfo(EPYC).unwrap().cpus[0].vendor().is_amd());
+        assert!(Vendor::from_vendor_id("HygonGenuine").is_hygon());
+        assert_eq!(
+            Vendor::from_vendor_id("  Shanghai  "),
+            Vendor::Unknown("  Shanghai  ".to_string())
+        );
+    }
+
+    #[test]
+    fn detects_arm_implementer() {
+        let vendor = Vendor::from_arm_implementer(0x41);
+        assert!(vendor.is_arm());
+        assert!(!vendor.is_intel());
+        assert_eq!(vendor, Vendor::Arm(0x41));
+    }
+}
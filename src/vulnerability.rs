@@ -0,0 +1,334 @@
+use std::{fs, io, path::Path};
+
+use crate::{CpuBug, CpuInfo, Result, Sysfs};
+
+const VULNERABILITIES: &str = "devices/system/cpu/vulnerabilities";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MitigationState {
+    NotAffected,
+    Mitigated,
+    Vulnerable,
+    /// The status could not be read or was not recognised.
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Vulnerability {
+    pub bug: CpuBug,
+    /// Status as printed in `devices/system/cpu/vulnerabilities`, e.g.
+    /// `Mitigation: PTI`.
+    pub status: Option<String>,
+}
+
+impl Vulnerability {
+    pub fn state(&self) -> MitigationState {
+        MitigationState::of(self.status.as_deref())
+    }
+}
+
+impl MitigationState {
+    fn of(status: Option<&str>) -> Self {
+        match status {
+            Some("Not affected") => MitigationState::NotAffected,
+            Some(s) if s.starts_with("Mitigation") => MitigationState::Mitigated,
+            Some(s) if s.starts_with("Vulnerable") || s.starts_with("Processor vulnerable") => {
+                MitigationState::Vulnerable
+            }
+            _ => MitigationState::Unknown,
+        }
+    }
+}
+
+/// One entry of a [`MitigationReport`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Mitigation {
+    /// The file in `devices/system/cpu/vulnerabilities`, or the bug's name
+    /// for listed bugs the kernel has no file for.
+    pub name: String,
+    /// Bugs from the `bugs` lines this entry reports on. Empty for issues
+    /// the kernel checked but did not list.
+    pub bugs: Vec<CpuBug>,
+    pub status: Option<String>,
+    pub state: MitigationState,
+}
+
+/// Everything the kernel knows about cpu vulnerabilities on one host: each
+/// file in `devices/system/cpu/vulnerabilities`, plus listed bugs without
+/// one, classified by [`MitigationState`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MitigationReport {
+    /// Entries with a file come first, sorted by name.
+    pub entries: Vec<Mitigation>,
+}
+
+impl MitigationReport {
+    pub fn get(&self, name: &str) -> Option<&Mitigation> {
+        self.entries.iter().find(|entry| entry.name == name)
+    }
+
+    pub fn with_state(&self, state: MitigationState) -> impl Iterator<Item = &Mitigation> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.state == state)
+    }
+
+    /// Whether no entry is known to be vulnerable.
+    pub fn is_mitigated(&self) -> bool {
+        self.with_state(MitigationState::Vulnerable)
+            .next()
+            .is_none()
+    }
+
+    #[cfg(feature = "json")]
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Vulnerabilities {
+    pub entries: Vec<Vulnerability>,
+}
+
+impl Vulnerabilities {
+    pub fn get(&self, bug: &CpuBug) -> Option<&Vulnerability> {
+        self.entries.iter().find(|entry| entry.bug == *bug)
+    }
+
+    pub fn vulnerable(&self) -> impl Iterator<Item = &Vulnerability> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.state() == MitigationState::Vulnerable)
+    }
+}
+
+impl CpuBug {
+    /// The file in `devices/system/cpu/vulnerabilities` reporting on this
+    /// bug. Several bugs share a file, and errata have none.
+    pub fn vulnerability_file(&self) -> Option<&'static str> {
+        Some(match self {
+            Self::Meltdown => "meltdown",
+            Self::SpectreV1 | Self::Swapgs => "spectre_v1",
+            Self::SpectreV2
+            | Self::SpectreV2User
+            | Self::EibrsPbrsb
+            | Self::Bhi
+            | Self::IbpbNoRet => "spectre_v2",
+            Self::SpecStoreBypass => "spec_store_bypass",
+            Self::L1tf => "l1tf",
+            Self::Mds | Self::MsbdsOnly => "mds",
+            Self::Taa => "tsx_async_abort",
+            Self::ItlbMultihit => "itlb_multihit",
+            Self::Srbds => "srbds",
+            Self::MmioStaleData | Self::MmioUnknown => "mmio_stale_data",
+            Self::Retbleed => "retbleed",
+            Self::Gds => "gather_data_sampling",
+            Self::Srso => "spec_rstack_overflow",
+            Self::Rfds => "reg_file_data_sampling",
+            Self::Its | Self::ItsNativeOnly => "indirect_target_selection",
+            Self::Tsa => "tsa",
+            Self::OldMicrocode => "old_microcode",
+            Self::Vmscape => "vmscape",
+            _ => return None,
+        })
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Pairs every bug listed by any cpu with the kernel's mitigation status.
+    pub fn vulnerabilities(&self, sysfs: &Sysfs) -> Vulnerabilities {
+        let mut entries: Vec<Vulnerability> = Vec::new();
+        for bug in self.cpus.iter().flat_map(|cpu| cpu.typed_bugs()) {
+            if entries.iter().any(|entry| entry.bug == bug) {
+                continue;
+            }
+            let status = bug
+                .vulnerability_file()
+                .and_then(|file| sysfs.read(Path::new(VULNERABILITIES).join(file)).ok());
+            entries.push(Vulnerability { bug, status });
+        }
+        Vulnerabilities { entries }
+    }
+
+    /// Combines the `bugs` lines with every vulnerability file sysfs has.
+    /// Fails only if the directory exists but cannot be listed.
+    pub fn mitigation_report(&self, sysfs: &Sysfs) -> Result<MitigationReport> {
+        let dir = sysfs.root().join(VULNERABILITIES);
+        let mut names = Vec::new();
+        match fs::read_dir(&dir) {
+            Ok(entries) => {
+                for entry in entries {
+                    names.push(entry?.file_name().to_string_lossy().into_owned());
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        names.sort();
+
+        let listed = self.vulnerabilities(sysfs);
+        let mut entries: Vec<Mitigation> = names
+            .into_iter()
+            .map(|name| {
+                let status = sysfs.read(Path::new(VULNERABILITIES).join(&name)).ok();
+                Mitigation {
+                    bugs: listed
+                        .entries
+                        .iter()
+                        .filter(|entry| entry.bug.vulnerability_file() == Some(name.as_str()))
+                        .map(|entry| entry.bug.clone())
+                        .collect(),
+                    state: MitigationState::of(status.as_deref()),
+                    name,
+                    status,
+                }
+            })
+            .collect();
+        for entry in listed.entries {
+            if entry
+                .bug
+                .vulnerability_file()
+                .is_some_and(|file| entries.iter().any(|mitigation| mitigation.name == file))
+            {
+                continue;
+            }
+            entries.push(Mitigation {
+                name: entry.bug.to_string(),
+                state: entry.state(),
+                bugs: vec![entry.bug],
+                status: entry.status,
+            });
+        }
+        Ok(MitigationReport { entries })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        cpuinfo,
+        fixtures::{FakeSysfs, EPYC},
+    };
+
+    #[test]
+    fn pairs_bugs_with_status() {
+        let fake = FakeSysfs::new(
+            "vulnerabilities",
+            &[
+                (
+                    "devices/system/cpu/vulnerabilities/spectre_v1",
+                    "Mitigation: usercopy/swapgs barriers and __user pointer sanitization\n",
+                ),
+                (
+                    "devices/system/cpu/vulnerabilities/spectre_v2",
+                    "Mitigation: Retpolines; IBPB: conditional; STIBP: always-on; RSB filling\n",
+                ),
+                (
+                    "devices/system/cpu/vulnerabilities/spec_store_bypass",
+                    "Vulnerable\n",
+                ),
+                (
+                    "devices/system/cpu/vulnerabilities/meltdown",
+                    "Not affected\n",
+                ),
+            ],
+        );
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let report = cpuinfo.vulnerabilities(&Sysfs::new(&fake.root));
+
+        assert_eq!(report.entries.len(), 6);
+        assert_eq!(
+            report.get(&CpuBug::SpectreV1).map(Vulnerability::state),
+            Some(MitigationState::Mitigated)
+        );
+        assert_eq!(
+            report
+                .vulnerable()
+                .map(|entry| &entry.bug)
+                .collect::<Vec<_>>(),
+            [&CpuBug::SpecStoreBypass]
+        );
+        assert_eq!(report.get(&CpuBug::NullSeg).unwrap().status, None);
+        assert_eq!(
+            report.get(&CpuBug::Srso).map(Vulnerability::state),
+            Some(MitigationState::Unknown)
+        );
+        assert!(report.get(&CpuBug::Meltdown).is_none());
+    }
+
+    #[test]
+    fn reports_every_mitigation() {
+        let fake = FakeSysfs::new(
+            "mitigations",
+            &[
+                (
+                    "devices/system/cpu/vulnerabilities/spectre_v2",
+                    "Mitigation: Retpolines\n",
+                ),
+                (
+                    "devices/system/cpu/vulnerabilities/spec_store_bypass",
+                    "Vulnerable\n",
+                ),
+                (
+                    "devices/system/cpu/vulnerabilities/meltdown",
+                    "Not affected\n",
+                ),
+            ],
+        );
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let report = cpuinfo.mitigation_report(&Sysfs::new(&fake.root)).unwrap();
+
+        let names: Vec<&str> = report.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            [
+                "meltdown",
+                "spec_store_bypass",
+                "spectre_v2",
+                "sysret_ss_attrs",
+                "null_seg",
+                "spectre_v1",
+                "srso",
+            ]
+        );
+        let meltdown = report.get("meltdown").unwrap();
+        assert_eq!(meltdown.state, MitigationState::NotAffected);
+        assert!(meltdown.bugs.is_empty());
+        assert_eq!(report.get("spectre_v2").unwrap().bugs, [CpuBug::SpectreV2]);
+        assert_eq!(report.get("srso").unwrap().state, MitigationState::Unknown);
+        assert!(!report.is_mitigated());
+
+        let empty = FakeSysfs::new("mitigations-none", &[]);
+        let report = cpuinfo.mitigation_report(&Sysfs::new(&empty.root)).unwrap();
+        assert_eq!(report.entries.len(), 6);
+        assert!(report.is_mitigated());
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn emits_mitigations_as_json() {
+        let fake = FakeSysfs::new(
+            "mitigations-json",
+            &[(
+                "devices/system/cpu/vulnerabilities/spectre_v1",
+                "Mitigation: usercopy/swapgs barriers\n",
+            )],
+        );
+        let cpuinfo = cpuinfo(EPYC).unwrap();
+        let json = cpuinfo
+            .mitigation_report(&Sysfs::new(&fake.root))
+            .unwrap()
+            .to_json()
+            .unwrap();
+        assert!(json.starts_with(
+            r#"{"entries":[{"name":"spectre_v1","bugs":["spectre_v1"],"status":"Mitigation: usercopy/swapgs barriers","state":"Mitigated"}"#
+        ));
+        let report: MitigationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(report.entries[1].bugs, [CpuBug::SysretSsAttrs]);
+    }
+}
@@ -0,0 +1,58 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{cpuinfo_guest, CpuInfoOwned};
+
+/// Parsed cpuinfo for JavaScript callers, e.g. a support portal showing text
+/// pasted by a user. Guest parsing is used since such text often comes from
+/// virtual machines.
+#[wasm_bindgen(js_name = CpuInfo)]
+pub struct WasmCpuInfo(CpuInfoOwned);
+
+#[wasm_bindgen(js_class = CpuInfo)]
+impl WasmCpuInfo {
+    #[wasm_bindgen(constructor)]
+    pub fn parse(input: &str) -> Result<WasmCpuInfo, JsError> {
+        Ok(Self(cpuinfo_guest(input)?.to_owned()))
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn length(&self) -> usize {
+        self.0.cpus.len()
+    }
+
+    pub fn summary(&self) -> String {
+        self.0.as_cpuinfo().summary()
+    }
+
+    /// The flags of the cpu at `index`, in the order the kernel printed them.
+    pub fn flags(&self, index: usize) -> Option<Vec<String>> {
+        self.0
+            .cpus
+            .get(index)
+            .map(|cpu| cpu.flags.iter().map(|flag| flag.to_string()).collect())
+    }
+
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<String, JsError> {
+        Ok(self.0.as_cpuinfo().to_json()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::EPYC;
+
+    #[test]
+    fn wraps_parsed_cpuinfo() {
+        let cpuinfo = WasmCpuInfo::parse(EPYC).unwrap_or_else(|_| unreachable!());
+        assert_eq!(cpuinfo.length(), 1);
+        assert_eq!(cpuinfo.summary(), "1 x AMD EPYC 7763 64-Core Processor");
+        assert_eq!(cpuinfo.flags(0).unwrap()[..2], ["fpu", "vme"]);
+        assert!(cpuinfo.flags(1).is_none());
+        assert!(cpuinfo
+            .to_json()
+            .unwrap_or_else(|_| unreachable!())
+            .starts_with(r#"{"cpus":[{"processor":3,"#));
+    }
+}
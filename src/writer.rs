@@ -0,0 +1,196 @@
+use alloc::{format, string::String};
+use core::fmt::{self, Write};
+
+use crate::{CacheSize, Cpu, CpuInfo, SizeUnit};
+
+fn list(out: &mut impl Write, label: &str, values: &[&str]) -> fmt::Result {
+    write!(out, "{}:", label)?;
+    for value in values {
+        write!(out, " {}", value)?;
+    }
+    writeln!(out)
+}
+
+fn page_size(size: u64) -> String {
+    match size {
+        s if s % (1 << 30) == 0 => format!("{}G", s >> 30),
+        s if s % (1 << 20) == 0 => format!("{}M", s >> 20),
+        s if s % (1 << 10) == 0 => format!("{}K", s >> 10),
+        s => format!("{}B", s),
+    }
+}
+
+/// In the unit it was parsed from, falling back to KB, and to bytes for
+/// sizes that are not a whole number of KiB.
+fn cache_size_text(size: CacheSize, unit: SizeUnit) -> String {
+    let (count, unit) = [unit, SizeUnit::Kilobytes]
+        .into_iter()
+        .find_map(|unit| Some((unit.count(size)?, unit)))
+        .unwrap_or((size.as_bytes(), SizeUnit::Bytes));
+    format!("{} {}", count, unit.symbol())
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value {
+        "yes"
+    } else {
+        "no"
+    }
+}
+
+impl Cpu<'_> {
+    /// Writes the block the way the kernel prints it, labels padded with
+    /// tabs and followed by a blank line.
+    pub fn write_proc<W: Write>(&self, out: &mut W) -> fmt::Result {
+        writeln!(out, "processor\t: {}", self.processor)?;
+        writeln!(out, "vendor_id\t: {}", self.vendor_id)?;
+        writeln!(out, "cpu family\t: {}", self.cpu_family)?;
+        writeln!(out, "model\t\t: {}", self.model)?;
+        writeln!(out, "model name\t: {}", self.model_name)?;
+        match self.stepping {
+            Some(stepping) => writeln!(out, "stepping\t: {}", stepping)?,
+            None => writeln!(out, "stepping\t: unknown")?,
+        }
+        if let Some(microcode) = self.microcode {
+            writeln!(out, "microcode\t: {:#x}", microcode)?;
+        }
+        writeln!(out, "cpu MHz\t\t: {:.3}", self.cpu_mhz.as_mhz())?;
+        if let Some(cache_size) = self.cache_size {
+            writeln!(
+                out,
+                "cache size\t: {}",
+                cache_size_text(cache_size, self.cache_size_unit)
+            )?;
+        }
+        if let Some(physical_id) = self.physical_id {
+            writeln!(out, "physical id\t: {}", physical_id)?;
+        }
+        writeln!(out, "siblings\t: {}", self.siblings)?;
+        writeln!(out, "core id\t\t: {}", self.core_id)?;
+        writeln!(out, "cpu cores\t: {}", self.cpu_cores)?;
+        writeln!(out, "apicid\t\t: {}", self.apicid)?;
+        writeln!(out, "initial apicid\t: {}", self.initial_apicid)?;
+        writeln!(out, "fpu\t\t: {}", yes_no(self.fpu))?;
+        writeln!(out, "fpu_exception\t: {}", yes_no(self.fpu_exception))?;
+        writeln!(out, "cpuid level\t: {}", self.cpuid_level)?;
+        writeln!(out, "wp\t\t: {}", yes_no(self.wp))?;
+        list(out, "flags\t\t", &self.flags)?;
+        if !self.vmx_flags.is_empty() {
+            list(out, "vmx flags\t", &self.vmx_flags)?;
+        }
+        if !self.svm_flags.is_empty() {
+            list(out, "svm flags\t", &self.svm_flags)?;
+        }
+        list(out, "bugs\t\t", &self.bugs)?;
+        writeln!(out, "bogomips\t: {:.2}", self.bogomips)?;
+        if let Some(tlb_size) = &self.tlb_size {
+            writeln!(
+                out,
+                "TLB size\t: {} {} pages",
+                tlb_size.entries,
+                page_size(tlb_size.page_size)
+            )?;
+        }
+        writeln!(out, "clflush size\t: {}", self.clflush_size)?;
+        writeln!(out, "cache_alignment\t: {}", self.cache_alignment)?;
+        writeln!(
+            out,
+            "address sizes\t: {} bits physical, {} bits virtual",
+            self.address_sizes.physical_size, self.address_sizes.virtual_size
+        )?;
+        list(out, "power management", &self.power_management)?;
+        writeln!(out)
+    }
+}
+
+impl CpuInfo<'_> {
+    /// Writes the text `/proc/cpuinfo` would contain for these cpus.
+    pub fn write_proc<W: Write>(&self, out: &mut W) -> fmt::Result {
+        for cpu in &self.cpus {
+            cpu.write_proc(out)?;
+        }
+        Ok(())
+    }
+
+    pub fn to_proc_string(&self) -> String {
+        let mut out = String::new();
+        self.write_proc(&mut out)
+            .expect("writing to a String cannot fail");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        cpuinfo, cpuinfo_guest,
+        fixtures::{EPYC, SKYLAKE},
+        CacheSize, CpuBuilder, CpuInfoBuilder, TlbSize,
+    };
+    use alloc::format;
+
+    #[test]
+    fn round_trips_skylake() {
+        let input = format!(
+            "{}\n{}\n",
+            SKYLAKE,
+            SKYLAKE.replace("processor	: 0", "processor	: 1")
+        );
+        let output = cpuinfo(&input).unwrap().to_proc_string();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn round_trips_epyc() {
+        let input = format!("{}\n", EPYC);
+        let output = cpuinfo(&input).unwrap().to_proc_string();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn round_trips_guest() {
+        let input = format!(
+            "{}\n",
+            SKYLAKE
+                .replace("microcode	: 0xf0\n", "")
+                .replace("physical id	: 0\n", "")
+                .replace("stepping	: 3", "stepping	: unknown")
+        );
+        let output = cpuinfo_guest(&input).unwrap().to_proc_string();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn round_trips_other_units() {
+        for size in ["8 MB", "1 GB", "1536 B"] {
+            let input = format!("{}\n", SKYLAKE.replace("8192 KB", size));
+            let output = cpuinfo(&input).unwrap().to_proc_string();
+            assert_eq!(output, input);
+        }
+
+        let input = format!("{}\n", EPYC.replace("2560 4K pages", "2560 512B pages"));
+        let output = cpuinfo(&input).unwrap().to_proc_string();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn writes_sizes_below_a_kib() {
+        let built = CpuInfoBuilder::new()
+            .cpu(
+                CpuBuilder::new()
+                    .cache_size(Some(CacheSize::from_bytes(1536)))
+                    .tlb_size(Some(TlbSize {
+                        entries: 64,
+                        page_size: 512,
+                    }))
+                    .build(),
+            )
+            .build();
+        let text = built.to_proc_string();
+        assert!(text.contains("cache size\t: 1536 B\n"));
+        assert!(text.contains("TLB size\t: 64 512B pages\n"));
+        let parsed = cpuinfo_guest(&text).unwrap();
+        assert_eq!(parsed.cpus[0].cache_size, built.cpus[0].cache_size);
+        assert_eq!(parsed.cpus[0].tlb_size, built.cpus[0].tlb_size);
+    }
+}
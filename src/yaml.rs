@@ -0,0 +1,21 @@
+use crate::{CpuInfo, Result};
+
+/// Uses the same field names and units as the JSON output.
+impl CpuInfo<'_> {
+    pub fn to_yaml(&self) -> Result<String> {
+        Ok(serde_yaml::to_string(self)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cpuinfo, fixtures::SKYLAKE};
+
+    #[test]
+    fn emits_yaml() {
+        let yaml = cpuinfo(SKYLAKE).unwrap().to_yaml().unwrap();
+        assert!(yaml.starts_with("cpus:\n- processor: 0\n  vendor_id: GenuineIntel\n"));
+        assert!(yaml.contains("\n  cache_size: 8388608\n"));
+        assert!(yaml.contains("\n  address_sizes:\n    physical_size: 39\n    virtual_size: 48\n"));
+    }
+}